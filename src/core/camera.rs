@@ -1,14 +1,15 @@
-use crate::core::config::{get_config, update_config};
+use crate::core::config::get_config;
 use crate::core::object::Object;
-use crate::geometry::{mesh::Mesh, triangle::Triangle};
-use glam::{Mat4, Vec3, Vec4};
+use crate::geometry::{frustum::Frustum, mesh::Mesh, ray::Ray, triangle::Triangle};
+use glam::{Mat4, Vec2, Vec3, Vec4};
 use std::f32::consts::PI;
 
 #[derive(Clone)]
 pub struct Camera {
     position: Vec3,
-    near: f32, // distance from camera to near plane (1.0)
-    far: f32,  // distance from camera to far plane (10.0)
+    near: f32,           // distance from camera to near plane (1.0)
+    far: f32,            // distance from camera to far plane (10.0)
+    aspect: Option<f32>, // width over height of the image, or the window's when None
 }
 
 impl Camera {
@@ -17,26 +18,44 @@ impl Camera {
             position: Vec3::ZERO,
             near: 1.0, // Standard near plane at 1.0
             far: 10.0, // Standard far plane at 10.0
+            aspect: None,
         };
 
         camera
     }
 
-    pub fn project_point(&self, point: Vec3) -> Vec3 {
+    pub fn get_projection_matrix(&self) -> Mat4 {
         let config = get_config();
 
-        let a = config.width as f32 / config.height as f32;
+        let a = self.get_aspect();
         let f = 1.0 / (config.fov * 0.5 * PI / 180.0).tan();
         let q = self.far / (self.far - self.near);
 
-        let matrix = Mat4::from_cols(
+        Mat4::from_cols(
             Vec4::new(f / a, 0.0, 0.0, 0.0),
             Vec4::new(0.0, f, 0.0, 0.0),
             Vec4::new(0.0, 0.0, q, 1.0),
             Vec4::new(0.0, 0.0, -self.near * q, 0.0),
-        );
+        )
+    }
+
+    // World to clip space
+    pub fn get_view_projection_matrix(&self) -> Mat4 {
+        self.get_projection_matrix() * Mat4::from_translation(-self.position)
+    }
 
-        let clip = matrix * point.extend(1.0);
+    pub fn get_frustum(&self) -> Frustum {
+        Frustum::from_matrix(self.get_view_projection_matrix())
+    }
+
+    // Clip-space position before the perspective divide. The GPU needs w to interpolate
+    // attributes perspective-correctly and to clip against the near plane.
+    pub fn project_point_clip(&self, point: Vec3) -> Vec4 {
+        self.get_projection_matrix() * (point - self.position).extend(1.0)
+    }
+
+    pub fn project_point(&self, point: Vec3) -> Vec3 {
+        let clip = self.project_point_clip(point);
         let projected_point = clip.truncate() / clip.w; // perspective divide
 
         projected_point
@@ -66,6 +85,82 @@ impl Camera {
     //     self.project_mesh(mesh)
     // }
 
+    // The camera always looks down +z
+    pub fn get_forward(&self) -> Vec3 {
+        Vec3::Z
+    }
+
+    pub fn get_near(&self) -> f32 {
+        self.near
+    }
+
+    pub fn get_far(&self) -> f32 {
+        self.far
+    }
+
+    // World-space corners of the slice of the view frustum between the `near` and `far`
+    // distances: the four near corners first, then the four far corners
+    pub fn get_frustum_corners(&self, near: f32, far: f32) -> [Vec3; 8] {
+        let config = get_config();
+
+        let a = self.get_aspect();
+        let tan_half_fov = (config.fov * 0.5 * PI / 180.0).tan();
+
+        let mut corners = [Vec3::ZERO; 8];
+        for (i, depth) in [near, far].into_iter().enumerate() {
+            let half_height = depth * tan_half_fov;
+            let half_width = half_height * a;
+            corners[i * 4] = self.position + Vec3::new(-half_width, -half_height, depth);
+            corners[i * 4 + 1] = self.position + Vec3::new(half_width, -half_height, depth);
+            corners[i * 4 + 2] = self.position + Vec3::new(half_width, half_height, depth);
+            corners[i * 4 + 3] = self.position + Vec3::new(-half_width, half_height, depth);
+        }
+        corners
+    }
+
+    // Ray through a point in normalized device coordinates (x right, y up, both -1 to 1),
+    // starting on the near plane like the rasterized image does
+    pub fn ndc_to_ray(&self, ndc: Vec2) -> Ray {
+        let config = get_config();
+
+        let a = self.get_aspect();
+        let tan_half_fov = (config.fov * 0.5 * PI / 180.0).tan();
+
+        let direction = Vec3::new(ndc.x * tan_half_fov * a, ndc.y * tan_half_fov, 1.0);
+        Ray::new(self.position + direction * self.near, direction)
+    }
+
+    // Ray through a point on the screen in pixels from the top-left corner, as given by mouse
+    // events, for the image size the renderer was configured with
+    pub fn screen_point_to_ray(&self, x: f32, y: f32) -> Ray {
+        let config = get_config();
+        let ndc = Vec2::new(
+            x / config.width as f32 * 2.0 - 1.0,
+            1.0 - y / config.height as f32 * 2.0,
+        );
+        self.ndc_to_ray(ndc)
+    }
+
+    // Width over height of the image being rendered. Renderers drawing into an image of their
+    // own size set this on a copy of the camera; without it the camera uses the window's size.
+    pub fn get_aspect(&self) -> f32 {
+        self.aspect.unwrap_or_else(|| {
+            let config = get_config();
+            config.width as f32 / config.height as f32
+        })
+    }
+
+    pub fn set_aspect(&mut self, aspect: Option<f32>) {
+        self.aspect = aspect;
+    }
+
+    // Copy of the camera projecting for an image of `width` by `height`
+    pub fn with_image_size(&self, width: u32, height: u32) -> Camera {
+        let mut camera = self.clone();
+        camera.set_aspect(Some(width as f32 / height as f32));
+        camera
+    }
+
     pub fn get_position(&self) -> Vec3 {
         self.position
     }
@@ -84,3 +179,9 @@ impl Camera {
     //     Triangle::new(projected_vertices)
     // }
 }
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,202 @@
+use crate::core::light::{Light, LightType};
+use glam::{Mat4, Vec3};
+use std::f32::consts::PI;
+
+// Segments used to approximate each circle of a sphere
+const CIRCLE_SEGMENTS: usize = 24;
+
+struct DebugLine {
+    start: Vec3,
+    end: Vec3,
+    color: Vec3,
+    remaining: Option<f32>, // seconds left, None for a single frame
+}
+
+// Immediate-mode debug lines in world space. Every scene has its own, reached through
+// Scene::get_debug_draw_mut, and every object has one for its update callbacks to draw
+// into, which the scene takes the lines from at the end of each update. Every call takes an optional lifetime in seconds; without one
+// the shape is drawn for the current frame only. The renderers draw all of the scene's lines
+// as one batch of depth-tested lines on top of it.
+pub struct DebugDraw {
+    lines: Vec<DebugLine>,
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        Self { lines: Vec::new() }
+    }
+
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Vec3, duration: Option<f32>) {
+        self.lines.push(DebugLine {
+            start,
+            end,
+            color,
+            remaining: duration,
+        });
+    }
+
+    // Axis-aligned box between two corners
+    pub fn aabb(&mut self, min: Vec3, max: Vec3, color: Vec3, duration: Option<f32>) {
+        let corner = |x: bool, y: bool, z: bool| {
+            Vec3::new(
+                if x { max.x } else { min.x },
+                if y { max.y } else { min.y },
+                if z { max.z } else { min.z },
+            )
+        };
+        for a in [false, true] {
+            for b in [false, true] {
+                self.line(corner(false, a, b), corner(true, a, b), color, duration);
+                self.line(corner(a, false, b), corner(a, true, b), color, duration);
+                self.line(corner(a, b, false), corner(a, b, true), color, duration);
+            }
+        }
+    }
+
+    // One circle around each axis
+    pub fn sphere(&mut self, center: Vec3, radius: f32, color: Vec3, duration: Option<f32>) {
+        self.circle(center, Vec3::X, Vec3::Y, radius, color, duration);
+        self.circle(center, Vec3::Y, Vec3::Z, radius, color, duration);
+        self.circle(center, Vec3::Z, Vec3::X, radius, color, duration);
+    }
+
+    // Line with a four-pronged head at `end`
+    pub fn arrow(&mut self, start: Vec3, end: Vec3, color: Vec3, duration: Option<f32>) {
+        self.line(start, end, color, duration);
+        let length = (end - start).length();
+        if length <= f32::EPSILON {
+            return;
+        }
+        let direction = (end - start) / length;
+        let (side, up) = direction.any_orthonormal_pair();
+        let head = length.min(1.0) * 0.2;
+        let base = end - direction * head;
+        for offset in [side, -side, up, -up] {
+            self.line(end, base + offset * head * 0.5, color, duration);
+        }
+    }
+
+    // The x, y and z axes of a transform in red, green and blue, `size` long before scaling
+    pub fn axes(&mut self, transform: Mat4, size: f32, duration: Option<f32>) {
+        let origin = transform.transform_point3(Vec3::ZERO);
+        let axes = [
+            (Vec3::X, Vec3::new(1.0, 0.0, 0.0)),
+            (Vec3::Y, Vec3::new(0.0, 1.0, 0.0)),
+            (Vec3::Z, Vec3::new(0.0, 0.0, 1.0)),
+        ];
+        for (axis, color) in axes {
+            self.line(
+                origin,
+                transform.transform_point3(axis * size),
+                color,
+                duration,
+            );
+        }
+    }
+
+    // Square grid on the plane y = center.y, `size` wide with `divisions` cells per side
+    pub fn grid(
+        &mut self,
+        center: Vec3,
+        size: f32,
+        divisions: u32,
+        color: Vec3,
+        duration: Option<f32>,
+    ) {
+        let divisions = divisions.max(1);
+        let half = size * 0.5;
+        for i in 0..=divisions {
+            let offset = -half + size * i as f32 / divisions as f32;
+            self.line(
+                center + Vec3::new(offset, 0.0, -half),
+                center + Vec3::new(offset, 0.0, half),
+                color,
+                duration,
+            );
+            self.line(
+                center + Vec3::new(-half, 0.0, offset),
+                center + Vec3::new(half, 0.0, offset),
+                color,
+                duration,
+            );
+        }
+    }
+
+    // Gizmo in the light's color: an arrow along a directional light's direction from its
+    // position, or a small star with a circle at a point light's position
+    pub fn light(&mut self, light: &dyn Light, duration: Option<f32>) {
+        let position = *light.get_position();
+        let color = light.get_color();
+        match light.get_light_type() {
+            LightType::Directional => {
+                self.arrow(position, position + light.get_direction(), color, duration)
+            }
+            LightType::Point => {
+                for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                    self.line(
+                        position - axis * 0.1,
+                        position + axis * 0.1,
+                        color,
+                        duration,
+                    );
+                }
+                self.sphere(position, 0.15, color, duration);
+            }
+        }
+    }
+
+    // Moves every line out of `other`, with what is left of its lifetime
+    pub fn append(&mut self, other: &mut DebugDraw) {
+        self.lines.append(&mut other.lines);
+    }
+
+    // Drops every line, including those that haven't expired yet
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    // Ages the lines by a frame, at the start of the frame before any new ones are added.
+    // Single-frame lines and lines whose lifetime ran out are removed. Scene::update calls
+    // this every frame; call it yourself when rendering frames without updating the scene.
+    pub fn advance(&mut self, delta_time: f32) {
+        self.lines.retain_mut(|line| match &mut line.remaining {
+            Some(remaining) => {
+                *remaining -= delta_time;
+                *remaining > 0.0
+            }
+            None => false,
+        });
+    }
+
+    // Start, end and color of every line to draw this frame
+    pub fn get_lines(&self) -> Vec<(Vec3, Vec3, Vec3)> {
+        self.lines
+            .iter()
+            .map(|line| (line.start, line.end, line.color))
+            .collect()
+    }
+
+    fn circle(
+        &mut self,
+        center: Vec3,
+        u: Vec3,
+        v: Vec3,
+        radius: f32,
+        color: Vec3,
+        duration: Option<f32>,
+    ) {
+        let point = |i: usize| {
+            let angle = 2.0 * PI * i as f32 / CIRCLE_SEGMENTS as f32;
+            center + (u * angle.cos() + v * angle.sin()) * radius
+        };
+        for i in 0..CIRCLE_SEGMENTS {
+            self.line(point(i), point(i + 1), color, duration);
+        }
+    }
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,143 @@
+use crate::core::camera::Camera;
+use crate::core::render_backend::RenderBackend;
+use crate::core::scene::Scene;
+use image::{Rgba, RgbaImage};
+use std::path::{Path, PathBuf};
+
+// Set to render and overwrite the reference images instead of comparing against them
+pub const UPDATE_ENV: &str = "UPDATE_GOLDEN";
+
+#[derive(Clone, Debug)]
+pub struct GoldenSettings {
+    pub tolerance: u8, // largest per-channel difference that still counts as a match
+    pub max_differing_percent: f32, // share of pixels allowed to differ by more than that
+    pub output_dir: PathBuf, // where the actual render and the diff go when a comparison fails
+}
+
+impl GoldenSettings {
+    pub fn new() -> Self {
+        Self {
+            tolerance: 2,
+            max_differing_percent: 0.1,
+            output_dir: PathBuf::from("target/golden"),
+        }
+    }
+}
+
+impl Default for GoldenSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct ImageDiff {
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    pub max_difference: u8,
+    pub image: RgbaImage, // differing pixels in red, the rest a faded copy of the reference
+}
+
+impl ImageDiff {
+    pub fn get_differing_percent(&self) -> f32 {
+        self.differing_pixels as f32 * 100.0 / self.total_pixels.max(1) as f32
+    }
+}
+
+// Compares two images of the same size channel by channel, alpha included
+pub fn compare_images(actual: &RgbaImage, reference: &RgbaImage, tolerance: u8) -> ImageDiff {
+    assert_eq!(
+        actual.dimensions(),
+        reference.dimensions(),
+        "images must have the same size"
+    );
+
+    let mut image = RgbaImage::new(actual.width(), actual.height());
+    let mut differing_pixels = 0;
+    let mut max_difference = 0;
+    for (x, y, expected) in reference.enumerate_pixels() {
+        let difference = actual
+            .get_pixel(x, y)
+            .0
+            .iter()
+            .zip(expected.0)
+            .map(|(a, b)| a.abs_diff(b))
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+
+        let pixel = if difference > tolerance {
+            differing_pixels += 1;
+            Rgba([128 + difference / 2, 0, 0, 255])
+        } else {
+            let luma = (expected[0] as u32 + expected[1] as u32 + expected[2] as u32) / 3;
+            let faded = (luma / 4) as u8;
+            Rgba([faded, faded, faded, 255])
+        };
+        image.put_pixel(x, y, pixel);
+    }
+
+    ImageDiff {
+        differing_pixels,
+        total_pixels: (actual.width() * actual.height()) as usize,
+        max_difference,
+        image,
+    }
+}
+
+// Renders `scene` and compares it against the PNG at `reference`. Panics when they differ by
+// more than `settings` allow, after writing the render and a diff image to the output
+// directory. A missing reference is written from the render and fails the check so it gets
+// reviewed and checked in; with UPDATE_GOLDEN set, references are rewritten and always pass.
+pub fn assert_golden(
+    renderer: &mut dyn RenderBackend,
+    scene: &Scene,
+    camera: &Camera,
+    reference: impl AsRef<Path>,
+    settings: &GoldenSettings,
+) {
+    let reference = reference.as_ref();
+    let actual = renderer.render(scene, camera);
+
+    let update = std::env::var_os(UPDATE_ENV).is_some();
+    if update || !reference.exists() {
+        if let Some(parent) = reference.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        actual.save(reference).unwrap();
+        assert!(
+            update,
+            "no reference image at {}, wrote one from this render; check it and commit it",
+            reference.display()
+        );
+        return;
+    }
+
+    let expected = image::open(reference).unwrap().to_rgba8();
+    let name = reference.file_stem().unwrap().to_string_lossy();
+    if actual.dimensions() != expected.dimensions() {
+        panic!(
+            "{name}: rendered {:?} but the reference is {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        );
+    }
+
+    let diff = compare_images(&actual, &expected, settings.tolerance);
+    if diff.get_differing_percent() > settings.max_differing_percent {
+        std::fs::create_dir_all(&settings.output_dir).unwrap();
+        let actual_path = settings.output_dir.join(format!("{name}.actual.png"));
+        let diff_path = settings.output_dir.join(format!("{name}.diff.png"));
+        actual.save(&actual_path).unwrap();
+        diff.image.save(&diff_path).unwrap();
+        panic!(
+            "{name}: {:.3}% of pixels differ by more than {} (max difference {}), allowed {}%\n  \
+             actual: {}\n  diff: {}",
+            diff.get_differing_percent(),
+            settings.tolerance,
+            diff.max_difference,
+            settings.max_differing_percent,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
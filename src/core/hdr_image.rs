@@ -0,0 +1,135 @@
+use crate::core::texture::linear_to_srgb;
+use glam::Vec3;
+use image::RgbaImage;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// Linear floating-point RGB image, rows top to bottom
+#[derive(Clone)]
+pub struct HdrImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<Vec3>,
+}
+
+impl HdrImage {
+    pub fn new(width: u32, height: u32, pixels: Vec<Vec3>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            (width * height) as usize,
+            "image must hold width * height pixels"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_pixels(&self) -> &[Vec3] {
+        &self.pixels
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Vec3 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    // Clamps to 0-1 and encodes as 8-bit sRGB, like the rasterizers' framebuffers
+    pub fn to_rgba8(&self) -> RgbaImage {
+        let mut data = Vec::with_capacity(self.pixels.len() * 4);
+        for pixel in &self.pixels {
+            for channel in pixel.to_array() {
+                let encoded = linear_to_srgb(channel.clamp(0.0, 1.0));
+                data.push((encoded * 255.0).round() as u8);
+            }
+            data.push(255);
+        }
+        RgbaImage::from_raw(self.width, self.height, data).unwrap()
+    }
+
+    // Portable float map: a tiny header and little-endian RGB floats, bottom row first
+    pub fn save_pfm(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        write!(file, "PF\n{} {}\n-1.0\n", self.width, self.height)?;
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                for channel in self.get_pixel(x, y).to_array() {
+                    file.write_all(&channel.to_le_bytes())?;
+                }
+            }
+        }
+        file.flush()
+    }
+
+    // Single-part scanline OpenEXR with uncompressed 32-bit float R, G and B channels
+    pub fn save_exr(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut header = Vec::new();
+        header.extend_from_slice(&[0x76, 0x2f, 0x31, 0x01]); // magic number
+        header.extend_from_slice(&2u32.to_le_bytes()); // version 2, scanline image
+
+        // Channels are listed in alphabetical order, and stored in that order in each line
+        let mut channels = Vec::new();
+        for name in ["B", "G", "R"] {
+            channels.extend_from_slice(name.as_bytes());
+            channels.push(0);
+            channels.extend_from_slice(&2i32.to_le_bytes()); // FLOAT
+            channels.extend_from_slice(&[0, 0, 0, 0]); // pLinear and reserved bytes
+            channels.extend_from_slice(&1i32.to_le_bytes()); // x sampling
+            channels.extend_from_slice(&1i32.to_le_bytes()); // y sampling
+        }
+        channels.push(0);
+
+        let max_x = (self.width as i32 - 1).to_le_bytes();
+        let max_y = (self.height as i32 - 1).to_le_bytes();
+        let window = [0i32.to_le_bytes(), 0i32.to_le_bytes(), max_x, max_y].concat();
+
+        let mut attribute = |name: &str, kind: &str, value: &[u8]| {
+            header.extend_from_slice(name.as_bytes());
+            header.push(0);
+            header.extend_from_slice(kind.as_bytes());
+            header.push(0);
+            header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+            header.extend_from_slice(value);
+        };
+        attribute("channels", "chlist", &channels);
+        attribute("compression", "compression", &[0]); // none
+        attribute("dataWindow", "box2i", &window);
+        attribute("displayWindow", "box2i", &window);
+        attribute("lineOrder", "lineOrder", &[0]); // increasing y
+        attribute("pixelAspectRatio", "float", &1.0f32.to_le_bytes());
+        attribute("screenWindowCenter", "v2f", &[0u8; 8]);
+        attribute("screenWindowWidth", "float", &1.0f32.to_le_bytes());
+        header.push(0); // end of header
+
+        // One scanline per block, each block prefixed with its y and its byte size
+        let line_size = self.width as usize * 3 * 4;
+        let block_size = 8 + line_size;
+        let table_size = self.height as usize * 8;
+        let first_block = (header.len() + table_size) as u64;
+
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(&header)?;
+        for y in 0..self.height as u64 {
+            file.write_all(&(first_block + y * block_size as u64).to_le_bytes())?;
+        }
+        for y in 0..self.height {
+            file.write_all(&(y as i32).to_le_bytes())?;
+            file.write_all(&(line_size as i32).to_le_bytes())?;
+            for channel in [2, 1, 0] {
+                for x in 0..self.width {
+                    file.write_all(&self.get_pixel(x, y)[channel].to_le_bytes())?;
+                }
+            }
+        }
+        file.flush()
+    }
+}
@@ -0,0 +1,206 @@
+use crate::core::camera::Camera;
+use crate::core::render_backend::RenderBackend;
+use crate::core::render_stats::RenderStats;
+use crate::core::scene::Scene;
+use crate::core::scene_renderer::{RenderTarget, SceneRenderer, create_depth_view};
+use image::RgbaImage;
+
+const COLOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+// Renders into an offscreen texture instead of a window, for CI, servers and tests
+pub struct HeadlessRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    adapter_info: wgpu::AdapterInfo,
+    width: u32,
+    height: u32,
+    color_texture: wgpu::Texture,
+    color_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+    readback_buffer: wgpu::Buffer,
+    padded_bytes_per_row: u32,
+    scene_renderer: SceneRenderer,
+}
+
+impl HeadlessRenderer {
+    // Uses the default adapter when there is one and falls back to a software adapter
+    // (e.g. llvmpipe or WARP) on machines without a GPU
+    pub async fn new(width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::from_env_or_default());
+        let adapter = match instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+        {
+            Ok(adapter) => adapter,
+            Err(_) => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::default(),
+                    compatible_surface: None,
+                    force_fallback_adapter: true,
+                })
+                .await
+                .unwrap(),
+        };
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                // Software and GL adapters may not reach the default limits
+                required_limits: wgpu::Limits::downlevel_defaults()
+                    .using_resolution(adapter.limits()),
+                memory_hints: wgpu::MemoryHints::default(),
+                trace: wgpu::Trace::default(),
+            })
+            .await
+            .unwrap();
+
+        let color_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Headless Color Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: COLOR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let color_view = color_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = create_depth_view(&device, width, height);
+
+        // Rows copied out of a texture have to be aligned to COPY_BYTES_PER_ROW_ALIGNMENT
+        let padded_bytes_per_row = (4 * width).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Headless Readback Buffer"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let scene_renderer = SceneRenderer::new(&device, COLOR_FORMAT);
+
+        Self {
+            device,
+            queue,
+            adapter_info: adapter.get_info(),
+            width,
+            height,
+            color_texture,
+            color_view,
+            depth_view,
+            readback_buffer,
+            padded_bytes_per_row,
+            scene_renderer,
+        }
+    }
+
+    // Objects and triangles drawn by the last render
+    pub fn get_stats(&self) -> RenderStats {
+        self.scene_renderer.get_stats()
+    }
+
+    // Index of the object the last render drew at a pixel, from the top left
+    pub fn pick(&mut self, x: u32, y: u32) -> Option<usize> {
+        self.pick_rect((x, y), (x, y)).first().copied()
+    }
+
+    // Indices of every object the last render drew between two corners of a rectangle, in
+    // pixels from the top left, given in any order; both corners are inside it
+    pub fn pick_rect(&mut self, a: (u32, u32), b: (u32, u32)) -> Vec<usize> {
+        self.scene_renderer.pick(
+            &self.device,
+            &self.queue,
+            (self.width, self.height),
+            (
+                a.0.min(b.0)..a.0.max(b.0) + 1,
+                a.1.min(b.1)..a.1.max(b.1) + 1,
+            ),
+        )
+    }
+
+    // Name, backend and device type of the adapter that is rendering
+    pub fn get_adapter_info(&self) -> &wgpu::AdapterInfo {
+        &self.adapter_info
+    }
+}
+
+impl RenderBackend for HeadlessRenderer {
+    fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    fn render(&mut self, scene: &Scene, camera: &Camera) -> RgbaImage {
+        self.scene_renderer.render(
+            &self.device,
+            &self.queue,
+            scene,
+            camera,
+            RenderTarget {
+                color_view: &self.color_view,
+                depth_view: &self.depth_view,
+                size: (self.width, self.height),
+            },
+        );
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Headless Readback"),
+            });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.color_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_bytes_per_row),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = self.readback_buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        self.device.poll(wgpu::PollType::Wait).unwrap();
+
+        // Strip the row padding
+        let row_bytes = (4 * self.width) as usize;
+        let mut pixels = Vec::with_capacity(row_bytes * self.height as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(self.padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..row_bytes]);
+            }
+        }
+        self.readback_buffer.unmap();
+
+        RgbaImage::from_raw(self.width, self.height, pixels).unwrap()
+    }
+}
@@ -1,9 +1,11 @@
-use crate::core::light::light::{BaseLight, Light};
+use crate::core::light::light::{BaseLight, Light, LightType};
+use crate::core::light::shadow::ShadowSettings;
 use glam::Vec3;
 
 pub struct DirectionalLight {
     base: BaseLight,
     direction: Vec3,
+    shadow_settings: Option<ShadowSettings>,
 }
 
 impl DirectionalLight {
@@ -13,6 +15,7 @@ impl DirectionalLight {
         Self {
             base: BaseLight::new(),
             direction: normalized_direction,
+            shadow_settings: None,
         }
     }
 
@@ -32,6 +35,11 @@ impl DirectionalLight {
         self.direction
     }
 
+    // Pass None to stop the light from casting shadows
+    pub fn set_shadow_settings(&mut self, shadow_settings: Option<ShadowSettings>) {
+        self.shadow_settings = shadow_settings;
+    }
+
     pub fn set_position(&mut self, position: Vec3) {
         self.base.set_position(position);
     }
@@ -50,6 +58,10 @@ impl DirectionalLight {
 }
 
 impl Light for DirectionalLight {
+    fn get_light_type(&self) -> LightType {
+        LightType::Directional
+    }
+
     fn get_color(&self) -> Vec3 {
         self.base.get_color()
     }
@@ -69,4 +81,8 @@ impl Light for DirectionalLight {
     fn get_direction(&self) -> Vec3 {
         self.direction
     }
+
+    fn get_shadow_settings(&self) -> Option<&ShadowSettings> {
+        self.shadow_settings.as_ref()
+    }
 }
@@ -1,13 +1,41 @@
+use crate::core::light::shadow::ShadowSettings;
 use crate::core::object::Object;
 use crate::geometry::mesh::Mesh;
 use glam::Vec3;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightType {
+    Directional,
+    Point,
+}
+
+impl LightType {
+    // Matches the `LIGHT_*` constants in mesh.frag.wgsl
+    pub fn to_index(self) -> u32 {
+        match self {
+            LightType::Directional => 0,
+            LightType::Point => 1,
+        }
+    }
+}
+
 pub trait Light {
+    fn get_light_type(&self) -> LightType;
     fn get_color(&self) -> Vec3;
     fn get_intensity(&self) -> f32;
     fn get_position(&self) -> &Vec3;
     fn get_rotation(&self) -> &Vec3;
     fn get_direction(&self) -> Vec3;
+
+    // Distance at which a point light's contribution fades to zero
+    fn get_range(&self) -> f32 {
+        f32::INFINITY
+    }
+
+    // None when the light doesn't cast shadows
+    fn get_shadow_settings(&self) -> Option<&ShadowSettings> {
+        None
+    }
 }
 
 pub struct BaseLight {
@@ -59,7 +87,17 @@ impl BaseLight {
     }
 }
 
+impl Default for BaseLight {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Light for BaseLight {
+    fn get_light_type(&self) -> LightType {
+        LightType::Directional
+    }
+
     fn get_color(&self) -> Vec3 {
         self.color
     }
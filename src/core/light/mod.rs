@@ -1,5 +1,9 @@
 pub mod directional_light;
 pub mod light;
+pub mod point_light;
+pub mod shadow;
 
 pub use directional_light::DirectionalLight;
-pub use light::{BaseLight, Light};
+pub use light::{BaseLight, Light, LightType};
+pub use point_light::PointLight;
+pub use shadow::ShadowSettings;
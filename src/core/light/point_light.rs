@@ -0,0 +1,83 @@
+use crate::core::light::light::{BaseLight, Light, LightType};
+use crate::core::light::shadow::ShadowSettings;
+use glam::Vec3;
+
+pub struct PointLight {
+    base: BaseLight,
+    range: f32,
+    shadow_settings: Option<ShadowSettings>,
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, range: f32) -> Self {
+        let mut base = BaseLight::new();
+        base.set_position(position);
+
+        Self {
+            base,
+            range,
+            shadow_settings: None,
+        }
+    }
+
+    pub fn set_color(&mut self, color: Vec3) {
+        self.base.set_color(color);
+    }
+
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.base.set_intensity(intensity);
+    }
+
+    pub fn set_range(&mut self, range: f32) {
+        self.range = range;
+    }
+
+    // Pass None to stop the light from casting shadows. `resolution` is the size of each cube
+    // face; the cascade settings don't apply to point lights.
+    pub fn set_shadow_settings(&mut self, shadow_settings: Option<ShadowSettings>) {
+        self.shadow_settings = shadow_settings;
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.base.set_position(position);
+    }
+
+    pub fn get_position(&self) -> &Vec3 {
+        self.base.get_position()
+    }
+}
+
+impl Light for PointLight {
+    fn get_light_type(&self) -> LightType {
+        LightType::Point
+    }
+
+    fn get_color(&self) -> Vec3 {
+        self.base.get_color()
+    }
+
+    fn get_intensity(&self) -> f32 {
+        self.base.get_intensity()
+    }
+
+    fn get_position(&self) -> &Vec3 {
+        self.base.get_position()
+    }
+
+    fn get_rotation(&self) -> &Vec3 {
+        self.base.get_rotation()
+    }
+
+    // Point lights shine in every direction
+    fn get_direction(&self) -> Vec3 {
+        Vec3::ZERO
+    }
+
+    fn get_range(&self) -> f32 {
+        self.range
+    }
+
+    fn get_shadow_settings(&self) -> Option<&ShadowSettings> {
+        self.shadow_settings.as_ref()
+    }
+}
@@ -0,0 +1,140 @@
+use crate::core::camera::Camera;
+use glam::{Mat4, Vec3, Vec4Swizzles};
+
+// The shadow map array and the shader are sized for this many cascades
+pub const MAX_CASCADES: usize = 4;
+
+// Point lights beyond this many (closest to the camera first) are rendered unshadowed
+pub const MAX_SHADOWED_POINT_LIGHTS: usize = 4;
+
+// Near plane of the cube map projections used for point light shadows
+pub const POINT_SHADOW_NEAR: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowSettings {
+    pub resolution: u32,  // width and height of each cascade's depth map
+    pub depth_bias: f32,  // constant offset subtracted from the receiver's depth
+    pub normal_bias: f32, // receiver offset along its normal, in shadow map texels
+    pub pcf_radius: u32,  // 0 for a single comparison, 1 for 3x3 taps, 2 for 5x5, ...
+    pub cascade_count: u32,
+    pub split_lambda: f32, // 0 spaces cascades linearly, 1 logarithmically
+    pub max_distance: Option<f32>, // shadow distance, defaults to the camera's far plane
+}
+
+impl ShadowSettings {
+    pub fn new() -> Self {
+        Self {
+            resolution: 2048,
+            depth_bias: 0.002,
+            normal_bias: 1.5,
+            pcf_radius: 1,
+            cascade_count: 1,
+            split_lambda: 0.5,
+            max_distance: None,
+        }
+    }
+
+    pub fn cascaded(cascade_count: u32) -> Self {
+        Self {
+            cascade_count: cascade_count.clamp(1, MAX_CASCADES as u32),
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct ShadowCascade {
+    pub view_projection: Mat4,
+    pub far: f32,        // view depth where this cascade ends
+    pub texel_size: f32, // world-space size of one shadow map texel
+}
+
+// Splits the camera frustum into cascades and fits an orthographic light projection around
+// each slice. `casters` are the world-space positions of every shadow caster, so geometry
+// between the light and the frustum still lands in the depth range.
+pub fn compute_cascades(
+    camera: &Camera,
+    direction: Vec3,
+    settings: &ShadowSettings,
+    casters: &[[f32; 3]],
+) -> Vec<ShadowCascade> {
+    let near = camera.get_near();
+    let far = settings
+        .max_distance
+        .unwrap_or(camera.get_far())
+        .max(near + f32::EPSILON);
+    let count = settings.cascade_count.clamp(1, MAX_CASCADES as u32);
+
+    let direction = direction.normalize();
+    let up = if direction.y.abs() > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    };
+
+    let mut cascades = Vec::new();
+    let mut split_near = near;
+    for i in 1..=count {
+        // Practical split scheme: blend of uniform and logarithmic splits
+        let t = i as f32 / count as f32;
+        let uniform = near + (far - near) * t;
+        let logarithmic = near * (far / near).powf(t);
+        let split_far = uniform + (logarithmic - uniform) * settings.split_lambda;
+
+        let corners = camera.get_frustum_corners(split_near, split_far);
+        let center = corners.iter().copied().sum::<Vec3>() / corners.len() as f32;
+        // A bounding sphere keeps the projection size constant from frame to frame
+        let radius = corners
+            .iter()
+            .map(|corner| corner.distance(center))
+            .fold(0.0, f32::max)
+            .ceil();
+
+        let view = Mat4::look_at_lh(center - direction * radius, center, up);
+
+        // Pull the near plane back to the furthest caster towards the light
+        let caster_near = casters
+            .iter()
+            .map(|position| (view * Vec3::from_array(*position).extend(1.0)).z)
+            .fold(0.0, f32::min);
+        let projection =
+            Mat4::orthographic_lh(-radius, radius, -radius, radius, caster_near, radius * 2.0);
+
+        // Snap the projection to whole texels so shadows don't shimmer as the camera moves
+        let resolution = settings.resolution as f32;
+        let origin = (projection * view).w_axis.xy() * resolution * 0.5;
+        let offset = (origin.round() - origin) * 2.0 / resolution;
+        let mut snapped = projection;
+        snapped.w_axis.x += offset.x;
+        snapped.w_axis.y += offset.y;
+
+        cascades.push(ShadowCascade {
+            view_projection: snapped * view,
+            far: split_far,
+            texel_size: radius * 2.0 / resolution,
+        });
+        split_near = split_far;
+    }
+    cascades
+}
+
+// View-projection matrices for the six faces of a point light's shadow cube map, in the
+// +X, -X, +Y, -Y, +Z, -Z layer order and orientation the GPU samples cube maps with
+pub fn compute_cube_faces(position: Vec3, range: f32) -> [Mat4; 6] {
+    let projection =
+        Mat4::perspective_lh(std::f32::consts::FRAC_PI_2, 1.0, POINT_SHADOW_NEAR, range);
+    let faces = [
+        (Vec3::X, Vec3::Y),
+        (Vec3::NEG_X, Vec3::Y),
+        (Vec3::Y, Vec3::NEG_Z),
+        (Vec3::NEG_Y, Vec3::Z),
+        (Vec3::Z, Vec3::Y),
+        (Vec3::NEG_Z, Vec3::Y),
+    ];
+    faces.map(|(direction, up)| projection * Mat4::look_to_lh(position, direction, up))
+}
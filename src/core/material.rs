@@ -0,0 +1,199 @@
+use crate::core::texture::Texture;
+use glam::{Vec3, Vec4};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShadingModel {
+    Unlit,
+    BlinnPhong,
+    Pbr,
+}
+
+impl ShadingModel {
+    // Matches the `SHADING_*` constants in mesh.frag.wgsl
+    pub fn to_index(self) -> u32 {
+        match self {
+            ShadingModel::Unlit => 0,
+            ShadingModel::BlinnPhong => 1,
+            ShadingModel::Pbr => 2,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Material {
+    shading_model: ShadingModel,
+    base_color: Vec4,
+    emissive: Vec3,
+    metallic: f32,
+    roughness: f32,
+    specular: f32,
+    shininess: f32,
+    base_color_texture: Option<Arc<Texture>>,
+    emissive_texture: Option<Arc<Texture>>,
+    normal_texture: Option<Arc<Texture>>,
+    normal_scale: f32,
+}
+
+impl Material {
+    // White Blinn-Phong without a specular term, i.e. plain Lambert shading
+    pub fn new() -> Self {
+        Self {
+            shading_model: ShadingModel::BlinnPhong,
+            base_color: Vec4::ONE,
+            emissive: Vec3::ZERO,
+            metallic: 0.0,
+            roughness: 1.0,
+            specular: 0.0,
+            shininess: 32.0,
+            base_color_texture: None,
+            emissive_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+        }
+    }
+
+    pub fn unlit(color: Vec4) -> Self {
+        Self {
+            shading_model: ShadingModel::Unlit,
+            base_color: color,
+            ..Self::new()
+        }
+    }
+
+    pub fn blinn_phong(color: Vec4, specular: f32, shininess: f32) -> Self {
+        Self {
+            shading_model: ShadingModel::BlinnPhong,
+            base_color: color,
+            specular,
+            shininess,
+            ..Self::new()
+        }
+    }
+
+    pub fn pbr(base_color: Vec4, metallic: f32, roughness: f32) -> Self {
+        Self {
+            shading_model: ShadingModel::Pbr,
+            base_color,
+            metallic: metallic.clamp(0.0, 1.0),
+            roughness: roughness.clamp(0.0, 1.0),
+            ..Self::new()
+        }
+    }
+
+    // Maps glTF's pbrMetallicRoughness factors (plus emissiveFactor) one to one.
+    // The defaults in the glTF spec are base color 1.0, metallic 1.0, roughness 1.0
+    // and emissive 0.0. Textures are multiplied with these factors, as in glTF.
+    pub fn from_gltf(
+        base_color_factor: [f32; 4],
+        metallic_factor: f32,
+        roughness_factor: f32,
+        emissive_factor: [f32; 3],
+    ) -> Self {
+        let mut material = Self::pbr(
+            Vec4::from_array(base_color_factor),
+            metallic_factor,
+            roughness_factor,
+        );
+        material.set_emissive(Vec3::from_array(emissive_factor));
+        material
+    }
+
+    pub fn get_shading_model(&self) -> ShadingModel {
+        self.shading_model
+    }
+
+    pub fn set_shading_model(&mut self, shading_model: ShadingModel) {
+        self.shading_model = shading_model;
+    }
+
+    pub fn get_base_color(&self) -> Vec4 {
+        self.base_color
+    }
+
+    pub fn set_base_color(&mut self, base_color: Vec4) {
+        self.base_color = base_color;
+    }
+
+    pub fn get_emissive(&self) -> Vec3 {
+        self.emissive
+    }
+
+    pub fn set_emissive(&mut self, emissive: Vec3) {
+        self.emissive = emissive;
+    }
+
+    pub fn get_metallic(&self) -> f32 {
+        self.metallic
+    }
+
+    pub fn set_metallic(&mut self, metallic: f32) {
+        self.metallic = metallic.clamp(0.0, 1.0);
+    }
+
+    pub fn get_roughness(&self) -> f32 {
+        self.roughness
+    }
+
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.roughness = roughness.clamp(0.0, 1.0);
+    }
+
+    pub fn get_specular(&self) -> f32 {
+        self.specular
+    }
+
+    pub fn set_specular(&mut self, specular: f32) {
+        self.specular = specular;
+    }
+
+    pub fn get_shininess(&self) -> f32 {
+        self.shininess
+    }
+
+    pub fn set_shininess(&mut self, shininess: f32) {
+        self.shininess = shininess;
+    }
+
+    // sRGB encoded, multiplied with the base color
+    pub fn get_base_color_texture(&self) -> Option<&Arc<Texture>> {
+        self.base_color_texture.as_ref()
+    }
+
+    pub fn set_base_color_texture(&mut self, texture: Option<Arc<Texture>>) {
+        self.base_color_texture = texture;
+    }
+
+    // sRGB encoded, multiplied with the emissive color
+    pub fn get_emissive_texture(&self) -> Option<&Arc<Texture>> {
+        self.emissive_texture.as_ref()
+    }
+
+    pub fn set_emissive_texture(&mut self, texture: Option<Arc<Texture>>) {
+        self.emissive_texture = texture;
+    }
+
+    // Linear tangent-space normal map, +Y (green) up as in glTF
+    pub fn get_normal_texture(&self) -> Option<&Arc<Texture>> {
+        self.normal_texture.as_ref()
+    }
+
+    pub fn set_normal_texture(&mut self, texture: Option<Arc<Texture>>) {
+        self.normal_texture = texture;
+    }
+
+    // Scales the normal map's X and Y, like glTF's normalTexture.scale
+    pub fn get_normal_scale(&self) -> f32 {
+        self.normal_scale
+    }
+
+    pub fn set_normal_scale(&mut self, normal_scale: f32) {
+        self.normal_scale = normal_scale;
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,24 @@
 pub mod camera;
 mod config;
+pub mod debug_draw;
+pub mod golden;
+pub mod hdr_image;
+pub mod headless;
 pub mod light;
+mod line_pass;
+pub mod material;
 pub mod object;
+mod outline_pass;
+pub mod path_tracer;
+mod pick_pass;
+pub mod render_backend;
+pub mod render_mode;
+pub mod render_stats;
 pub mod renderer;
 pub mod scene;
+mod scene_renderer;
+mod shading;
+mod shadow_pass;
+pub mod software_renderer;
+pub mod texture;
+mod texture_cache;
@@ -1,24 +1,72 @@
+use crate::core::debug_draw::DebugDraw;
+use crate::core::material::Material;
+use crate::core::render_mode::RenderMode;
+use crate::geometry::bounds::{Aabb, BoundingSphere};
 use crate::geometry::mesh::Mesh;
+use crate::geometry::ray::Ray;
 use crate::geometry::triangle::Triangle;
-use glam::{Mat3, Vec3};
+use crate::physics::character_controller::CharacterController;
+use crate::physics::collider::Collider;
+use crate::physics::mass_properties::MassProperties;
+use crate::physics::rigid_body::RigidBody;
+use crate::physics::soft_body::SoftBody;
+use crate::physics::world::TriggerEvent;
+use glam::{Mat3, Mat4, Quat, Vec3};
+
+// Called with the object and the time to advance it by
+pub type Callback = Box<dyn FnMut(&mut Object, f32)>;
 
 pub struct Object {
     mesh: Mesh,
+    material: Material,
     position: Vec3,
     rotation: Vec3,
-    update: Option<Box<dyn FnMut(&mut Self, f32)>>,
+    cast_shadows: bool,
+    receive_shadows: bool,
+    render_mode: Option<RenderMode>, // None follows the scene's render mode
+    rigid_body: Option<RigidBody>,   // None leaves the object out of physics
+    collider: Option<Collider>,      // without a rigid body, the object is a static obstacle
+    character_controller: Option<CharacterController>, // moves the object instead of a rigid body
+    soft_body: Option<SoftBody>,     // deforms the mesh as cloth or a squishy solid
+    trigger_events: Vec<TriggerEvent>, // this object took part in during the last scene update
+    fixed_step_motion: (Vec3, Quat), // how the last fixed step moved and turned the object
+    debug_draw: DebugDraw, // drawn into by the callbacks, emptied into the scene's every update
+    update: Option<Callback>,
+    fixed_update: Option<Callback>,
 }
 
 impl Object {
     pub fn new(mesh: Mesh) -> Self {
         Object {
             mesh,
+            material: Material::new(),
             position: Vec3::ZERO,
             rotation: Vec3::ZERO,
+            cast_shadows: true,
+            receive_shadows: true,
+            render_mode: None,
+            rigid_body: None,
+            collider: None,
+            character_controller: None,
+            soft_body: None,
+            trigger_events: Vec::new(),
+            fixed_step_motion: (Vec3::ZERO, Quat::IDENTITY),
+            debug_draw: DebugDraw::new(),
             update: None,
+            fixed_update: None,
         }
     }
 
+    pub fn get_debug_draw(&self) -> &DebugDraw {
+        &self.debug_draw
+    }
+
+    // Lines drawn here, typically from the update callbacks, join the scene's debug lines at
+    // the end of the scene update, and then age like any of them
+    pub fn get_debug_draw_mut(&mut self) -> &mut DebugDraw {
+        &mut self.debug_draw
+    }
+
     pub fn set_update<F>(&mut self, f: F)
     where
         F: FnMut(&mut Self, f32) + 'static,
@@ -33,25 +81,135 @@ impl Object {
         }
     }
 
-    pub fn transformed_triangle(&self, triangle: Triangle) -> Triangle {
-        let vertices = triangle.get_vertices();
+    // Called before every fixed step of the scene, with the fixed timestep, for anything
+    // that should happen at the same rate whatever the frame rate
+    pub fn set_fixed_update<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut Self, f32) + 'static,
+    {
+        self.fixed_update = Some(Box::new(f));
+    }
+
+    pub fn fixed_update(&mut self, fixed_timestep: f32) {
+        if let Some(mut f) = self.fixed_update.take() {
+            f(self, fixed_timestep);
+            self.fixed_update = Some(f);
+        }
+    }
+
+    pub fn get_rotation_matrix(&self) -> Mat3 {
         let rotate_matrix_x = Mat3::from_rotation_x(self.rotation.x);
         let rotate_matrix_y = Mat3::from_rotation_y(self.rotation.y);
         let rotate_matrix_z = Mat3::from_rotation_z(self.rotation.z);
 
-        let rotation: Mat3 = rotate_matrix_z * rotate_matrix_y * rotate_matrix_x;
+        rotate_matrix_z * rotate_matrix_y * rotate_matrix_x
+    }
+
+    // Local to world space
+    pub fn get_transform(&self) -> Mat4 {
+        Mat4::from_translation(self.position) * Mat4::from_mat3(self.get_rotation_matrix())
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.get_rotation_matrix() * point + self.position
+    }
+
+    // The same ray in the mesh's local space; distances along it don't change
+    pub fn to_local_ray(&self, ray: &Ray) -> Ray {
+        let inverse_rotation = self.get_rotation_matrix().transpose();
+        Ray::new(
+            inverse_rotation * (ray.origin - self.position),
+            inverse_rotation * ray.direction,
+        )
+    }
 
-        crate::geometry::triangle::Triangle::new([
-            rotation * vertices[0] + self.position,
-            rotation * vertices[1] + self.position,
-            rotation * vertices[2] + self.position,
-        ])
+    // World-space box around the mesh in its current position and rotation
+    pub fn get_world_aabb(&self) -> Aabb {
+        self.mesh
+            .get_aabb()
+            .transformed(self.get_rotation_matrix(), self.position)
+    }
+
+    pub fn get_world_bounding_sphere(&self) -> BoundingSphere {
+        self.mesh
+            .get_bounding_sphere()
+            .transformed(self.get_rotation_matrix(), self.position)
+    }
+
+    // Position and rotation `alpha` of the way from before the last fixed step to after it,
+    // for drawing between steps. Whatever moved the object since that step moves this too.
+    pub fn get_interpolated_pose(&self, alpha: f32) -> (Vec3, Mat3) {
+        let (translation, turn) = self.fixed_step_motion;
+        let back = 1.0 - alpha.clamp(0.0, 1.0);
+        let rotation = self.get_rotation_matrix();
+        if turn == Quat::IDENTITY || back == 0.0 {
+            return (self.position - translation * back, rotation);
+        }
+        let rotation = Quat::IDENTITY.slerp(turn.inverse(), back) * Quat::from_mat3(&rotation);
+        (
+            self.position - translation * back,
+            Mat3::from_quat(rotation),
+        )
+    }
+
+    // World bounds at the interpolated pose, which the renderers cull against
+    pub fn get_interpolated_world_aabb(&self, alpha: f32) -> Aabb {
+        let (position, rotation) = self.get_interpolated_pose(alpha);
+        self.mesh.get_aabb().transformed(rotation, position)
+    }
+
+    pub fn get_interpolated_transform(&self, alpha: f32) -> Mat4 {
+        let (position, rotation) = self.get_interpolated_pose(alpha);
+        Mat4::from_translation(position) * Mat4::from_mat3(rotation)
+    }
+
+    pub fn transformed_triangle(&self, triangle: Triangle) -> Triangle {
+        transform_triangle(triangle, self.get_rotation_matrix(), self.position)
+    }
+
+    // In world space at the interpolated pose
+    pub fn interpolated_triangle(&self, triangle: Triangle, alpha: f32) -> Triangle {
+        let (position, rotation) = self.get_interpolated_pose(alpha);
+        transform_triangle(triangle, rotation, position)
+    }
+
+    // Where the rigid body's center of mass is in world space; the position without one
+    pub fn get_world_center_of_mass(&self) -> Vec3 {
+        let local_center = self
+            .rigid_body
+            .as_ref()
+            .map_or(Vec3::ZERO, RigidBody::get_local_center_of_mass);
+        self.transform_point(local_center)
+    }
+
+    // World-space force at a world-space point, which also turns the body unless it acts
+    // through the center of mass
+    pub fn apply_force_at_point(&mut self, force: Vec3, point: Vec3) {
+        let center = self.get_world_center_of_mass();
+        if let Some(body) = &mut self.rigid_body {
+            body.apply_force(force);
+            body.apply_torque((point - center).cross(force));
+        }
+    }
+
+    // World-space impulse at a world-space point
+    pub fn apply_impulse_at_point(&mut self, impulse: Vec3, point: Vec3) {
+        let center = self.get_world_center_of_mass();
+        let rotation = self.get_rotation_matrix();
+        if let Some(body) = &mut self.rigid_body {
+            body.apply_impulse(impulse);
+            body.apply_angular_impulse((point - center).cross(impulse), rotation);
+        }
     }
 
     pub fn get_mesh(&self) -> &Mesh {
         &self.mesh
     }
 
+    pub fn get_material(&self) -> &Material {
+        &self.material
+    }
+
     pub fn get_position(&self) -> &Vec3 {
         &self.position
     }
@@ -60,10 +218,61 @@ impl Object {
         &self.rotation
     }
 
+    pub fn get_cast_shadows(&self) -> bool {
+        self.cast_shadows
+    }
+
+    pub fn get_receive_shadows(&self) -> bool {
+        self.receive_shadows
+    }
+
+    pub fn get_render_mode(&self) -> Option<RenderMode> {
+        self.render_mode
+    }
+
+    pub fn get_rigid_body(&self) -> Option<&RigidBody> {
+        self.rigid_body.as_ref()
+    }
+
+    pub fn get_rigid_body_mut(&mut self) -> Option<&mut RigidBody> {
+        self.rigid_body.as_mut()
+    }
+
+    pub fn get_collider(&self) -> Option<&Collider> {
+        self.collider.as_ref()
+    }
+
+    pub fn get_character_controller(&self) -> Option<&CharacterController> {
+        self.character_controller.as_ref()
+    }
+
+    pub fn get_character_controller_mut(&mut self) -> Option<&mut CharacterController> {
+        self.character_controller.as_mut()
+    }
+
+    pub fn get_soft_body(&self) -> Option<&SoftBody> {
+        self.soft_body.as_ref()
+    }
+
+    pub fn get_soft_body_mut(&mut self) -> Option<&mut SoftBody> {
+        self.soft_body.as_mut()
+    }
+
+    // Triggers this object entered, stayed in or left, or things that did so with this
+    // object's trigger, during the last scene update's fixed steps, which run before the
+    // update callbacks
+    pub fn get_trigger_events(&self) -> &Vec<TriggerEvent> {
+        &self.trigger_events
+    }
+
     pub fn set_mesh(&mut self, mesh: Mesh) {
         self.mesh = mesh;
     }
 
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
     pub fn set_position(&mut self, position: Vec3) {
         self.position = position;
     }
@@ -71,4 +280,96 @@ impl Object {
     pub fn set_rotation(&mut self, rotation: Vec3) {
         self.rotation = rotation;
     }
+
+    pub fn set_cast_shadows(&mut self, cast_shadows: bool) {
+        self.cast_shadows = cast_shadows;
+    }
+
+    pub fn set_receive_shadows(&mut self, receive_shadows: bool) {
+        self.receive_shadows = receive_shadows;
+    }
+
+    pub fn set_render_mode(&mut self, render_mode: Option<RenderMode>) {
+        self.render_mode = render_mode;
+    }
+
+    // A body without explicitly set mass properties gets them from the collider, or the mesh
+    // without one, as a solid of its mass
+    pub fn set_rigid_body(&mut self, rigid_body: Option<RigidBody>) {
+        self.rigid_body = rigid_body;
+        if let Some(body) = &self.rigid_body
+            && (body.get_mass_properties().is_none() || body.has_derived_mass_properties())
+        {
+            self.derive_mass_properties();
+        }
+    }
+
+    // A rigid body takes its mass properties from the new collider, unless they were set
+    // explicitly with RigidBody::set_mass_properties
+    pub fn set_collider(&mut self, collider: Option<Collider>) {
+        self.collider = collider;
+        if let Some(body) = &self.rigid_body
+            && (body.get_mass_properties().is_none() || body.has_derived_mass_properties())
+        {
+            self.derive_mass_properties();
+        }
+    }
+
+    pub fn set_character_controller(&mut self, character_controller: Option<CharacterController>) {
+        self.character_controller = character_controller;
+    }
+
+    pub fn set_soft_body(&mut self, soft_body: Option<SoftBody>) {
+        self.soft_body = soft_body;
+    }
+
+    // Remembers how the fixed step just taken moved the object from where it was
+    pub(crate) fn set_fixed_step_motion(
+        &mut self,
+        previous_position: Vec3,
+        previous_rotation: Mat3,
+    ) {
+        let rotation = self.get_rotation_matrix();
+        let turn = if rotation == previous_rotation {
+            Quat::IDENTITY
+        } else {
+            Quat::from_mat3(&rotation) * Quat::from_mat3(&previous_rotation).inverse()
+        };
+        self.fixed_step_motion = (self.position - previous_position, turn);
+    }
+
+    pub(crate) fn set_trigger_events(&mut self, trigger_events: Vec<TriggerEvent>) {
+        self.trigger_events = trigger_events;
+    }
+
+    fn derive_mass_properties(&mut self) {
+        let Some(body) = &mut self.rigid_body else {
+            return;
+        };
+        let mass = body.get_mass();
+        body.set_derived_mass_properties(match &self.collider {
+            Some(collider) => collider.get_mass_properties(mass),
+            None => MassProperties::from_mesh(&self.mesh, mass),
+        });
+    }
+}
+
+fn transform_triangle(triangle: Triangle, rotation: Mat3, position: Vec3) -> Triangle {
+    let vertices = triangle.get_vertices();
+    let mut transformed = Triangle::with_uvs(
+        [
+            rotation * vertices[0] + position,
+            rotation * vertices[1] + position,
+            rotation * vertices[2] + position,
+        ],
+        triangle.get_uvs(),
+    );
+    if triangle.has_tangents() {
+        // Rotations keep the handedness in w intact
+        let tangents = triangle
+            .get_tangents()
+            .map(|tangent| (rotation * tangent.truncate()).extend(tangent.w));
+        transformed.set_tangents(Some(tangents));
+    }
+    transformed
 }
@@ -0,0 +1,488 @@
+use crate::core::camera::Camera;
+use crate::core::hdr_image::HdrImage;
+use crate::core::light::{Light, LightType};
+use crate::core::material::{Material, ShadingModel};
+use crate::core::render_backend::RenderBackend;
+use crate::core::scene::Scene;
+use crate::core::shading::{Surface, light_incidence, reflectance, shade};
+use crate::geometry::bounds::Aabb;
+use crate::geometry::bvh::Bvh;
+use crate::geometry::ray::Ray;
+use glam::{Vec2, Vec3, Vec4};
+use image::RgbaImage;
+use std::f32::consts::PI;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Offset along the normal for rays leaving a surface, so they don't hit it again
+const SURFACE_OFFSET: f32 = 1e-3;
+// Bounces that always continue before Russian roulette may end a path
+const MIN_BOUNCES: u32 = 3;
+
+// Reference renderer that traces paths through the scene on the CPU. It uses the same
+// materials and lights as the rasterizers, adds shadows from every light and indirect light
+// from diffuse and glossy bounces, and accumulates samples across calls so the image can be
+// refined progressively. Work is split into tiles shared between threads.
+pub struct PathTracer {
+    width: u32,
+    height: u32,
+    samples_per_pixel: u32, // samples taken by each render() call
+    max_bounces: u32,
+    tile_size: u32,
+    thread_count: usize,
+    accumulation: Vec<Vec3>, // sum of all samples taken so far, in linear radiance
+    sample_count: u32,
+}
+
+impl PathTracer {
+    pub fn new(width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+
+        Self {
+            width,
+            height,
+            samples_per_pixel: 64,
+            max_bounces: 4,
+            tile_size: 16,
+            thread_count: std::thread::available_parallelism().map_or(1, |count| count.get()),
+            accumulation: vec![Vec3::ZERO; (width * height) as usize],
+            sample_count: 0,
+        }
+    }
+
+    pub fn get_samples_per_pixel(&self) -> u32 {
+        self.samples_per_pixel
+    }
+
+    pub fn set_samples_per_pixel(&mut self, samples_per_pixel: u32) {
+        self.samples_per_pixel = samples_per_pixel.max(1);
+    }
+
+    pub fn get_max_bounces(&self) -> u32 {
+        self.max_bounces
+    }
+
+    // 0 gives direct lighting only
+    pub fn set_max_bounces(&mut self, max_bounces: u32) {
+        self.max_bounces = max_bounces;
+    }
+
+    pub fn get_tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    pub fn set_tile_size(&mut self, tile_size: u32) {
+        self.tile_size = tile_size.max(1);
+    }
+
+    pub fn get_thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    pub fn set_thread_count(&mut self, thread_count: usize) {
+        self.thread_count = thread_count.max(1);
+    }
+
+    // Samples per pixel accumulated since the last reset
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    // Drops the accumulated samples; needed whenever the scene or camera changes
+    pub fn reset(&mut self) {
+        self.accumulation.fill(Vec3::ZERO);
+        self.sample_count = 0;
+    }
+
+    // Traces `samples` more paths through every pixel and adds them to the accumulation
+    pub fn accumulate(&mut self, scene: &Scene, camera: &Camera, samples: u32) {
+        let camera = &camera.with_image_size(self.width, self.height);
+        let world = World::new(scene);
+        let tiles_x = self.width.div_ceil(self.tile_size);
+        let tiles_y = self.height.div_ceil(self.tile_size);
+        let tile_count = (tiles_x * tiles_y) as usize;
+        let next_tile = AtomicUsize::new(0);
+
+        let finished_tiles: Vec<(usize, Vec<Vec3>)> = std::thread::scope(|threads| {
+            let workers: Vec<_> = (0..self.thread_count.min(tile_count))
+                .map(|_| {
+                    threads.spawn(|| {
+                        let mut finished = Vec::new();
+                        loop {
+                            let tile = next_tile.fetch_add(1, Ordering::Relaxed);
+                            if tile >= tile_count {
+                                break;
+                            }
+                            let x = (tile as u32 % tiles_x) * self.tile_size;
+                            let y = (tile as u32 / tiles_x) * self.tile_size;
+                            finished.push((tile, self.render_tile(&world, camera, x, y, samples)));
+                        }
+                        finished
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        for (tile, radiance) in finished_tiles {
+            let x_start = (tile as u32 % tiles_x) * self.tile_size;
+            let y_start = (tile as u32 / tiles_x) * self.tile_size;
+            let x_end = (x_start + self.tile_size).min(self.width);
+            let y_end = (y_start + self.tile_size).min(self.height);
+            let mut radiance = radiance.into_iter();
+            for y in y_start..y_end {
+                for x in x_start..x_end {
+                    self.accumulation[(y * self.width + x) as usize] += radiance.next().unwrap();
+                }
+            }
+        }
+        self.sample_count += samples;
+    }
+
+    // Average linear radiance per pixel, for saving as PFM or EXR
+    pub fn get_image(&self) -> HdrImage {
+        let scale = 1.0 / self.sample_count.max(1) as f32;
+        let pixels = self.accumulation.iter().map(|sum| *sum * scale).collect();
+        HdrImage::new(self.width, self.height, pixels)
+    }
+
+    // Sum of `samples` paths for every pixel of one tile, row by row
+    fn render_tile(
+        &self,
+        world: &World,
+        camera: &Camera,
+        x: u32,
+        y: u32,
+        samples: u32,
+    ) -> Vec<Vec3> {
+        let x_end = (x + self.tile_size).min(self.width);
+        let y_end = (y + self.tile_size).min(self.height);
+        let size = Vec2::new(self.width as f32, self.height as f32);
+        // Primary rays stop at the far plane, like the rasterized image
+        let far_depth = camera.get_far() - camera.get_near();
+
+        let mut radiance = Vec::with_capacity(((x_end - x) * (y_end - y)) as usize);
+        for pixel_y in y..y_end {
+            for pixel_x in x..x_end {
+                let mut sum = Vec3::ZERO;
+                for sample in 0..samples {
+                    // Seeded by pixel and sample, so the result doesn't depend on the threads
+                    let mut random = Random::new(
+                        (pixel_y * self.width + pixel_x) as u64,
+                        (self.sample_count + sample) as u64,
+                    );
+                    let jitter = Vec2::new(random.next_f32(), random.next_f32());
+                    let screen = Vec2::new(pixel_x as f32, pixel_y as f32) + jitter;
+                    let ndc =
+                        Vec2::new(screen.x / size.x * 2.0 - 1.0, 1.0 - screen.y / size.y * 2.0);
+                    let ray = camera.ndc_to_ray(ndc);
+                    let max_distance = far_depth / ray.direction.z.max(1e-6);
+                    sum += self.trace(world, ray, max_distance, &mut random);
+                }
+                radiance.push(sum);
+            }
+        }
+        radiance
+    }
+
+    fn trace(
+        &self,
+        world: &World,
+        mut ray: Ray,
+        mut max_distance: f32,
+        random: &mut Random,
+    ) -> Vec3 {
+        let mut radiance = Vec3::ZERO;
+        let mut throughput = Vec3::ONE;
+
+        for bounce in 0..=self.max_bounces {
+            let Some(hit) = world.intersect(&ray, max_distance) else {
+                break; // the background is black
+            };
+            let triangle = &world.triangles[hit.triangle];
+            let object = &world.objects[triangle.object];
+            let material = &object.material;
+
+            let weights = Vec3::new(1.0 - hit.u - hit.v, hit.u, hit.v);
+            let position = ray.at(hit.distance);
+            let uv = triangle.uvs[0] * weights.x
+                + triangle.uvs[1] * weights.y
+                + triangle.uvs[2] * weights.z;
+            let tangent = triangle.tangents[0] * weights.x
+                + triangle.tangents[1] * weights.y
+                + triangle.tangents[2] * weights.z;
+            // Faces are lit from whichever side the ray arrives on
+            let mut normal = triangle.normal;
+            if normal.dot(ray.direction) > 0.0 {
+                normal = -normal;
+            }
+
+            let surface = Surface::new(material, normal, tangent, uv);
+            let v = -ray.direction;
+            radiance += throughput * surface.emissive;
+            if material.get_shading_model() == ShadingModel::Unlit {
+                radiance += throughput * surface.base_color;
+                break;
+            }
+
+            // Direct light, with a shadow ray towards every light
+            let origin = position + normal * SURFACE_OFFSET;
+            for light in &world.lights {
+                let (l, light_radiance) = light_incidence(light, position);
+                if light_radiance == Vec3::ZERO || normal.dot(l) <= 0.0 {
+                    continue;
+                }
+                if object.receive_shadows {
+                    let distance = match light.light_type {
+                        LightType::Point => (light.position - origin).length(),
+                        LightType::Directional => f32::INFINITY,
+                    };
+                    if world.occluded(&Ray::new(origin, l), distance) {
+                        continue;
+                    }
+                }
+                radiance += throughput * shade(material, &surface, v, l, light_radiance);
+            }
+
+            if bounce == self.max_bounces {
+                break;
+            }
+
+            // Indirect light from a cosine-weighted direction around the normal
+            let direction = sample_cosine_hemisphere(surface.normal, random);
+            let cos_theta = surface.normal.dot(direction);
+            if cos_theta <= 0.0 || normal.dot(direction) <= 0.0 {
+                break;
+            }
+            let pdf = cos_theta / PI;
+            throughput *= reflectance(material, &surface, v, direction) / pdf;
+
+            if bounce + 1 >= MIN_BOUNCES {
+                let survival = throughput.max_element().clamp(0.05, 1.0);
+                if random.next_f32() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+            if throughput == Vec3::ZERO {
+                break;
+            }
+
+            ray = Ray::new(origin, direction);
+            max_distance = f32::INFINITY;
+        }
+
+        radiance
+    }
+}
+
+impl RenderBackend for PathTracer {
+    fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    fn render(&mut self, scene: &Scene, camera: &Camera) -> RgbaImage {
+        self.reset();
+        self.accumulate(scene, camera, self.samples_per_pixel);
+        self.get_image().to_rgba8()
+    }
+}
+
+// World-space copy of the scene that can be shared between threads
+struct World {
+    triangles: Vec<WorldTriangle>,
+    objects: Vec<WorldObject>,
+    lights: Vec<WorldLight>,
+    bvh: Bvh, // over the triangles
+}
+
+struct WorldTriangle {
+    vertices: [Vec3; 3],
+    uvs: [Vec2; 3],
+    tangents: [Vec4; 3],
+    normal: Vec3,
+    object: usize,
+}
+
+struct WorldObject {
+    material: Material,
+    cast_shadows: bool,
+    receive_shadows: bool,
+}
+
+struct Hit {
+    distance: f32,
+    triangle: usize,
+    u: f32,
+    v: f32,
+}
+
+impl World {
+    fn new(scene: &Scene) -> Self {
+        let mut triangles = Vec::new();
+        let mut objects = Vec::new();
+        // Where the rasterizers draw the objects, between their last two fixed steps
+        let alpha = scene.get_interpolation_alpha();
+        for object in scene.get_objects() {
+            for tri in object.get_mesh().get_triangles() {
+                let transformed_tri = object.interpolated_triangle(tri.clone(), alpha);
+                triangles.push(WorldTriangle {
+                    vertices: transformed_tri.get_vertices(),
+                    uvs: transformed_tri.get_uvs(),
+                    tangents: transformed_tri.get_tangents(),
+                    normal: transformed_tri.get_normal(),
+                    object: objects.len(),
+                });
+            }
+            objects.push(WorldObject {
+                material: object.get_material().clone(),
+                cast_shadows: object.get_cast_shadows(),
+                receive_shadows: object.get_receive_shadows(),
+            });
+        }
+
+        let lights = scene
+            .get_lights()
+            .iter()
+            .map(|light| WorldLight {
+                light_type: light.get_light_type(),
+                color: light.get_color(),
+                intensity: light.get_intensity(),
+                position: *light.get_position(),
+                rotation: *light.get_rotation(),
+                direction: light.get_direction(),
+                range: light.get_range(),
+            })
+            .collect();
+
+        let bounds: Vec<Aabb> = triangles
+            .iter()
+            .map(|triangle| Aabb::from_points(triangle.vertices))
+            .collect();
+        Self {
+            bvh: Bvh::new(&bounds),
+            triangles,
+            objects,
+            lights,
+        }
+    }
+
+    // Closest hit closer than `max_distance`
+    fn intersect(&self, ray: &Ray, max_distance: f32) -> Option<Hit> {
+        let mut weights = (0.0, 0.0);
+        let (triangle, distance) = self.bvh.raycast(ray, max_distance, |index, limit| {
+            let (distance, u, v) = ray.intersect_triangle(self.triangles[index].vertices)?;
+            if distance < limit {
+                weights = (u, v);
+            }
+            Some(distance)
+        })?;
+        Some(Hit {
+            distance,
+            triangle,
+            u: weights.0,
+            v: weights.1,
+        })
+    }
+
+    // Whether anything that casts shadows lies closer than `max_distance` along the ray
+    fn occluded(&self, ray: &Ray, max_distance: f32) -> bool {
+        self.bvh.raycast_any(ray, max_distance, |index, _| {
+            let triangle = &self.triangles[index];
+            if !self.objects[triangle.object].cast_shadows {
+                return None;
+            }
+            ray.intersect_triangle(triangle.vertices)
+                .map(|(distance, _, _)| distance)
+        })
+    }
+}
+
+// Plain copy of a scene light, so the shading code can use it from any thread
+struct WorldLight {
+    light_type: LightType,
+    color: Vec3,
+    intensity: f32,
+    position: Vec3,
+    rotation: Vec3,
+    direction: Vec3,
+    range: f32,
+}
+
+impl Light for WorldLight {
+    fn get_light_type(&self) -> LightType {
+        self.light_type
+    }
+
+    fn get_color(&self) -> Vec3 {
+        self.color
+    }
+
+    fn get_intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    fn get_position(&self) -> &Vec3 {
+        &self.position
+    }
+
+    fn get_rotation(&self) -> &Vec3 {
+        &self.rotation
+    }
+
+    fn get_direction(&self) -> Vec3 {
+        self.direction
+    }
+
+    fn get_range(&self) -> f32 {
+        self.range
+    }
+}
+
+// Direction around `normal` with probability proportional to the cosine to it
+fn sample_cosine_hemisphere(normal: Vec3, random: &mut Random) -> Vec3 {
+    let radius = random.next_f32().sqrt();
+    let angle = 2.0 * PI * random.next_f32();
+    let x = radius * angle.cos();
+    let y = radius * angle.sin();
+    let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+    let (tangent, bitangent) = normal.any_orthonormal_pair();
+    (tangent * x + bitangent * y + normal * z).normalize()
+}
+
+// Small PCG generator; good enough for sampling and free of dependencies
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(stream: u64, seed: u64) -> Self {
+        let mut random = Self {
+            state: seed.wrapping_mul(0x9e37_79b9_7f4a_7c15)
+                ^ stream.wrapping_mul(0xbf58_476d_1ce4_e5b9),
+        };
+        random.next_u32();
+        random
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let shifted = (((state >> 18) ^ state) >> 27) as u32;
+        shifted.rotate_right((state >> 59) as u32)
+    }
+
+    // Uniform in [0, 1)
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
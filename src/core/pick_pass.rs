@@ -0,0 +1,247 @@
+use crate::core::scene_renderer::{DEPTH_FORMAT, create_depth_view};
+use std::collections::BTreeSet;
+use std::ops::Range;
+use wgpu;
+
+const ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+// Id target and its depth buffer, kept between picks of the same size
+struct PickTarget {
+    width: u32,
+    height: u32,
+    id_texture: wgpu::Texture,
+    id_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+}
+
+// Redraws the scene's mesh vertices with each object's id instead of its shading, then reads
+// back the ids under a screen region. Ids are the object's index plus one, so zero is empty.
+// The vertices and the depth test are the same as the color pass, so the ids line up with
+// what's on screen pixel for pixel.
+pub struct PickPass {
+    pipeline: wgpu::RenderPipeline,
+    target: Option<PickTarget>,
+}
+
+impl PickPass {
+    // `vertex_stride` is the size of the scene renderer's vertices, which start with the
+    // clip-space position
+    pub fn new(device: &wgpu::Device, vertex_stride: u64) -> Self {
+        let vertex_buffer_layout = wgpu::VertexBufferLayout {
+            array_stride: vertex_stride,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                // Position attribute
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        };
+
+        let vs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Pick Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/pick.vert.wgsl").into()),
+        });
+        let fs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Pick Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/pick.frag.wgsl").into()),
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Pick Pipeline Layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Pick Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &vs_module,
+                entry_point: Some("vs_main"),
+                buffers: &[vertex_buffer_layout],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &fs_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: ID_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            // Same rasterization and depth test as the mesh pipeline
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            target: None,
+        }
+    }
+
+    // Draws `draws`, one range of `vertex_buffer` per object in scene order, into an id
+    // buffer of `size` pixels and returns the indices of the objects covering any pixel in
+    // the columns and rows of `region`, in ascending order
+    pub fn pick(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertex_buffer: &wgpu::Buffer,
+        draws: &[Range<u32>],
+        size: (u32, u32),
+        region: (Range<u32>, Range<u32>),
+    ) -> Vec<usize> {
+        let (width, height) = size;
+        let min = (region.0.start, region.1.start);
+        let max = (region.0.end.min(width), region.1.end.min(height));
+        if min.0 >= max.0 || min.1 >= max.1 {
+            return Vec::new();
+        }
+        self.prepare_target(device, width, height);
+        let target = self.target.as_ref().unwrap();
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Pick Encoder"),
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Pick Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target.id_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &target.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            for (index, range) in draws.iter().enumerate() {
+                if range.is_empty() {
+                    continue;
+                }
+                let id = index as u32 + 1;
+                render_pass.draw(range.clone(), id..id + 1);
+            }
+        }
+
+        // Copy out just the region, with rows aligned to COPY_BYTES_PER_ROW_ALIGNMENT
+        let region_width = max.0 - min.0;
+        let region_height = max.1 - min.1;
+        let padded_bytes_per_row = (4 * region_width).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pick Readback Buffer"),
+            size: (padded_bytes_per_row * region_height) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &target.id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: min.0,
+                    y: min.1,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(region_height),
+                },
+            },
+            wgpu::Extent3d {
+                width: region_width,
+                height: region_height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let slice = readback_buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::PollType::Wait).unwrap();
+
+        let mut ids: BTreeSet<u32> = BTreeSet::new();
+        {
+            let data = slice.get_mapped_range();
+            let row_bytes = (4 * region_width) as usize;
+            for row in data.chunks(padded_bytes_per_row as usize) {
+                let row: &[u32] = bytemuck::cast_slice(&row[..row_bytes]);
+                ids.extend(row.iter().copied().filter(|&id| id != 0));
+            }
+        }
+        readback_buffer.unmap();
+
+        ids.into_iter().map(|id| id as usize - 1).collect()
+    }
+
+    fn prepare_target(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if self
+            .target
+            .as_ref()
+            .is_none_or(|target| target.width != width || target.height != height)
+        {
+            let id_texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Pick Id Texture"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: ID_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            self.target = Some(PickTarget {
+                width,
+                height,
+                id_view: id_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                id_texture,
+                depth_view: create_depth_view(device, width, height),
+            });
+        }
+    }
+}
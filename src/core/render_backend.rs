@@ -0,0 +1,24 @@
+use crate::core::camera::Camera;
+use crate::core::scene::Scene;
+use image::RgbaImage;
+use std::path::Path;
+
+// A renderer that draws a scene into an image, on the GPU or on the CPU
+pub trait RenderBackend {
+    fn get_width(&self) -> u32;
+    fn get_height(&self) -> u32;
+
+    // Renders one frame as 8-bit sRGB RGBA. The scene isn't updated, so rendering the same
+    // scene twice gives the same image.
+    fn render(&mut self, scene: &Scene, camera: &Camera) -> RgbaImage;
+
+    fn render_to_png(
+        &mut self,
+        scene: &Scene,
+        camera: &Camera,
+        path: &Path,
+    ) -> Result<(), image::ImageError> {
+        self.render(scene, camera)
+            .save_with_format(path, image::ImageFormat::Png)
+    }
+}
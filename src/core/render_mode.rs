@@ -0,0 +1,42 @@
+use crate::core::camera::Camera;
+use crate::core::object::Object;
+use glam::Vec4;
+
+// How an object is drawn. Wireframes are drawn as lines along the triangle edges, so they
+// don't need the POLYGON_MODE_LINE feature and work on every adapter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    Solid,
+    Wireframe,
+    SolidWireframe, // shaded faces with the edges drawn on top
+}
+
+impl RenderMode {
+    pub fn draws_solid(self) -> bool {
+        matches!(self, RenderMode::Solid | RenderMode::SolidWireframe)
+    }
+
+    pub fn draws_wireframe(self) -> bool {
+        matches!(self, RenderMode::Wireframe | RenderMode::SolidWireframe)
+    }
+}
+
+// Share of w the edges are pulled towards the camera in clip space, so they win the depth
+// test against the faces they lie on
+const WIREFRAME_DEPTH_BIAS: f32 = 1e-3;
+
+// The object's triangle edges as a clip-space line list, two points per line, with the
+// object at its pose interpolated by `alpha`
+pub(crate) fn wireframe_lines(object: &Object, camera: &Camera, alpha: f32) -> Vec<Vec4> {
+    let (position, rotation) = object.get_interpolated_pose(alpha);
+    object
+        .get_mesh()
+        .get_vertices()
+        .into_iter()
+        .map(|vertex| {
+            let mut clip = camera.project_point_clip(rotation * vertex + position);
+            clip.z -= WIREFRAME_DEPTH_BIAS * clip.w;
+            clip
+        })
+        .collect()
+}
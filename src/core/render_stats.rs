@@ -0,0 +1,7 @@
+// What the last frame drew, after frustum and back-face culling
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    pub drawn_objects: u32,
+    pub culled_objects: u32, // outside the view frustum, skipped without looking at a triangle
+    pub drawn_triangles: u32,
+}
@@ -1,29 +1,34 @@
 use crate::core::camera::Camera;
+use crate::core::config::{get_config, update_config};
+use crate::core::render_stats::RenderStats;
 use crate::core::scene::Scene;
-use bytemuck;
+use crate::core::scene_renderer::{RenderTarget, SceneRenderer, create_depth_view};
+use glam::Vec3;
 use std::sync::Arc;
 use std::time::Instant;
 use wgpu;
 use winit::{
-    event::{Event, WindowEvent},
+    event::{ElementState, Event, MouseButton, WindowEvent},
     event_loop::{ActiveEventLoop, EventLoop},
     window::Window,
 };
 
+// Drags shorter than this many pixels are clicks rather than marquee selections
+const MARQUEE_THRESHOLD: f32 = 4.0;
+
 pub struct Renderer {
     device: wgpu::Device,
     queue: wgpu::Queue,
     surface: wgpu::Surface<'static>,
     config: wgpu::SurfaceConfiguration,
+    depth_view: wgpu::TextureView,
     window: Arc<Window>,
     scene: Scene,
     camera: Camera,
-    vertex_buffer: Option<wgpu::Buffer>,
-    vertex_capacity: usize,
-    render_pipeline: wgpu::RenderPipeline,
-    light_buffer: wgpu::Buffer,
-    light_bind_group: wgpu::BindGroup,
+    scene_renderer: SceneRenderer,
     last_frame_time: Instant,
+    cursor_position: Option<(f32, f32)>, // in pixels, None while outside the window
+    drag_start: Option<(f32, f32)>,      // where the left button went down
 }
 
 impl Renderer {
@@ -74,113 +79,10 @@ impl Renderer {
             desired_maximum_frame_latency: 2,
         };
         surface.configure(&device, &config);
+        update_config(config.width, config.height, get_config().fov);
+        let depth_view = create_depth_view(&device, config.width, config.height);
 
-        // Create light buffer
-        let light_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Light Buffer"),
-            size: std::mem::size_of::<[f32; 8]>() as u64, // Changed from 7 to 8 to match shader expectation (32 bytes)
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        // Create bind group layout for light
-        let light_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Light Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-            });
-
-        // Create bind group for light
-        let light_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Light Bind Group"),
-            layout: &light_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: light_buffer.as_entire_binding(),
-            }],
-        });
-
-        // Create vertex buffer layout with normals
-        let vertex_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<[f32; 6]>() as u64, // 3 for position, 3 for normal
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-                // Position attribute
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3,
-                },
-                // Normal attribute
-                wgpu::VertexAttribute {
-                    offset: std::mem::size_of::<[f32; 3]>() as u64,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3,
-                },
-            ],
-        };
-
-        // Create shader modules
-        let vs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Line Vertex Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/line.vert.wgsl").into()),
-        });
-        let fs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Line Fragment Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/line.frag.wgsl").into()),
-        });
-
-        // Create pipeline layout
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&light_bind_group_layout],
-                push_constant_ranges: &[],
-            });
-
-        // Create render pipeline
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Line Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &vs_module,
-                entry_point: Some("vs_main"),
-                buffers: &[vertex_buffer_layout],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &fs_module,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                unclipped_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+        let scene_renderer = SceneRenderer::new(&device, config.format);
 
         (
             Renderer {
@@ -188,15 +90,14 @@ impl Renderer {
                 queue,
                 surface,
                 config,
+                depth_view,
                 window,
                 scene,
                 camera,
-                vertex_buffer: None,
-                vertex_capacity: 0,
-                render_pipeline,
-                light_buffer,
-                light_bind_group,
+                scene_renderer,
                 last_frame_time: Instant::now(),
+                cursor_position: None,
+                drag_start: None,
             },
             event_loop,
         )
@@ -208,6 +109,9 @@ impl Renderer {
         self.last_frame_time = now;
 
         self.scene.update(delta_time);
+        if let (Some(start), Some(end)) = (self.drag_start, self.cursor_position) {
+            self.draw_marquee(start, end);
+        }
 
         let frame = match self.surface.get_current_texture() {
             Ok(frame) => frame,
@@ -217,108 +121,61 @@ impl Renderer {
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
-
-        // Update light data
-        if let Some(light) = self.scene.get_lights().first() {
-            let light_data = [
-                light.get_direction().x,
-                light.get_direction().y,
-                light.get_direction().z,
-                0.0, // Padding to align to 32 bytes
-                light.get_color().x,
-                light.get_color().y,
-                light.get_color().z,
-                light.get_intensity(),
-            ];
-            self.queue
-                .write_buffer(&self.light_buffer, 0, bytemuck::cast_slice(&light_data));
-        }
-
-        // --- Projected triangle edges from scene ---
-        let mut vertices: Vec<[f32; 6]> = Vec::new(); // [x, y, z, nx, ny, nz]
-        for object in self.scene.get_objects() {
-            for tri in object.get_mesh().get_triangles() {
-                let transformed_tri = object.transformed_triangle(tri.clone());
-
-                // culling
-                if transformed_tri
-                    .get_normal()
-                    .dot(transformed_tri.get_vertices()[0] - self.camera.get_position())
-                    >= 0.0
-                {
-                    continue;
-                }
-
-                // projection
-                let projected_tri = self.camera.project_triangle(transformed_tri.clone());
-                let [v0, v1, v2] = projected_tri.get_vertices();
-                let normal = transformed_tri.get_normal();
+        self.scene_renderer.render(
+            &self.device,
+            &self.queue,
+            &self.scene,
+            &self.camera,
+            RenderTarget {
+                color_view: &view,
+                depth_view: &self.depth_view,
+                size: (self.config.width, self.config.height),
+            },
+        );
+        frame.present();
+    }
 
-                // Add triangle vertices with normals
-                vertices.push([v0.x, v0.y, v0.z, normal.x, normal.y, normal.z]);
-                vertices.push([v1.x, v1.y, v1.z, normal.x, normal.y, normal.z]);
-                vertices.push([v2.x, v2.y, v2.z, normal.x, normal.y, normal.z]);
-            }
-        }
+    // The scene, whose selection and hovered object the mouse controls
+    pub fn get_scene(&self) -> &Scene {
+        &self.scene
+    }
 
-        if vertices.is_empty() {
-            // Skip rendering if there are no vertices
-            self.queue.submit(Some(encoder.finish()));
-            frame.present();
-            return;
-        }
+    pub fn get_scene_mut(&mut self) -> &mut Scene {
+        &mut self.scene
+    }
 
-        // --- Dynamic vertex buffer allocation ---
-        let needed_capacity = vertices.len();
-        let needed_bytes = std::mem::size_of::<[f32; 6]>() * needed_capacity;
-        if self.vertex_buffer.is_none() || self.vertex_capacity < needed_capacity {
-            self.vertex_buffer = Some(self.device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("Line Vertex Buffer"),
-                size: needed_bytes as u64,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            }));
-            self.vertex_capacity = needed_capacity;
-        }
-        if let Some(buffer) = &self.vertex_buffer {
-            self.queue
-                .write_buffer(buffer, 0, bytemuck::cast_slice(&vertices));
-        }
+    // Index of the object under a point on the window, in pixels, by casting a ray into the
+    // scene on the CPU
+    pub fn object_at(&self, x: f32, y: f32) -> Option<usize> {
+        let ray = self.camera.screen_point_to_ray(x, y);
+        // Only as far as the far plane, so nothing that isn't drawn gets picked
+        let max_distance = (self.camera.get_far() - self.camera.get_near()) / ray.direction.z;
+        self.scene.raycast(&ray, max_distance).map(|hit| hit.object)
+    }
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.0,
-                            g: 0.0,
-                            b: 0.0,
-                            a: 1.0,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
+    // Index of the object the last frame drew at a pixel, from the top left, read from the
+    // GPU's object id buffer
+    pub fn pick(&mut self, x: u32, y: u32) -> Option<usize> {
+        self.pick_rect((x, y), (x, y)).first().copied()
+    }
 
-            if let Some(buffer) = &self.vertex_buffer {
-                render_pass.set_pipeline(&self.render_pipeline);
-                render_pass.set_bind_group(0, &self.light_bind_group, &[]);
-                render_pass.set_vertex_buffer(0, buffer.slice(..));
-                render_pass.draw(0..vertices.len() as u32, 0..1);
-            }
-        }
+    // Indices of every object the last frame drew between two corners of a rectangle, in
+    // pixels from the top left, given in any order; both corners are inside it
+    pub fn pick_rect(&mut self, a: (u32, u32), b: (u32, u32)) -> Vec<usize> {
+        self.scene_renderer.pick(
+            &self.device,
+            &self.queue,
+            (self.config.width, self.config.height),
+            (
+                a.0.min(b.0)..a.0.max(b.0) + 1,
+                a.1.min(b.1)..a.1.max(b.1) + 1,
+            ),
+        )
+    }
 
-        self.queue.submit(Some(encoder.finish()));
-        frame.present();
+    // Objects and triangles drawn by the last frame
+    pub fn get_stats(&self) -> RenderStats {
+        self.scene_renderer.get_stats()
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
@@ -326,6 +183,24 @@ impl Renderer {
             self.config.width = width;
             self.config.height = height;
             self.surface.configure(&self.device, &self.config);
+            update_config(width, height, get_config().fov);
+            self.depth_view = create_depth_view(&self.device, width, height);
+        }
+    }
+
+    // Outline of the selection rectangle, just past the near plane
+    fn draw_marquee(&mut self, start: (f32, f32), end: (f32, f32)) {
+        let corner = |x: f32, y: f32| self.camera.screen_point_to_ray(x, y).at(0.01);
+        let corners = [
+            corner(start.0, start.1),
+            corner(end.0, start.1),
+            corner(end.0, end.1),
+            corner(start.0, end.1),
+        ];
+        for i in 0..4 {
+            self.scene
+                .get_debug_draw_mut()
+                .line(corners[i], corners[(i + 1) % 4], Vec3::ONE, None);
         }
     }
 
@@ -349,6 +224,39 @@ impl winit::application::ApplicationHandler<()> for Renderer {
                 self.resize(physical_size.width, physical_size.height);
                 self.window.request_redraw();
             }
+            // Hovering uses a CPU ray, which is cheap enough for every mouse move
+            WindowEvent::CursorMoved { position, .. } => {
+                let (x, y) = (position.x as f32, position.y as f32);
+                self.cursor_position = Some((x, y));
+                let hovered = self.object_at(x, y);
+                self.scene.set_hovered_object(hovered);
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor_position = None;
+                self.scene.set_hovered_object(None);
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => match state {
+                ElementState::Pressed => self.drag_start = self.cursor_position,
+                // A click selects the object under the cursor and a drag everything in the
+                // rectangle, going by the ids drawn in the last frame
+                ElementState::Released => {
+                    if let (Some(start), Some(end)) = (self.drag_start.take(), self.cursor_position)
+                    {
+                        let to_pixel = |(x, y): (f32, f32)| (x as u32, y as u32);
+                        let dragged = (end.0 - start.0).abs().max((end.1 - start.1).abs());
+                        let selected = if dragged < MARQUEE_THRESHOLD {
+                            self.pick(end.0 as u32, end.1 as u32).into_iter().collect()
+                        } else {
+                            self.pick_rect(to_pixel(start), to_pixel(end))
+                        };
+                        self.scene.set_selected_objects(selected);
+                    }
+                }
+            },
             WindowEvent::RedrawRequested => {
                 self.render();
             }
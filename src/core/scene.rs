@@ -1,9 +1,45 @@
+use crate::core::debug_draw::DebugDraw;
 use crate::core::light::Light;
 use crate::core::object::Object;
+use crate::core::render_mode::RenderMode;
+use crate::geometry::bounds::Aabb;
+use crate::geometry::bvh::Bvh;
+use crate::geometry::ray::Ray;
+use crate::physics::world::{PhysicsWorld, TriggerEvent};
+use glam::{Vec3, Vec4};
+use std::cell::Cell;
+use std::sync::OnceLock;
+
+// Outlines are found by searching this many pixels around each pixel, so thicker ones are
+// clamped to it
+const MAX_OUTLINE_THICKNESS: f32 = 16.0;
 
 pub struct Scene {
     objects: Vec<Object>,
     lights: Vec<Box<dyn Light>>,
+    render_mode: RenderMode,      // for objects that don't set their own
+    line_color: Vec3,             // linear color of wireframe edges
+    bvh: OnceLock<Bvh>,           // over the objects' world bounds, built on first use
+    stale_bvh: Cell<Option<Bvh>>, // taken out of `bvh` when objects may have moved
+    selected_objects: Vec<usize>,
+    hovered_object: Option<usize>,
+    outline_color: Vec4,    // linear color and opacity of the selection outline
+    outline_thickness: f32, // in pixels
+    hover_tint: Vec4,       // color mixed into the hovered object, by the amount in w
+    physics: PhysicsWorld,
+    trigger_events: Vec<TriggerEvent>, // from the last update
+    debug_draw: DebugDraw,
+}
+
+// Where a ray hits the scene
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RaycastHit {
+    pub object: usize,   // index into get_objects()
+    pub triangle: usize, // index into the object's mesh triangles
+    pub distance: f32,
+    pub point: Vec3,       // world space
+    pub normal: Vec3,      // world-space face normal
+    pub barycentric: Vec3, // weights of the triangle's three vertices at the hit
 }
 
 impl Scene {
@@ -11,6 +47,18 @@ impl Scene {
         Scene {
             objects: Vec::new(),
             lights: Vec::new(),
+            render_mode: RenderMode::Solid,
+            line_color: Vec3::new(1.0, 1.0, 1.0),
+            bvh: OnceLock::new(),
+            stale_bvh: Cell::new(None),
+            selected_objects: Vec::new(),
+            hovered_object: None,
+            outline_color: Vec4::new(1.0, 0.5, 0.05, 1.0),
+            outline_thickness: 2.0,
+            hover_tint: Vec4::new(1.0, 1.0, 1.0, 0.2),
+            physics: PhysicsWorld::new(),
+            trigger_events: Vec::new(),
+            debug_draw: DebugDraw::new(),
         }
     }
 
@@ -18,8 +66,72 @@ impl Scene {
         &self.objects
     }
 
+    // The object may be moved, so the bounds hierarchy is refitted before its next use
+    pub fn get_object_mut(&mut self, index: usize) -> &mut Object {
+        self.invalidate_bvh();
+        &mut self.objects[index]
+    }
+
     pub fn add_object(&mut self, object: Object) {
         self.objects.push(object);
+        self.bvh = OnceLock::new();
+        self.stale_bvh = Cell::new(None);
+    }
+
+    // Hierarchy over the objects' world bounds, indexed like get_objects(). Refitted on first
+    // use after objects may have moved, and rebuilt when objects are added.
+    pub fn get_bvh(&self) -> &Bvh {
+        self.bvh.get_or_init(|| {
+            let bounds = self.get_world_bounds();
+            match self.stale_bvh.take() {
+                Some(mut bvh) => {
+                    bvh.refit(&bounds);
+                    bvh
+                }
+                None => Bvh::new(&bounds),
+            }
+        })
+    }
+
+    // Keeps the built tree's shape for the refit in get_bvh
+    fn invalidate_bvh(&mut self) {
+        if let Some(bvh) = self.bvh.take() {
+            self.stale_bvh.set(Some(bvh));
+        }
+    }
+
+    // Indices of the objects whose world bounds overlap `aabb`
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<usize> {
+        let mut objects = Vec::new();
+        self.get_bvh().query_aabb(aabb, |index| objects.push(index));
+        objects
+    }
+
+    // Closest object hit by a world-space ray within `max_distance`. Both sides of every
+    // triangle count, so rays starting inside a mesh hit it from within.
+    pub fn raycast(&self, ray: &Ray, max_distance: f32) -> Option<RaycastHit> {
+        let mut closest = None;
+        self.get_bvh().raycast(ray, max_distance, |index, limit| {
+            let object = &self.objects[index];
+            let hit = object
+                .get_mesh()
+                .raycast(&object.to_local_ray(ray), limit)?;
+            let normal = object.get_mesh().get_triangles()[hit.triangle].get_normal();
+            closest = Some(RaycastHit {
+                object: index,
+                triangle: hit.triangle,
+                distance: hit.distance,
+                point: ray.at(hit.distance),
+                normal: object.get_rotation_matrix() * normal,
+                barycentric: hit.barycentric,
+            });
+            Some(hit.distance)
+        });
+        closest
+    }
+
+    fn get_world_bounds(&self) -> Vec<Aabb> {
+        self.objects.iter().map(Object::get_world_aabb).collect()
     }
 
     pub fn get_lights(&self) -> &Vec<Box<dyn Light>> {
@@ -30,9 +142,165 @@ impl Scene {
         self.lights.push(Box::new(light));
     }
 
+    pub fn get_render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    pub fn set_render_mode(&mut self, render_mode: RenderMode) {
+        self.render_mode = render_mode;
+    }
+
+    // The mode `object` is drawn with, its own if it has one
+    pub fn get_object_render_mode(&self, object: &Object) -> RenderMode {
+        object.get_render_mode().unwrap_or(self.render_mode)
+    }
+
+    pub fn get_line_color(&self) -> Vec3 {
+        self.line_color
+    }
+
+    pub fn set_line_color(&mut self, line_color: Vec3) {
+        self.line_color = line_color;
+    }
+
+    // Indices into get_objects() of the objects drawn with an outline
+    pub fn get_selected_objects(&self) -> &Vec<usize> {
+        &self.selected_objects
+    }
+
+    pub fn set_selected_objects(&mut self, selected_objects: Vec<usize>) {
+        self.selected_objects = selected_objects;
+    }
+
+    pub fn is_selected(&self, object: usize) -> bool {
+        self.selected_objects.contains(&object)
+    }
+
+    // Index of the object drawn with the hover tint, such as the one under the mouse
+    pub fn get_hovered_object(&self) -> Option<usize> {
+        self.hovered_object
+    }
+
+    pub fn set_hovered_object(&mut self, hovered_object: Option<usize>) {
+        self.hovered_object = hovered_object;
+    }
+
+    pub fn get_outline_color(&self) -> Vec4 {
+        self.outline_color
+    }
+
+    pub fn set_outline_color(&mut self, outline_color: Vec4) {
+        self.outline_color = outline_color;
+    }
+
+    pub fn get_outline_thickness(&self) -> f32 {
+        self.outline_thickness
+    }
+
+    // Zero hides the outline
+    pub fn set_outline_thickness(&mut self, outline_thickness: f32) {
+        self.outline_thickness = outline_thickness.clamp(0.0, MAX_OUTLINE_THICKNESS);
+    }
+
+    pub fn get_hover_tint(&self) -> Vec4 {
+        self.hover_tint
+    }
+
+    // A w of zero turns the tint off
+    pub fn set_hover_tint(&mut self, hover_tint: Vec4) {
+        self.hover_tint = hover_tint;
+    }
+
+    // Tint for the object at `index`: the hover tint when it's hovered, otherwise none
+    pub(crate) fn get_object_tint(&self, index: usize) -> Vec4 {
+        if self.hovered_object == Some(index) {
+            self.hover_tint
+        } else {
+            Vec4::ZERO
+        }
+    }
+
+    pub fn get_physics(&self) -> &PhysicsWorld {
+        &self.physics
+    }
+
+    pub fn get_physics_mut(&mut self) -> &mut PhysicsWorld {
+        &mut self.physics
+    }
+
+    // Everything that entered, stayed in or left a trigger during the last update, oldest
+    // first; each object also gets its own through Object::get_trigger_events
+    pub fn get_trigger_events(&self) -> &Vec<TriggerEvent> {
+        &self.trigger_events
+    }
+
+    // Debug lines drawn with this scene
+    pub fn get_debug_draw(&self) -> &DebugDraw {
+        &self.debug_draw
+    }
+
+    pub fn get_debug_draw_mut(&mut self) -> &mut DebugDraw {
+        &mut self.debug_draw
+    }
+
+    // How far the frame time left over after the last update's fixed steps goes toward the
+    // next one, from 0 to 1; objects are drawn this far between their poses before and after
+    // the last step
+    pub fn get_interpolation_alpha(&self) -> f32 {
+        self.physics.get_interpolation_alpha()
+    }
+
+    // Ages the debug lines, then runs as many fixed steps as the physics world's clock lets
+    // the frame time cover, each calling every object's fixed_update and then stepping
+    // physics, then every object's update once. Lines the objects drew meanwhile are moved
+    // into the scene's debug lines last, so they show this frame.
     pub fn update(&mut self, delta_time: f32) {
+        self.debug_draw.advance(delta_time);
+        for _ in 0..self.physics.advance(delta_time) {
+            self.step_fixed();
+        }
+        self.deliver_trigger_events();
+
         for object in &mut self.objects {
             object.update(delta_time);
+            self.debug_draw.append(object.get_debug_draw_mut());
         }
+        // Objects may have moved
+        self.invalidate_bvh();
+    }
+
+    fn step_fixed(&mut self) {
+        let poses: Vec<_> = self
+            .objects
+            .iter()
+            .map(|object| (*object.get_position(), object.get_rotation_matrix()))
+            .collect();
+        let fixed_timestep = self.physics.get_fixed_timestep();
+        for object in &mut self.objects {
+            object.fixed_update(fixed_timestep);
+        }
+        self.physics.step_fixed(&mut self.objects, fixed_timestep);
+        for (object, (position, rotation)) in self.objects.iter_mut().zip(poses) {
+            object.set_fixed_step_motion(position, rotation);
+        }
+    }
+
+    // Passes the physics steps' trigger events to the objects on both sides of each
+    fn deliver_trigger_events(&mut self) {
+        self.trigger_events = self.physics.take_trigger_events();
+        let mut inboxes = vec![Vec::new(); self.objects.len()];
+        for event in &self.trigger_events {
+            inboxes[event.trigger].push(*event);
+            inboxes[event.other].push(*event);
+        }
+        for (object, inbox) in self.objects.iter_mut().zip(inboxes) {
+            object.set_trigger_events(inbox);
+        }
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,674 @@
+use crate::core::camera::Camera;
+use crate::core::line_pass::{LinePass, LineVertex};
+use crate::core::material::Material;
+use crate::core::object::Object;
+use crate::core::outline_pass::OutlinePass;
+use crate::core::pick_pass::PickPass;
+use crate::core::render_mode::wireframe_lines;
+use crate::core::render_stats::RenderStats;
+use crate::core::scene::Scene;
+use crate::core::shadow_pass::ShadowPass;
+use crate::core::texture_cache::{TextureCache, TextureKey};
+use bytemuck;
+use glam::Vec4;
+use std::ops::Range;
+use wgpu;
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Vertex {
+    position: [f32; 4],       // clip-space position
+    normal: [f32; 3],         // world-space normal
+    world_position: [f32; 3], // world-space position, used for view-dependent shading
+    uv: [f32; 2],
+    tangent: [f32; 4], // world-space tangent, w holds the bitangent sign
+}
+
+// Must match the `Material` struct in mesh.frag.wgsl
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct MaterialUniform {
+    base_color: [f32; 4],
+    emissive: [f32; 3],
+    shading_model: u32,
+    metallic: f32,
+    roughness: f32,
+    specular: f32,
+    shininess: f32,
+    normal_scale: f32,    // zero when the material has no normal map
+    receive_shadows: u32, // per-object flag, stored alongside the object's material
+    _padding: [f32; 2],
+    tint: [f32; 4], // per-object color mixed into the result by the amount in w, for hovering
+}
+
+impl MaterialUniform {
+    fn from_object(object: &Object, tint: Vec4) -> Self {
+        let material: &Material = object.get_material();
+        Self {
+            base_color: material.get_base_color().to_array(),
+            emissive: material.get_emissive().to_array(),
+            shading_model: material.get_shading_model().to_index(),
+            metallic: material.get_metallic(),
+            roughness: material.get_roughness(),
+            specular: material.get_specular(),
+            shininess: material.get_shininess(),
+            normal_scale: if material.get_normal_texture().is_some() {
+                material.get_normal_scale()
+            } else {
+                0.0
+            },
+            receive_shadows: object.get_receive_shadows() as u32,
+            _padding: [0.0; 2],
+            tint: tint.to_array(),
+        }
+    }
+}
+
+// Lights beyond this many are ignored by the shader
+const MAX_LIGHTS: usize = 8;
+
+// Must match the `Light` struct in mesh.frag.wgsl
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LightUniform {
+    position: [f32; 3],
+    light_type: u32,
+    direction: [f32; 3],
+    intensity: f32,
+    color: [f32; 3],
+    range: f32,
+    shadow_index: i32, // -1 when the light casts no shadows this frame
+    _padding: [f32; 3],
+}
+
+// Must match the `Lights` struct in mesh.frag.wgsl
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LightsUniform {
+    count: u32,
+    _padding: [u32; 3],
+    lights: [LightUniform; MAX_LIGHTS],
+}
+
+pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+// Color and depth views to draw into, both `size` pixels wide and high
+pub struct RenderTarget<'a> {
+    pub color_view: &'a wgpu::TextureView,
+    pub depth_view: &'a wgpu::TextureView,
+    pub size: (u32, u32),
+}
+
+// Everything needed to draw a scene with a camera into a color and depth target. The windowed
+// and headless renderers own the device and the targets and share this for the drawing.
+pub struct SceneRenderer {
+    vertex_buffer: Option<wgpu::Buffer>,
+    vertex_capacity: usize,
+    render_pipeline: wgpu::RenderPipeline,
+    light_buffer: wgpu::Buffer,
+    camera_buffer: wgpu::Buffer,
+    light_bind_group: wgpu::BindGroup,
+    material_bind_group_layout: wgpu::BindGroupLayout,
+    material_buffer: Option<wgpu::Buffer>,
+    material_bind_group: Option<wgpu::BindGroup>,
+    material_capacity: usize,
+    material_stride: u64,
+    texture_cache: TextureCache,
+    shadow_pass: ShadowPass,
+    line_pass: LinePass,
+    pick_pass: PickPass,
+    outline_pass: OutlinePass,
+    draws: Vec<Range<u32>>, // last frame's vertex range for each object, kept for picking
+    stats: RenderStats,
+}
+
+impl SceneRenderer {
+    pub fn new(device: &wgpu::Device, color_format: wgpu::TextureFormat) -> Self {
+        // Create light buffer
+        let light_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Light Buffer"),
+            size: std::mem::size_of::<LightsUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Create camera buffer (vec3 position and vec3 forward, each padded to 16 bytes)
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Camera Buffer"),
+            size: std::mem::size_of::<[f32; 8]>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Create bind group layout for light and camera
+        let light_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Light Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        // Create bind group for light and camera
+        let light_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Light Bind Group"),
+            layout: &light_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: light_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Create bind group layout for per-object materials, indexed with a dynamic offset
+        let material_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Material Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(
+                            std::mem::size_of::<MaterialUniform>() as u64,
+                        ),
+                    },
+                    count: None,
+                }],
+            });
+
+        // Each material slot has to start on the device's uniform offset alignment
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let material_stride =
+            (std::mem::size_of::<MaterialUniform>() as u64).div_ceil(alignment) * alignment;
+
+        let texture_cache = TextureCache::new(device);
+        let shadow_pass = ShadowPass::new(device);
+        let line_pass = LinePass::new(device, color_format);
+        let pick_pass = PickPass::new(device, std::mem::size_of::<Vertex>() as u64);
+        let outline_pass =
+            OutlinePass::new(device, color_format, std::mem::size_of::<Vertex>() as u64);
+
+        // Create vertex buffer layout with normals, world positions, texture coordinates and tangents
+        let vertex_buffer_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                // Position attribute
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                // Normal attribute
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as u64,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // World position attribute
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 7]>() as u64,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // Texture coordinate attribute
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 10]>() as u64,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                // Tangent attribute
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 12]>() as u64,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        };
+
+        // Create shader modules
+        let vs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Mesh Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/mesh.vert.wgsl").into()),
+        });
+        let fs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Mesh Fragment Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/mesh.frag.wgsl").into()),
+        });
+
+        // Create pipeline layout
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[
+                    &light_bind_group_layout,
+                    &material_bind_group_layout,
+                    texture_cache.get_bind_group_layout(),
+                    shadow_pass.get_bind_group_layout(),
+                ],
+                push_constant_ranges: &[],
+            });
+
+        // Create render pipeline
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mesh Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &vs_module,
+                entry_point: Some("vs_main"),
+                buffers: &[vertex_buffer_layout],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &fs_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            vertex_buffer: None,
+            vertex_capacity: 0,
+            render_pipeline,
+            light_buffer,
+            camera_buffer,
+            light_bind_group,
+            material_bind_group_layout,
+            material_buffer: None,
+            material_bind_group: None,
+            material_capacity: 0,
+            material_stride,
+            texture_cache,
+            shadow_pass,
+            line_pass,
+            pick_pass,
+            outline_pass,
+            draws: Vec::new(),
+            stats: RenderStats::default(),
+        }
+    }
+
+    // Renders the shadow maps and then the scene into the target, whose views must match the
+    // color format given to `new` and DEPTH_FORMAT
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        scene: &Scene,
+        camera: &Camera,
+        target: RenderTarget,
+    ) {
+        let RenderTarget {
+            color_view,
+            depth_view,
+            size,
+        } = target;
+        let camera = &camera.with_image_size(size.0, size.1);
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        let camera_position = camera.get_position();
+        let camera_forward = camera.get_forward();
+        let camera_data = [
+            camera_position.x,
+            camera_position.y,
+            camera_position.z,
+            0.0,
+            camera_forward.x,
+            camera_forward.y,
+            camera_forward.z,
+            0.0,
+        ];
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&camera_data));
+
+        // --- Projected triangles from scene, one draw range per object ---
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut draws: Vec<Range<u32>> = Vec::new();
+        let mut lines: Vec<LineVertex> = Vec::new();
+        let line_color = scene.get_line_color().extend(1.0).to_array();
+        let frustum = camera.get_frustum();
+        self.stats = RenderStats::default();
+        let alpha = scene.get_interpolation_alpha();
+        for object in scene.get_objects() {
+            // Objects entirely outside the view are skipped before looking at their triangles
+            if !frustum.intersects_aabb(&object.get_interpolated_world_aabb(alpha)) {
+                self.stats.culled_objects += 1;
+                draws.push(vertices.len() as u32..vertices.len() as u32);
+                continue;
+            }
+            self.stats.drawn_objects += 1;
+
+            let render_mode = scene.get_object_render_mode(object);
+            if render_mode.draws_wireframe() {
+                lines.extend(
+                    wireframe_lines(object, camera, alpha)
+                        .into_iter()
+                        .map(|position| LineVertex {
+                            position: position.to_array(),
+                            color: line_color,
+                        }),
+                );
+            }
+
+            let first_vertex = vertices.len() as u32;
+            if !render_mode.draws_solid() {
+                draws.push(first_vertex..first_vertex);
+                continue;
+            }
+            for tri in object.get_mesh().get_triangles() {
+                let transformed_tri = object.interpolated_triangle(tri.clone(), alpha);
+
+                // culling
+                if transformed_tri
+                    .get_normal()
+                    .dot(transformed_tri.get_vertices()[0] - camera.get_position())
+                    >= 0.0
+                {
+                    continue;
+                }
+
+                // projection
+                let world = transformed_tri.get_vertices();
+                let projected = world.map(|vertex| camera.project_point_clip(vertex));
+                let uvs = transformed_tri.get_uvs();
+                let tangents = transformed_tri.get_tangents();
+                let normal = transformed_tri.get_normal();
+
+                // Add triangle vertices with normals
+                for i in 0..3 {
+                    vertices.push(Vertex {
+                        position: projected[i].to_array(),
+                        normal: normal.to_array(),
+                        world_position: world[i].to_array(),
+                        uv: uvs[i].to_array(),
+                        tangent: tangents[i].to_array(),
+                    });
+                }
+            }
+            draws.push(first_vertex..vertices.len() as u32);
+            self.stats.drawn_triangles += (vertices.len() as u32 - first_vertex) / 3;
+        }
+
+        // --- Dynamic vertex buffer allocation ---
+        let needed_capacity = vertices.len();
+        let needed_bytes = std::mem::size_of::<Vertex>() * needed_capacity;
+        if !vertices.is_empty()
+            && (self.vertex_buffer.is_none() || self.vertex_capacity < needed_capacity)
+        {
+            self.vertex_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Mesh Vertex Buffer"),
+                size: needed_bytes as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+            self.vertex_capacity = needed_capacity;
+        }
+        if let Some(buffer) = &self.vertex_buffer {
+            queue.write_buffer(buffer, 0, bytemuck::cast_slice(&vertices));
+        }
+
+        for (start, end, color) in scene.get_debug_draw().get_lines() {
+            let color = color.extend(1.0).to_array();
+            for point in [start, end] {
+                lines.push(LineVertex {
+                    position: camera.project_point_clip(point).to_array(),
+                    color,
+                });
+            }
+        }
+        self.line_pass.prepare(device, queue, &lines);
+        self.write_materials(device, queue, scene);
+        let texture_keys = self.prepare_textures(device, queue, scene);
+
+        // Shadow maps are rendered before the scene that samples them
+        self.shadow_pass.prepare(device, queue, scene, camera);
+        self.shadow_pass.encode(&mut encoder);
+
+        // Light data, written after the shadow pass has assigned each light its shadow slot
+        self.write_lights(queue, scene);
+
+        self.outline_pass.prepare(
+            device,
+            queue,
+            size,
+            scene.get_outline_color(),
+            scene.get_outline_thickness(),
+        );
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 0.0,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            if let (Some(buffer), Some(material_bind_group)) =
+                (&self.vertex_buffer, &self.material_bind_group)
+            {
+                render_pass.set_pipeline(&self.render_pipeline);
+                render_pass.set_bind_group(0, &self.light_bind_group, &[]);
+                render_pass.set_bind_group(3, self.shadow_pass.get_bind_group(), &[]);
+                render_pass.set_vertex_buffer(0, buffer.slice(..));
+                for (index, range) in draws.iter().cloned().enumerate() {
+                    if range.is_empty() {
+                        continue;
+                    }
+                    let offset = (index as u64 * self.material_stride) as u32;
+                    render_pass.set_bind_group(1, material_bind_group, &[offset]);
+                    render_pass.set_bind_group(
+                        2,
+                        self.texture_cache.get_bind_group(&texture_keys[index]),
+                        &[],
+                    );
+                    render_pass.draw(range, 0..1);
+                }
+            }
+
+            // Wireframe edges and debug lines go on top of the shaded faces, in one draw
+            self.line_pass.draw(&mut render_pass);
+        }
+
+        // Selection outlines go on top of everything
+        if let Some(buffer) = &self.vertex_buffer {
+            let selected: Vec<Range<u32>> = draws
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| scene.is_selected(*index))
+                .map(|(_, range)| range.clone())
+                .collect();
+            self.outline_pass
+                .encode(&mut encoder, buffer, &selected, color_view, depth_view);
+        }
+
+        queue.submit(Some(encoder.finish()));
+        self.draws = draws;
+    }
+
+    // Indices of the objects the last frame drew on any pixel in the columns and rows of
+    // `region`, counted from the top left of a `size` target, by drawing the frame's triangles
+    // again into an object id buffer. Only solid faces count, not wireframe edges.
+    pub fn pick(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: (u32, u32),
+        region: (Range<u32>, Range<u32>),
+    ) -> Vec<usize> {
+        let Some(vertex_buffer) = &self.vertex_buffer else {
+            return Vec::new();
+        };
+        self.pick_pass
+            .pick(device, queue, vertex_buffer, &self.draws, size, region)
+    }
+
+    pub fn get_stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    fn write_lights(&self, queue: &wgpu::Queue, scene: &Scene) {
+        let mut uniform: LightsUniform = bytemuck::Zeroable::zeroed();
+        for (index, light) in scene.get_lights().iter().take(MAX_LIGHTS).enumerate() {
+            uniform.lights[index] = LightUniform {
+                position: light.get_position().to_array(),
+                light_type: light.get_light_type().to_index(),
+                direction: light.get_direction().to_array(),
+                intensity: light.get_intensity(),
+                color: light.get_color().to_array(),
+                range: light.get_range(),
+                shadow_index: self.shadow_pass.get_light_shadow_index(index),
+                _padding: [0.0; 3],
+            };
+            uniform.count = index as u32 + 1;
+        }
+        queue.write_buffer(&self.light_buffer, 0, bytemuck::bytes_of(&uniform));
+    }
+
+    // Uploads one material per object, each in its own aligned slot of a dynamic uniform buffer
+    fn write_materials(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, scene: &Scene) {
+        let objects = scene.get_objects();
+        if objects.is_empty() {
+            return;
+        }
+        let stride = self.material_stride as usize;
+
+        if self.material_buffer.is_none() || self.material_capacity < objects.len() {
+            let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Material Buffer"),
+                size: (stride * objects.len()) as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            self.material_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Material Bind Group"),
+                layout: &self.material_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &buffer,
+                        offset: 0,
+                        size: wgpu::BufferSize::new(std::mem::size_of::<MaterialUniform>() as u64),
+                    }),
+                }],
+            }));
+            self.material_buffer = Some(buffer);
+            self.material_capacity = objects.len();
+        }
+
+        let mut data = vec![0u8; stride * objects.len()];
+        for (index, object) in objects.iter().enumerate() {
+            let uniform = MaterialUniform::from_object(object, scene.get_object_tint(index));
+            let bytes = bytemuck::bytes_of(&uniform);
+            data[index * stride..index * stride + bytes.len()].copy_from_slice(bytes);
+        }
+        if let Some(buffer) = &self.material_buffer {
+            queue.write_buffer(buffer, 0, &data);
+        }
+    }
+
+    // Uploads any new material textures and returns each object's texture bind group key
+    fn prepare_textures(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        scene: &Scene,
+    ) -> Vec<TextureKey> {
+        self.texture_cache.collect_garbage();
+        scene
+            .get_objects()
+            .iter()
+            .map(|object| {
+                self.texture_cache
+                    .prepare(device, queue, object.get_material())
+            })
+            .collect()
+    }
+}
+
+pub fn create_depth_view(device: &wgpu::Device, width: u32, height: u32) -> wgpu::TextureView {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Depth Texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
@@ -0,0 +1,152 @@
+// CPU port of the shading in mesh.frag.wgsl, used by the renderers that don't run on the GPU.
+// Keep the two in sync.
+use crate::core::light::{Light, LightType};
+use crate::core::material::{Material, ShadingModel};
+use glam::{Vec2, Vec3, Vec4, Vec4Swizzles};
+use std::f32::consts::PI;
+
+// Material inputs at a point on a surface, after texturing and normal mapping
+pub struct Surface {
+    pub base_color: Vec3,
+    pub emissive: Vec3,
+    pub normal: Vec3,
+}
+
+impl Surface {
+    // Texture factors multiply the material factors, as in glTF
+    pub fn new(material: &Material, normal: Vec3, tangent: Vec4, uv: Vec2) -> Self {
+        let mut base_color = material.get_base_color().xyz();
+        if let Some(texture) = material.get_base_color_texture() {
+            base_color *= texture.sample(uv, true).xyz();
+        }
+        let mut emissive = material.get_emissive();
+        if let Some(texture) = material.get_emissive_texture() {
+            emissive *= texture.sample(uv, true).xyz();
+        }
+        let normal = match material.get_normal_texture() {
+            Some(texture) => apply_normal_map(
+                normal,
+                tangent,
+                texture.sample(uv, false).xyz(),
+                material.get_normal_scale(),
+            ),
+            None => normal.normalize(),
+        };
+
+        Self {
+            base_color,
+            emissive,
+            normal,
+        }
+    }
+}
+
+// Direction towards the light and the radiance arriving at `position`, before shadowing
+pub fn light_incidence(light: &dyn Light, position: Vec3) -> (Vec3, Vec3) {
+    let mut radiance = light.get_color() * light.get_intensity();
+    match light.get_light_type() {
+        LightType::Point => {
+            let to_light = *light.get_position() - position;
+            let distance = to_light.length();
+            // Inverse square falloff with a smooth cutoff at the range, as in KHR_lights_punctual
+            let cutoff = (1.0 - (distance / light.get_range()).powi(4)).clamp(0.0, 1.0);
+            radiance *= cutoff * cutoff / (distance * distance).max(1e-4);
+            (to_light / distance.max(1e-4), radiance)
+        }
+        LightType::Directional => (-light.get_direction(), radiance),
+    }
+}
+
+// Light reflected towards `v` from one light arriving from `l`
+pub fn shade(material: &Material, surface: &Surface, v: Vec3, l: Vec3, radiance: Vec3) -> Vec3 {
+    match material.get_shading_model() {
+        ShadingModel::Unlit => Vec3::ZERO,
+        ShadingModel::BlinnPhong => shade_blinn_phong(material, surface, v, l, radiance),
+        ShadingModel::Pbr => shade_pbr(material, surface, v, l, radiance),
+    }
+}
+
+// BRDF times the cosine term for light arriving from `l`, for integrating indirect light.
+// Blinn-Phong's diffuse term is base_color * n_dot_l without the 1/pi of a Lambertian BRDF,
+// so it is scaled down here to keep bounces from adding energy.
+pub fn reflectance(material: &Material, surface: &Surface, v: Vec3, l: Vec3) -> Vec3 {
+    match material.get_shading_model() {
+        ShadingModel::Unlit => Vec3::ZERO,
+        ShadingModel::BlinnPhong => shade_blinn_phong(material, surface, v, l, Vec3::ONE) / PI,
+        ShadingModel::Pbr => shade_pbr(material, surface, v, l, Vec3::ONE),
+    }
+}
+
+fn shade_blinn_phong(
+    material: &Material,
+    surface: &Surface,
+    v: Vec3,
+    l: Vec3,
+    radiance: Vec3,
+) -> Vec3 {
+    let n = surface.normal;
+    let n_dot_l = n.dot(l).max(0.0);
+    let diffuse = surface.base_color * n_dot_l;
+    let mut specular = 0.0;
+    if n_dot_l > 0.0 {
+        let h = (l + v).normalize();
+        specular = material.get_specular() * n.dot(h).max(0.0).powf(material.get_shininess());
+    }
+    (diffuse + Vec3::splat(specular)) * radiance
+}
+
+// Cook-Torrance metallic-roughness BRDF following the glTF 2.0 reference implementation
+fn shade_pbr(material: &Material, surface: &Surface, v: Vec3, l: Vec3, radiance: Vec3) -> Vec3 {
+    let n = surface.normal;
+    let n_dot_l = n.dot(l).max(0.0);
+    if n_dot_l <= 0.0 {
+        return Vec3::ZERO;
+    }
+    let h = (l + v).normalize();
+    let n_dot_v = n.dot(v).max(1e-4);
+    let n_dot_h = n.dot(h).max(0.0);
+    let v_dot_h = v.dot(h).max(0.0);
+
+    let metallic = material.get_metallic();
+    let roughness = material.get_roughness().clamp(0.04, 1.0);
+    let alpha = roughness * roughness;
+    let f0 = Vec3::splat(0.04).lerp(surface.base_color, metallic);
+
+    let f = fresnel_schlick(v_dot_h, f0);
+    let specular =
+        f * distribution_ggx(n_dot_h, alpha) * visibility_smith_ggx(n_dot_l, n_dot_v, alpha);
+    let diffuse = (Vec3::ONE - f) * (1.0 - metallic) * surface.base_color / PI;
+
+    (diffuse + specular) * radiance * n_dot_l
+}
+
+// GGX / Trowbridge-Reitz normal distribution
+fn distribution_ggx(n_dot_h: f32, alpha: f32) -> f32 {
+    let alpha2 = alpha * alpha;
+    let d = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    alpha2 / (PI * d * d)
+}
+
+// Height-correlated Smith visibility term (G / (4 * n_dot_l * n_dot_v)), as used by glTF
+fn visibility_smith_ggx(n_dot_l: f32, n_dot_v: f32, alpha: f32) -> f32 {
+    let alpha2 = alpha * alpha;
+    let ggx_v = n_dot_l * (n_dot_v * n_dot_v * (1.0 - alpha2) + alpha2).sqrt();
+    let ggx_l = n_dot_v * (n_dot_l * n_dot_l * (1.0 - alpha2) + alpha2).sqrt();
+    let ggx = ggx_v + ggx_l;
+    if ggx > 0.0 { 0.5 / ggx } else { 0.0 }
+}
+
+fn fresnel_schlick(v_dot_h: f32, f0: Vec3) -> Vec3 {
+    f0 + (Vec3::ONE - f0) * (1.0 - v_dot_h).powi(5)
+}
+
+// Perturbs the interpolated normal with the tangent-space normal map
+fn apply_normal_map(normal: Vec3, tangent: Vec4, sampled: Vec3, scale: f32) -> Vec3 {
+    let n = normal.normalize();
+    let t = (tangent.xyz() - n * n.dot(tangent.xyz())).normalize();
+    let b = n.cross(t) * tangent.w;
+    let mut tangent_normal = sampled * 2.0 - Vec3::ONE;
+    tangent_normal.x *= scale;
+    tangent_normal.y *= scale;
+    (t * tangent_normal.x + b * tangent_normal.y + n * tangent_normal.z).normalize()
+}
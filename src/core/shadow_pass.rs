@@ -0,0 +1,552 @@
+use crate::core::camera::Camera;
+use crate::core::light::LightType;
+use crate::core::light::shadow::{
+    MAX_CASCADES, MAX_SHADOWED_POINT_LIGHTS, POINT_SHADOW_NEAR, compute_cascades,
+    compute_cube_faces,
+};
+use crate::core::scene::Scene;
+
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+// Light view-projection slots: the cascades first, then six cube faces per point light
+const VIEW_COUNT: usize = MAX_CASCADES + MAX_SHADOWED_POINT_LIGHTS * 6;
+
+// Must match the `PointShadow` struct in mesh.frag.wgsl
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PointShadowUniform {
+    near: f32,
+    far: f32,
+    depth_bias: f32,
+    normal_bias: f32,
+    texel_scale: f32, // size of a cube map texel at unit distance from the light
+    pcf_radius: u32,
+    _padding: [f32; 2],
+}
+
+// Must match the `Shadow` struct in mesh.frag.wgsl
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShadowUniform {
+    cascades: [[f32; 16]; MAX_CASCADES],
+    splits: [f32; MAX_CASCADES],
+    texel_sizes: [f32; MAX_CASCADES],
+    cascade_count: u32, // zero when no directional light casts shadows
+    depth_bias: f32,
+    normal_bias: f32,
+    pcf_radius: u32,
+    point_shadows: [PointShadowUniform; MAX_SHADOWED_POINT_LIGHTS],
+}
+
+// A depth texture with one render view per layer and one view that samples all of them
+struct ShadowMap {
+    sample_view: wgpu::TextureView,
+    layer_views: Vec<wgpu::TextureView>,
+    resolution: u32,
+}
+
+impl ShadowMap {
+    // A 2D array with a layer per cascade, or a cube map when `cube` is set
+    fn new(device: &wgpu::Device, resolution: u32, cube: bool) -> Self {
+        let layers = if cube { 6 } else { MAX_CASCADES as u32 };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(if cube {
+                "Point Shadow Map"
+            } else {
+                "Shadow Map"
+            }),
+            size: wgpu::Extent3d {
+                width: resolution,
+                height: resolution,
+                depth_or_array_layers: layers,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        let sample_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Shadow Map Sample View"),
+            dimension: Some(if cube {
+                wgpu::TextureViewDimension::Cube
+            } else {
+                wgpu::TextureViewDimension::D2Array
+            }),
+            ..Default::default()
+        });
+        let layer_views = (0..layers)
+            .map(|layer| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("Shadow Map Layer View"),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Self {
+            sample_view,
+            layer_views,
+            resolution,
+        }
+    }
+}
+
+// Renders shadow casters into a cascaded depth map for the first shadowed directional light
+// and a cube map per shadowed point light, and exposes the maps to mesh.frag.wgsl as bind
+// group 3
+pub struct ShadowPass {
+    pipeline: wgpu::RenderPipeline,
+    view_bind_group: wgpu::BindGroup,
+    view_buffer: wgpu::Buffer,
+    view_stride: u64,
+    uniform_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    cascade_map: ShadowMap,
+    point_maps: Vec<ShadowMap>,
+    vertex_buffer: Option<wgpu::Buffer>,
+    vertex_capacity: usize,
+    vertex_count: u32,
+    cascade_count: u32,
+    point_count: usize,
+    light_shadow_indices: Vec<i32>,
+}
+
+impl ShadowPass {
+    pub fn new(device: &wgpu::Device) -> Self {
+        // One light view-projection matrix per rendered view, selected with a dynamic offset
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let view_stride = (std::mem::size_of::<[f32; 16]>() as u64).div_ceil(alignment) * alignment;
+        let view_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow View Buffer"),
+            size: view_stride * VIEW_COUNT as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let view_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Shadow View Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(
+                            std::mem::size_of::<[f32; 16]>() as u64
+                        ),
+                    },
+                    count: None,
+                }],
+            });
+        let view_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow View Bind Group"),
+            layout: &view_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &view_buffer,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(std::mem::size_of::<[f32; 16]>() as u64),
+                }),
+            }],
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow Buffer"),
+            size: std::mem::size_of::<ShadowUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let mut entries = vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Depth,
+                    view_dimension: wgpu::TextureViewDimension::D2Array,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                count: None,
+            },
+        ];
+        // One binding per point light slot rather than a cube array, so every adapter
+        // supports it and each light keeps its own resolution
+        for slot in 0..MAX_SHADOWED_POINT_LIGHTS as u32 {
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 3 + slot,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Depth,
+                    view_dimension: wgpu::TextureViewDimension::Cube,
+                    multisampled: false,
+                },
+                count: None,
+            });
+        }
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Bind Group Layout"),
+            entries: &entries,
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shadow Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/shadow.vert.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&view_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shadow Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 3]>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x3,
+                    }],
+                }],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: None,
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                // Slope-scaled bias against acne on surfaces at grazing angles to the light
+                bias: wgpu::DepthBiasState {
+                    constant: 2,
+                    slope_scale: 2.0,
+                    clamp: 0.0,
+                },
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        // Start with tiny maps; they are resized to each light's resolution on first use
+        let cascade_map = ShadowMap::new(device, 1, false);
+        let point_maps: Vec<ShadowMap> = (0..MAX_SHADOWED_POINT_LIGHTS)
+            .map(|_| ShadowMap::new(device, 1, true))
+            .collect();
+        let bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            &uniform_buffer,
+            &sampler,
+            &cascade_map,
+            &point_maps,
+        );
+
+        Self {
+            pipeline,
+            view_bind_group,
+            view_buffer,
+            view_stride,
+            uniform_buffer,
+            sampler,
+            bind_group_layout,
+            bind_group,
+            cascade_map,
+            point_maps,
+            vertex_buffer: None,
+            vertex_capacity: 0,
+            vertex_count: 0,
+            cascade_count: 0,
+            point_count: 0,
+            light_shadow_indices: Vec::new(),
+        }
+    }
+
+    pub fn get_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.bind_group_layout
+    }
+
+    pub fn get_bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    // Shadow slot of the scene's light at `index`: 0 for the directional light that owns the
+    // cascades, the cube map slot for point lights, and -1 when the light is unshadowed
+    pub fn get_light_shadow_index(&self, index: usize) -> i32 {
+        self.light_shadow_indices.get(index).copied().unwrap_or(-1)
+    }
+
+    // Picks the shadowed lights, fits their projections and uploads the shadow casters
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        scene: &Scene,
+        camera: &Camera,
+    ) {
+        let lights = scene.get_lights();
+        self.light_shadow_indices = vec![-1; lights.len()];
+        let max_resolution = device.limits().max_texture_dimension_2d;
+        let mut maps_changed = false;
+
+        // World-space positions of every triangle of every shadow caster
+        let mut casters: Vec<[f32; 3]> = Vec::new();
+        if lights
+            .iter()
+            .any(|light| light.get_shadow_settings().is_some())
+        {
+            let alpha = scene.get_interpolation_alpha();
+            for object in scene.get_objects() {
+                if !object.get_cast_shadows() {
+                    continue;
+                }
+                for tri in object.get_mesh().get_triangles() {
+                    for vertex in object
+                        .interpolated_triangle(tri.clone(), alpha)
+                        .get_vertices()
+                    {
+                        casters.push(vertex.to_array());
+                    }
+                }
+            }
+        }
+
+        let mut uniform: ShadowUniform = bytemuck::Zeroable::zeroed();
+        let mut views = vec![[0.0f32; 16]; VIEW_COUNT];
+
+        // Cascades for the first directional light that casts shadows
+        let directional = lights.iter().enumerate().find(|(_, light)| {
+            light.get_light_type() == LightType::Directional
+                && light.get_shadow_settings().is_some()
+        });
+        self.cascade_count = 0;
+        if let Some((index, light)) = directional {
+            let settings = *light.get_shadow_settings().unwrap();
+            let resolution = settings.resolution.clamp(1, max_resolution);
+            if resolution != self.cascade_map.resolution {
+                self.cascade_map = ShadowMap::new(device, resolution, false);
+                maps_changed = true;
+            }
+
+            let cascades = compute_cascades(camera, light.get_direction(), &settings, &casters);
+            for (i, cascade) in cascades.iter().enumerate() {
+                let matrix = cascade.view_projection.to_cols_array();
+                uniform.cascades[i] = matrix;
+                uniform.splits[i] = cascade.far;
+                uniform.texel_sizes[i] = cascade.texel_size;
+                views[i] = matrix;
+            }
+            self.cascade_count = cascades.len() as u32;
+            uniform.cascade_count = self.cascade_count;
+            uniform.depth_bias = settings.depth_bias;
+            uniform.normal_bias = settings.normal_bias;
+            uniform.pcf_radius = settings.pcf_radius;
+            self.light_shadow_indices[index] = 0;
+        }
+
+        // Cube maps for the shadowed point lights closest to the camera
+        let mut points: Vec<usize> = (0..lights.len())
+            .filter(|&index| {
+                lights[index].get_light_type() == LightType::Point
+                    && lights[index].get_shadow_settings().is_some()
+            })
+            .collect();
+        let camera_position = camera.get_position();
+        points.sort_by(|&a, &b| {
+            let distance_a = lights[a].get_position().distance_squared(camera_position);
+            let distance_b = lights[b].get_position().distance_squared(camera_position);
+            distance_a.total_cmp(&distance_b)
+        });
+        points.truncate(MAX_SHADOWED_POINT_LIGHTS);
+        self.point_count = points.len();
+
+        for (slot, &index) in points.iter().enumerate() {
+            let light = &lights[index];
+            let settings = *light.get_shadow_settings().unwrap();
+            let resolution = settings.resolution.clamp(1, max_resolution);
+            if resolution != self.point_maps[slot].resolution {
+                self.point_maps[slot] = ShadowMap::new(device, resolution, true);
+                maps_changed = true;
+            }
+
+            let faces = compute_cube_faces(*light.get_position(), light.get_range());
+            for (face, matrix) in faces.iter().enumerate() {
+                views[MAX_CASCADES + slot * 6 + face] = matrix.to_cols_array();
+            }
+            uniform.point_shadows[slot] = PointShadowUniform {
+                near: POINT_SHADOW_NEAR,
+                far: light.get_range(),
+                depth_bias: settings.depth_bias,
+                normal_bias: settings.normal_bias,
+                texel_scale: 2.0 / resolution as f32,
+                pcf_radius: settings.pcf_radius,
+                _padding: [0.0; 2],
+            };
+            self.light_shadow_indices[index] = slot as i32;
+        }
+
+        if maps_changed {
+            self.bind_group = create_bind_group(
+                device,
+                &self.bind_group_layout,
+                &self.uniform_buffer,
+                &self.sampler,
+                &self.cascade_map,
+                &self.point_maps,
+            );
+        }
+
+        let stride = self.view_stride as usize;
+        let mut view_data = vec![0u8; stride * VIEW_COUNT];
+        for (i, matrix) in views.iter().enumerate() {
+            let bytes = bytemuck::cast_slice::<f32, u8>(matrix);
+            view_data[i * stride..i * stride + bytes.len()].copy_from_slice(bytes);
+        }
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+        queue.write_buffer(&self.view_buffer, 0, &view_data);
+
+        self.vertex_count = casters.len() as u32;
+        if casters.is_empty() {
+            return;
+        }
+        if self.vertex_buffer.is_none() || self.vertex_capacity < casters.len() {
+            self.vertex_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Shadow Vertex Buffer"),
+                size: (std::mem::size_of::<[f32; 3]>() * casters.len()) as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+            self.vertex_capacity = casters.len();
+        }
+        if let Some(buffer) = &self.vertex_buffer {
+            queue.write_buffer(buffer, 0, bytemuck::cast_slice(&casters));
+        }
+    }
+
+    // Records one depth-only pass per cascade and per point light cube face
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder) {
+        let mut targets = Vec::new();
+        for cascade in 0..self.cascade_count as usize {
+            targets.push((cascade, &self.cascade_map.layer_views[cascade]));
+        }
+        for slot in 0..self.point_count {
+            for face in 0..6 {
+                targets.push((
+                    MAX_CASCADES + slot * 6 + face,
+                    &self.point_maps[slot].layer_views[face],
+                ));
+            }
+        }
+
+        for (view_index, target) in targets {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shadow Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: target,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            if let (Some(buffer), true) = (&self.vertex_buffer, self.vertex_count > 0) {
+                let offset = (view_index as u64 * self.view_stride) as u32;
+                render_pass.set_pipeline(&self.pipeline);
+                render_pass.set_bind_group(0, &self.view_bind_group, &[offset]);
+                render_pass.set_vertex_buffer(0, buffer.slice(..));
+                render_pass.draw(0..self.vertex_count, 0..1);
+            }
+        }
+    }
+}
+
+// Creates the bind group that samples the cascade array and every point light cube map
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    uniform_buffer: &wgpu::Buffer,
+    sampler: &wgpu::Sampler,
+    cascade_map: &ShadowMap,
+    point_maps: &[ShadowMap],
+) -> wgpu::BindGroup {
+    let mut entries = vec![
+        wgpu::BindGroupEntry {
+            binding: 0,
+            resource: uniform_buffer.as_entire_binding(),
+        },
+        wgpu::BindGroupEntry {
+            binding: 1,
+            resource: wgpu::BindingResource::TextureView(&cascade_map.sample_view),
+        },
+        wgpu::BindGroupEntry {
+            binding: 2,
+            resource: wgpu::BindingResource::Sampler(sampler),
+        },
+    ];
+    for (slot, point_map) in point_maps.iter().enumerate() {
+        entries.push(wgpu::BindGroupEntry {
+            binding: 3 + slot as u32,
+            resource: wgpu::BindingResource::TextureView(&point_map.sample_view),
+        });
+    }
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Shadow Bind Group"),
+        layout,
+        entries: &entries,
+    })
+}
@@ -0,0 +1,374 @@
+use crate::core::camera::Camera;
+use crate::core::material::ShadingModel;
+use crate::core::render_backend::RenderBackend;
+use crate::core::render_mode::wireframe_lines;
+use crate::core::render_stats::RenderStats;
+use crate::core::scene::Scene;
+use crate::core::shading::{Surface, light_incidence, shade};
+use crate::core::texture::linear_to_srgb;
+use glam::{Vec2, Vec3, Vec4};
+use image::RgbaImage;
+
+// Everything a triangle corner carries through clipping and rasterization
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    position: Vec4, // clip-space position
+    world_position: Vec3,
+    uv: Vec2,
+    tangent: Vec4,
+}
+
+impl ClipVertex {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            position: self.position.lerp(other.position, t),
+            world_position: self.world_position.lerp(other.world_position, t),
+            uv: self.uv.lerp(other.uv, t),
+            tangent: self.tangent.lerp(other.tangent, t),
+        }
+    }
+}
+
+// Pure-Rust rasterizer that needs no GPU. It does the same transform, culling, projection
+// and shading as the wgpu path, with edge functions, perspective-correct interpolation and
+// a depth buffer. Shadows aren't rendered.
+pub struct SoftwareRenderer {
+    width: u32,
+    height: u32,
+    color: Vec<Vec3>, // linear color
+    depth: Vec<f32>,
+    ids: Vec<u32>, // index plus one of the object drawn at each pixel, zero where none is
+    stats: RenderStats,
+}
+
+impl SoftwareRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+
+        let pixel_count = (width * height) as usize;
+        Self {
+            width,
+            height,
+            color: vec![Vec3::ZERO; pixel_count],
+            depth: vec![1.0; pixel_count],
+            ids: vec![0; pixel_count],
+            stats: RenderStats::default(),
+        }
+    }
+
+    pub fn get_stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    fn rasterize(
+        &mut self,
+        scene: &Scene,
+        camera: &Camera,
+        object: usize,
+        normal: Vec3,
+        corners: [ClipVertex; 3],
+    ) {
+        let material = scene.get_objects()[object].get_material();
+        let tint = scene.get_object_tint(object);
+        let size = Vec2::new(self.width as f32, self.height as f32);
+
+        // Perspective divide and viewport transform; y points down in the framebuffer
+        let screen = corners.map(|corner| {
+            let ndc = corner.position.truncate() / corner.position.w;
+            Vec3::new(
+                (ndc.x * 0.5 + 0.5) * size.x,
+                (0.5 - ndc.y * 0.5) * size.y,
+                ndc.z,
+            )
+        });
+        let area = edge(screen[0], screen[1], screen[2]);
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+
+        let min = screen[0].min(screen[1]).min(screen[2]);
+        let max = screen[0].max(screen[1]).max(screen[2]);
+        let x_start = min.x.floor().max(0.0) as u32;
+        let y_start = min.y.floor().max(0.0) as u32;
+        let x_end = (max.x.ceil() as u32).min(self.width);
+        let y_end = (max.y.ceil() as u32).min(self.height);
+
+        let inverse_w = corners.map(|corner| 1.0 / corner.position.w);
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                let pixel = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+                // Barycentric weights, positive inside for either winding
+                let weights = Vec3::new(
+                    edge(screen[1], screen[2], pixel),
+                    edge(screen[2], screen[0], pixel),
+                    edge(screen[0], screen[1], pixel),
+                ) / area;
+                if weights.min_element() < 0.0 {
+                    continue;
+                }
+
+                // Depth is interpolated linearly in screen space, like the GPU does
+                let depth = weights.dot(Vec3::new(screen[0].z, screen[1].z, screen[2].z));
+                let index = (y * self.width + x) as usize;
+                if !(0.0..=1.0).contains(&depth) || depth >= self.depth[index] {
+                    continue;
+                }
+
+                // Perspective-correct weights for the vertex attributes
+                let perspective = weights * Vec3::from_array(inverse_w);
+                let perspective = perspective / (perspective.x + perspective.y + perspective.z);
+                let world_position = corners[0].world_position * perspective.x
+                    + corners[1].world_position * perspective.y
+                    + corners[2].world_position * perspective.z;
+                let uv = corners[0].uv * perspective.x
+                    + corners[1].uv * perspective.y
+                    + corners[2].uv * perspective.z;
+                let tangent = corners[0].tangent * perspective.x
+                    + corners[1].tangent * perspective.y
+                    + corners[2].tangent * perspective.z;
+
+                let surface = Surface::new(material, normal, tangent, uv);
+                let v = (camera.get_position() - world_position).normalize();
+                let mut color = surface.emissive;
+                if material.get_shading_model() == ShadingModel::Unlit {
+                    color += surface.base_color;
+                } else {
+                    for light in scene.get_lights() {
+                        let (l, radiance) = light_incidence(light.as_ref(), world_position);
+                        color += shade(material, &surface, v, l, radiance);
+                    }
+                }
+
+                self.depth[index] = depth;
+                self.color[index] = color.lerp(tint.truncate(), tint.w);
+                self.ids[index] = object as u32 + 1;
+            }
+        }
+    }
+
+    // One pixel wide line between two clip-space points, depth tested against the faces
+    // without writing depth
+    fn draw_line(&mut self, start: Vec4, end: Vec4, color: Vec3) {
+        let Some((start, end)) = clip_line(start, end) else {
+            return;
+        };
+        let size = Vec2::new(self.width as f32, self.height as f32);
+        let [start, end] = [start, end].map(|point| {
+            let ndc = point.truncate() / point.w;
+            Vec3::new(
+                (ndc.x * 0.5 + 0.5) * size.x,
+                (0.5 - ndc.y * 0.5) * size.y,
+                ndc.z,
+            )
+        });
+
+        // One step per pixel along the longer axis; depth is linear in screen space
+        let steps = (end.x - start.x).abs().max((end.y - start.y).abs()).ceil() as u32;
+        for step in 0..=steps {
+            let point = start.lerp(end, step as f32 / steps.max(1) as f32);
+            if point.x < 0.0 || point.y < 0.0 || point.x >= size.x || point.y >= size.y {
+                continue;
+            }
+            let index = (point.y as u32 * self.width + point.x as u32) as usize;
+            if point.z <= self.depth[index] {
+                self.color[index] = color;
+            }
+        }
+    }
+
+    // Colors the pixels within the outline's thickness of a selected object's visible pixels,
+    // like the GPU's outline pass
+    fn draw_outline(&mut self, scene: &Scene) {
+        let thickness = scene.get_outline_thickness();
+        let color = scene.get_outline_color();
+        if thickness <= 0.0 || color.w <= 0.0 || scene.get_selected_objects().is_empty() {
+            return;
+        }
+        let mask: Vec<bool> = self
+            .ids
+            .iter()
+            .map(|&id| id > 0 && scene.is_selected(id as usize - 1))
+            .collect();
+        let (width, height) = (self.width as i32, self.height as i32);
+        let radius = thickness.ceil() as i32;
+        for y in 0..height {
+            for x in 0..width {
+                let index = (y * width + x) as usize;
+                if mask[index] {
+                    continue;
+                }
+                let near = (-radius..=radius).any(|dy| {
+                    (-radius..=radius).any(|dx| {
+                        let (nx, ny) = (x + dx, y + dy);
+                        (dx * dx + dy * dy) as f32 <= thickness * thickness
+                            && (0..width).contains(&nx)
+                            && (0..height).contains(&ny)
+                            && mask[(ny * width + nx) as usize]
+                    })
+                });
+                if near {
+                    self.color[index] = self.color[index].lerp(color.truncate(), color.w);
+                }
+            }
+        }
+    }
+}
+
+impl RenderBackend for SoftwareRenderer {
+    fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    fn render(&mut self, scene: &Scene, camera: &Camera) -> RgbaImage {
+        self.color.fill(Vec3::ZERO);
+        self.depth.fill(1.0);
+        self.ids.fill(0);
+        let camera = &camera.with_image_size(self.width, self.height);
+
+        // Objects are drawn between their last two fixed steps, like on the GPU. Those entirely
+        // outside the view there are skipped before looking at their triangles.
+        let alpha = scene.get_interpolation_alpha();
+        let frustum = camera.get_frustum();
+        let objects = scene.get_objects();
+        let visible: Vec<usize> = (0..objects.len())
+            .filter(|&index| {
+                frustum.intersects_aabb(&objects[index].get_interpolated_world_aabb(alpha))
+            })
+            .collect();
+        self.stats = RenderStats {
+            drawn_objects: visible.len() as u32,
+            culled_objects: (scene.get_objects().len() - visible.len()) as u32,
+            drawn_triangles: 0,
+        };
+
+        for &index in &visible {
+            let object = &objects[index];
+            if !scene.get_object_render_mode(object).draws_solid() {
+                continue;
+            }
+            for tri in object.get_mesh().get_triangles() {
+                let transformed_tri = object.interpolated_triangle(tri.clone(), alpha);
+
+                // culling
+                if transformed_tri
+                    .get_normal()
+                    .dot(transformed_tri.get_vertices()[0] - camera.get_position())
+                    >= 0.0
+                {
+                    continue;
+                }
+
+                self.stats.drawn_triangles += 1;
+                let world = transformed_tri.get_vertices();
+                let uvs = transformed_tri.get_uvs();
+                let tangents = transformed_tri.get_tangents();
+                let corners: [ClipVertex; 3] = std::array::from_fn(|i| ClipVertex {
+                    position: camera.project_point_clip(world[i]),
+                    world_position: world[i],
+                    uv: uvs[i],
+                    tangent: tangents[i],
+                });
+
+                // Fan-triangulate what is left after clipping
+                let polygon = clip_polygon(&corners);
+                for i in 1..polygon.len().saturating_sub(1) {
+                    self.rasterize(
+                        scene,
+                        camera,
+                        index,
+                        transformed_tri.get_normal(),
+                        [polygon[0], polygon[i], polygon[i + 1]],
+                    );
+                }
+            }
+        }
+
+        // Wireframe edges go on top of all the shaded faces, like the GPU's line pass
+        for &index in &visible {
+            let object = &objects[index];
+            if !scene.get_object_render_mode(object).draws_wireframe() {
+                continue;
+            }
+            for line in wireframe_lines(object, camera, alpha).chunks_exact(2) {
+                self.draw_line(line[0], line[1], scene.get_line_color());
+            }
+        }
+        for (start, end, color) in scene.get_debug_draw().get_lines() {
+            self.draw_line(
+                camera.project_point_clip(start),
+                camera.project_point_clip(end),
+                color,
+            );
+        }
+        self.draw_outline(scene);
+
+        let mut pixels = Vec::with_capacity(self.color.len() * 4);
+        for color in &self.color {
+            for channel in color.to_array() {
+                let encoded = linear_to_srgb(channel.clamp(0.0, 1.0));
+                pixels.push((encoded * 255.0).round() as u8);
+            }
+            pixels.push(255);
+        }
+        RgbaImage::from_raw(self.width, self.height, pixels).unwrap()
+    }
+}
+
+// Twice the signed area of the triangle (a, b, c) in screen space
+fn edge(a: Vec3, b: Vec3, c: Vec3) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+// Clips a line against the near and far planes of clip space, like clip_polygon
+fn clip_line(mut start: Vec4, mut end: Vec4) -> Option<(Vec4, Vec4)> {
+    let planes: [fn(Vec4) -> f32; 2] = [|p| p.z, |p| p.w - p.z];
+    for plane in planes {
+        let start_distance = plane(start);
+        let end_distance = plane(end);
+        if start_distance < 0.0 && end_distance < 0.0 {
+            return None;
+        }
+        let t = start_distance / (start_distance - end_distance);
+        if start_distance < 0.0 {
+            start = start.lerp(end, t);
+        } else if end_distance < 0.0 {
+            end = start.lerp(end, t);
+        }
+    }
+    Some((start, end))
+}
+
+// Clips a triangle against the near (z >= 0) and far (z <= w) planes of clip space. The
+// sides aren't clipped; rasterization is limited to the framebuffer instead.
+fn clip_polygon(triangle: &[ClipVertex; 3]) -> Vec<ClipVertex> {
+    let planes: [fn(Vec4) -> f32; 2] = [|p| p.z, |p| p.w - p.z];
+
+    let mut polygon = triangle.to_vec();
+    for plane in planes {
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for i in 0..polygon.len() {
+            let current = polygon[i];
+            let next = polygon[(i + 1) % polygon.len()];
+            let current_distance = plane(current.position);
+            let next_distance = plane(next.position);
+
+            if current_distance >= 0.0 {
+                clipped.push(current);
+            }
+            if (current_distance >= 0.0) != (next_distance >= 0.0) {
+                let t = current_distance / (current_distance - next_distance);
+                clipped.push(current.lerp(next, t));
+            }
+        }
+        polygon = clipped;
+        if polygon.is_empty() {
+            break;
+        }
+    }
+    polygon
+}
@@ -0,0 +1,192 @@
+use glam::{Vec2, Vec4};
+use image::error::{ParameterError, ParameterErrorKind};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterMode {
+    Nearest,
+    Linear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sampler {
+    pub filter: FilterMode,
+    pub address_mode: AddressMode,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self {
+            filter: FilterMode::Linear,
+            address_mode: AddressMode::Repeat,
+        }
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// An RGBA8 image kept on the CPU. The renderer uploads it to the GPU the first time a
+// material that references it is drawn.
+#[derive(Clone)]
+pub struct Texture {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+    sampler: Sampler,
+}
+
+impl Texture {
+    // Fails when either side is zero, so sampling always has a texel to read, or when `data`
+    // doesn't hold exactly width * height RGBA8 pixels
+    pub fn from_rgba8(width: u32, height: u32, data: Vec<u8>) -> Result<Self, image::ImageError> {
+        if width == 0 || height == 0 {
+            return Err(parameter_error(ParameterErrorKind::Generic(format!(
+                "texture is {width}x{height}, but needs at least one pixel"
+            ))));
+        }
+        let expected = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(4));
+        if expected != Some(data.len()) {
+            return Err(parameter_error(ParameterErrorKind::DimensionMismatch));
+        }
+        Ok(Self {
+            width,
+            height,
+            data,
+            sampler: Sampler::new(),
+        })
+    }
+
+    // A single pixel texture, used as a neutral stand-in for empty material slots
+    pub fn solid(color: [u8; 4]) -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            data: color.to_vec(),
+            sampler: Sampler::new(),
+        }
+    }
+
+    // Decodes a PNG, JPEG or TGA image from memory
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.to_rgba8();
+        Self::from_rgba8(image.width(), image.height(), image.into_raw())
+    }
+
+    // Decodes a PNG, JPEG or TGA image file, picking the format from its extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgba8();
+        Self::from_rgba8(image.width(), image.height(), image.into_raw())
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn get_sampler(&self) -> Sampler {
+        self.sampler
+    }
+
+    pub fn set_sampler(&mut self, sampler: Sampler) {
+        self.sampler = sampler;
+    }
+
+    // Samples the texture on the CPU the way the GPU sampler would: texel centers at half
+    // coordinates, no mipmaps. With `srgb` the texels are decoded to linear before filtering,
+    // like an Rgba8UnormSrgb texture.
+    pub fn sample(&self, uv: Vec2, srgb: bool) -> Vec4 {
+        let size = Vec2::new(self.width as f32, self.height as f32);
+        match self.sampler.filter {
+            FilterMode::Nearest => {
+                let texel = (uv * size).floor();
+                self.texel(texel.x as i64, texel.y as i64, srgb)
+            }
+            FilterMode::Linear => {
+                let position = uv * size - Vec2::splat(0.5);
+                let base = position.floor();
+                let fraction = position - base;
+                let (x, y) = (base.x as i64, base.y as i64);
+                let top = self
+                    .texel(x, y, srgb)
+                    .lerp(self.texel(x + 1, y, srgb), fraction.x);
+                let bottom = self
+                    .texel(x, y + 1, srgb)
+                    .lerp(self.texel(x + 1, y + 1, srgb), fraction.x);
+                top.lerp(bottom, fraction.y)
+            }
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64, srgb: bool) -> Vec4 {
+        let x = wrap(x, self.width as i64, self.sampler.address_mode);
+        let y = wrap(y, self.height as i64, self.sampler.address_mode);
+        let index = ((y * self.width as i64 + x) * 4) as usize;
+        let pixel = &self.data[index..index + 4];
+        let channel = |value: u8| {
+            let value = value as f32 / 255.0;
+            if srgb { srgb_to_linear(value) } else { value }
+        };
+        // Alpha is never sRGB encoded
+        Vec4::new(
+            channel(pixel[0]),
+            channel(pixel[1]),
+            channel(pixel[2]),
+            pixel[3] as f32 / 255.0,
+        )
+    }
+}
+
+fn wrap(coordinate: i64, size: i64, address_mode: AddressMode) -> i64 {
+    match address_mode {
+        AddressMode::Repeat => coordinate.rem_euclid(size),
+        AddressMode::MirroredRepeat => {
+            let period = coordinate.rem_euclid(size * 2);
+            if period < size {
+                period
+            } else {
+                size * 2 - 1 - period
+            }
+        }
+        AddressMode::ClampToEdge => coordinate.clamp(0, size - 1),
+    }
+}
+
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn parameter_error(kind: ParameterErrorKind) -> image::ImageError {
+    image::ImageError::Parameter(ParameterError::from_kind(kind))
+}
@@ -0,0 +1,208 @@
+use crate::core::material::Material;
+use crate::core::texture::{AddressMode, FilterMode, Texture};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Number of texture slots a material exposes to mesh.frag.wgsl (group 2), in binding order
+const SLOT_COUNT: usize = 3;
+
+pub type TextureKey = [usize; SLOT_COUNT];
+
+struct GpuTexture {
+    // Keeps the pointer used as cache key alive for as long as the upload exists
+    source: Arc<Texture>,
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    sampler: wgpu::Sampler,
+}
+
+// Uploads material textures on first use and shares the resulting bind groups between
+// every object that uses the same combination of textures
+pub struct TextureCache {
+    bind_group_layout: wgpu::BindGroupLayout,
+    white: Arc<Texture>,
+    flat_normal: Arc<Texture>,
+    textures: HashMap<(usize, bool), GpuTexture>,
+    bind_groups: HashMap<TextureKey, wgpu::BindGroup>,
+}
+
+impl TextureCache {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let mut entries = Vec::new();
+        for slot in 0..SLOT_COUNT as u32 {
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: slot * 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            });
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: slot * 2 + 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            });
+        }
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Texture Bind Group Layout"),
+            entries: &entries,
+        });
+
+        Self {
+            bind_group_layout,
+            white: Arc::new(Texture::solid([255, 255, 255, 255])),
+            flat_normal: Arc::new(Texture::solid([128, 128, 255, 255])),
+            textures: HashMap::new(),
+            bind_groups: HashMap::new(),
+        }
+    }
+
+    pub fn get_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.bind_group_layout
+    }
+
+    // Makes sure every texture of `material` is on the GPU and returns the key of its bind group
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        material: &Material,
+    ) -> TextureKey {
+        // (texture, is sRGB) per slot; empty slots sample a neutral pixel
+        let slots: [(Arc<Texture>, bool); SLOT_COUNT] = [
+            (
+                slot_or(material.get_base_color_texture(), &self.white),
+                true,
+            ),
+            (slot_or(material.get_emissive_texture(), &self.white), true),
+            (
+                slot_or(material.get_normal_texture(), &self.flat_normal),
+                false,
+            ),
+        ];
+
+        let mut key = [0; SLOT_COUNT];
+        for (index, (texture, srgb)) in slots.iter().enumerate() {
+            let pointer = Arc::as_ptr(texture) as usize;
+            self.textures
+                .entry((pointer, *srgb))
+                .or_insert_with(|| upload(device, queue, texture, *srgb));
+            key[index] = pointer;
+        }
+
+        if !self.bind_groups.contains_key(&key) {
+            let mut entries = Vec::new();
+            for (slot, (texture, srgb)) in slots.iter().enumerate() {
+                let gpu_texture = &self.textures[&(Arc::as_ptr(texture) as usize, *srgb)];
+                entries.push(wgpu::BindGroupEntry {
+                    binding: slot as u32 * 2,
+                    resource: wgpu::BindingResource::TextureView(&gpu_texture.view),
+                });
+                entries.push(wgpu::BindGroupEntry {
+                    binding: slot as u32 * 2 + 1,
+                    resource: wgpu::BindingResource::Sampler(&gpu_texture.sampler),
+                });
+            }
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Texture Bind Group"),
+                layout: &self.bind_group_layout,
+                entries: &entries,
+            });
+            self.bind_groups.insert(key, bind_group);
+        }
+
+        key
+    }
+
+    pub fn get_bind_group(&self, key: &TextureKey) -> &wgpu::BindGroup {
+        &self.bind_groups[key]
+    }
+
+    // Drops uploads whose texture is no longer referenced by any material
+    pub fn collect_garbage(&mut self) {
+        let before = self.textures.len();
+        self.textures
+            .retain(|_, gpu_texture| Arc::strong_count(&gpu_texture.source) > 1);
+        if self.textures.len() != before {
+            self.bind_groups.clear();
+        }
+    }
+}
+
+fn slot_or(texture: Option<&Arc<Texture>>, fallback: &Arc<Texture>) -> Arc<Texture> {
+    texture.unwrap_or(fallback).clone()
+}
+
+fn upload(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    source: &Arc<Texture>,
+    srgb: bool,
+) -> GpuTexture {
+    let size = wgpu::Extent3d {
+        width: source.get_width(),
+        height: source.get_height(),
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Material Texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: if srgb {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        },
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        source.get_data(),
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * source.get_width()),
+            rows_per_image: Some(source.get_height()),
+        },
+        size,
+    );
+
+    let sampler_settings = source.get_sampler();
+    let filter = match sampler_settings.filter {
+        FilterMode::Nearest => wgpu::FilterMode::Nearest,
+        FilterMode::Linear => wgpu::FilterMode::Linear,
+    };
+    let address_mode = match sampler_settings.address_mode {
+        AddressMode::Repeat => wgpu::AddressMode::Repeat,
+        AddressMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+        AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+    };
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("Material Sampler"),
+        address_mode_u: address_mode,
+        address_mode_v: address_mode,
+        address_mode_w: address_mode,
+        mag_filter: filter,
+        min_filter: filter,
+        ..Default::default()
+    });
+
+    GpuTexture {
+        source: source.clone(),
+        view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+        _texture: texture,
+        sampler,
+    }
+}
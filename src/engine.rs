@@ -1,7 +1,9 @@
 use crate::core::camera::Camera;
+use crate::core::render_backend::RenderBackend;
 use crate::core::renderer::Renderer;
 use crate::core::scene::Scene;
 use pollster::block_on;
+use std::path::Path;
 
 pub struct Engine {
     scene: Scene,
@@ -13,6 +15,14 @@ impl Engine {
         Engine { scene, camera }
     }
 
+    pub fn get_scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    pub fn get_camera(&self) -> &Camera {
+        &self.camera
+    }
+
     pub fn run(self) {
         block_on(self.run_async());
     }
@@ -21,4 +31,13 @@ impl Engine {
         let (renderer, event_loop) = Renderer::new(self.scene, self.camera).await;
         renderer.run(event_loop);
     }
+
+    // Renders a single frame with an offscreen backend instead of opening a window
+    pub fn render_to_png(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        path: impl AsRef<Path>,
+    ) -> Result<(), image::ImageError> {
+        renderer.render_to_png(&self.scene, &self.camera, path.as_ref())
+    }
 }
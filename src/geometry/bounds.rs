@@ -0,0 +1,150 @@
+use crate::geometry::ray::Ray;
+use glam::{Mat3, Vec3};
+
+// 1 + 2γ₃ from PBRT's robust ray-box test, which bounds the rounding error in the slab test
+const SLAB_ROUNDING: f32 =
+    1.0 + 2.0 * (3.0 * f32::EPSILON * 0.5) / (1.0 - 3.0 * f32::EPSILON * 0.5);
+
+// Axis-aligned bounding box
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    // Smallest box holding every point; a zero-size box at the origin when there are none
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Self {
+        let mut points = points.into_iter();
+        let Some(first) = points.next() else {
+            return Self::new(Vec3::ZERO, Vec3::ZERO);
+        };
+        points.fold(Self::new(first, first), |aabb, point| {
+            Self::new(aabb.min.min(point), aabb.max.max(point))
+        })
+    }
+
+    pub fn get_center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    // Half the size along each axis
+    pub fn get_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn get_size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn get_surface_area(&self) -> f32 {
+        let size = self.get_size();
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    pub fn get_corners(&self) -> [Vec3; 8] {
+        std::array::from_fn(|i| {
+            Vec3::new(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+            )
+        })
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point.clamp(self.min, self.max)
+    }
+
+    // Zero inside the box
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    // Distance along the ray to where it enters the box, zero when it starts inside
+    pub fn intersect_ray(&self, ray: &Ray, max_distance: f32) -> Option<f32> {
+        self.intersect_ray_inverse(ray.origin, ray.direction.recip(), max_distance)
+    }
+
+    // Slab test with the reciprocal of the ray direction, which callers testing many boxes
+    // against one ray compute once
+    pub(crate) fn intersect_ray_inverse(
+        &self,
+        origin: Vec3,
+        inverse_direction: Vec3,
+        max_distance: f32,
+    ) -> Option<f32> {
+        let t1 = (self.min - origin) * inverse_direction;
+        let t2 = (self.max - origin) * inverse_direction;
+        let entry = t1.min(t2).max_element().max(0.0);
+        // Pushing the exit out by a few rounding errors keeps rays that graze an edge from
+        // missing a box around a triangle they hit
+        let exit = (t1.max(t2).min_element() * SLAB_ROUNDING).min(max_distance);
+        (entry <= exit).then_some(entry)
+    }
+
+    // Box holding this one after rotating it and moving it by `translation`
+    pub fn transformed(&self, rotation: Mat3, translation: Vec3) -> Aabb {
+        let center = rotation * self.get_center() + translation;
+        // Each world axis gets the extents projected onto it through the absolute rotation
+        let abs = Mat3::from_cols(
+            rotation.x_axis.abs(),
+            rotation.y_axis.abs(),
+            rotation.z_axis.abs(),
+        );
+        let extents = abs * self.get_extents();
+        Aabb::new(center - extents, center + extents)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    // Sphere around the points' bounding box center, reaching the farthest point. Not the
+    // smallest possible sphere, but close for most meshes and cheap to build.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3> + Clone) -> Self {
+        let center = Aabb::from_points(points.clone()).get_center();
+        let radius = points
+            .into_iter()
+            .map(|point| point.distance(center))
+            .fold(0.0, f32::max);
+        Self::new(center, radius)
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.distance_squared(self.center) <= self.radius * self.radius
+    }
+
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        let reach = self.radius + other.radius;
+        self.center.distance_squared(other.center) <= reach * reach
+    }
+
+    pub fn transformed(&self, rotation: Mat3, translation: Vec3) -> BoundingSphere {
+        BoundingSphere::new(rotation * self.center + translation, self.radius)
+    }
+}
@@ -0,0 +1,392 @@
+use crate::geometry::bounds::Aabb;
+use crate::geometry::ray::Ray;
+use glam::Vec3;
+
+// Buckets the centroids are sorted into when looking for the cheapest split
+const SAH_BINS: usize = 12;
+// Nodes with this many primitives or fewer always become leaves
+const MIN_LEAF_SIZE: usize = 2;
+// Nodes with more primitives are always split, even when the SAH prefers a leaf
+const MAX_LEAF_SIZE: usize = 8;
+
+#[derive(Clone, Debug)]
+struct BvhNode {
+    bounds: Aabb,
+    first: u32, // first primitive of a leaf, or the left child of an interior node
+    count: u32, // primitives in a leaf; zero for interior nodes, whose right child is first + 1
+}
+
+// Bounding volume hierarchy over anything with a bounding box, built with the surface area
+// heuristic. Primitives are identified by their index in the slice the tree was built from;
+// the queries call back with those indices to test the primitives themselves.
+#[derive(Clone, Debug, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>, // root first, children always after their parent
+    indices: Vec<u32>,   // primitive indices, grouped by leaf
+    bounds: Vec<Aabb>,   // primitive boxes, by primitive index
+}
+
+impl Bvh {
+    pub fn new(bounds: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            indices: (0..bounds.len() as u32).collect(),
+            bounds: bounds.to_vec(),
+        };
+        if bounds.is_empty() {
+            return bvh;
+        }
+        let centroids: Vec<Vec3> = bounds.iter().map(|aabb| aabb.get_center()).collect();
+        bvh.nodes.push(BvhNode {
+            bounds: bounds[0],
+            first: 0,
+            count: bounds.len() as u32,
+        });
+        bvh.subdivide(0, bounds, &centroids);
+        bvh
+    }
+
+    // Number of primitives the tree was built over
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    // Box around everything, None when empty
+    pub fn get_bounds(&self) -> Option<Aabb> {
+        self.nodes.first().map(|node| node.bounds)
+    }
+
+    // Updates the boxes for primitives that moved, keeping the tree's shape. Cheaper than a
+    // rebuild, but queries slow down as the primitives drift far from where they were.
+    // `bounds` must hold the same primitives, in the same order, as the tree was built from.
+    pub fn refit(&mut self, bounds: &[Aabb]) {
+        assert_eq!(bounds.len(), self.indices.len(), "primitive count changed");
+        self.bounds.copy_from_slice(bounds);
+        for index in (0..self.nodes.len()).rev() {
+            let node = &self.nodes[index];
+            let (first, count) = (node.first as usize, node.count as usize);
+            self.nodes[index].bounds = if count > 0 {
+                self.leaf_bounds(first, count, bounds)
+            } else {
+                self.nodes[first]
+                    .bounds
+                    .union(&self.nodes[first + 1].bounds)
+            };
+        }
+    }
+
+    // Closest hit along the ray. `intersect` tests one primitive and returns the distance to
+    // it when it's hit closer than the distance it's given.
+    pub fn raycast(
+        &self,
+        ray: &Ray,
+        max_distance: f32,
+        mut intersect: impl FnMut(usize, f32) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        let mut closest = None;
+        let mut limit = max_distance;
+        self.traverse_ray(ray, &mut limit, &mut |primitive, limit| {
+            if let Some(distance) = intersect(primitive, *limit)
+                && distance < *limit
+            {
+                *limit = distance;
+                closest = Some((primitive, distance));
+            }
+            false
+        });
+        closest
+    }
+
+    // Whether anything is hit closer than `max_distance`, stopping at the first hit; for
+    // shadow and line-of-sight tests
+    pub fn raycast_any(
+        &self,
+        ray: &Ray,
+        max_distance: f32,
+        mut intersect: impl FnMut(usize, f32) -> Option<f32>,
+    ) -> bool {
+        let mut limit = max_distance;
+        self.traverse_ray(ray, &mut limit, &mut |primitive, limit| {
+            intersect(primitive, *limit).is_some_and(|distance| distance < *limit)
+        })
+    }
+
+    // Calls `visit` with every primitive whose box overlaps `aabb`
+    pub fn query_aabb(&self, aabb: &Aabb, mut visit: impl FnMut(usize)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.intersects(aabb) {
+                continue;
+            }
+            let (first, count) = (node.first as usize, node.count as usize);
+            if count > 0 {
+                for &primitive in &self.indices[first..first + count] {
+                    if self.bounds[primitive as usize].intersects(aabb) {
+                        visit(primitive as usize);
+                    }
+                }
+            } else {
+                stack.push(first);
+                stack.push(first + 1);
+            }
+        }
+    }
+
+    // Closest primitive to `point` within `max_distance`. `distance` returns the exact
+    // distance from the point to one primitive.
+    pub fn nearest(
+        &self,
+        point: Vec3,
+        max_distance: f32,
+        mut distance: impl FnMut(usize) -> f32,
+    ) -> Option<(usize, f32)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut closest = None;
+        let mut limit = max_distance;
+        let mut stack = vec![(0, self.nodes[0].bounds.distance_to_point(point))];
+        while let Some((index, box_distance)) = stack.pop() {
+            if box_distance > limit {
+                continue;
+            }
+            let node = &self.nodes[index];
+            let (first, count) = (node.first as usize, node.count as usize);
+            if count > 0 {
+                for &primitive in &self.indices[first..first + count] {
+                    if self.bounds[primitive as usize].distance_to_point(point) > limit {
+                        continue;
+                    }
+                    let primitive_distance = distance(primitive as usize);
+                    if primitive_distance <= limit {
+                        limit = primitive_distance;
+                        closest = Some((primitive as usize, primitive_distance));
+                    }
+                }
+                continue;
+            }
+            // Visit the closer child first, so it can shrink the limit for the other
+            let left = (first, self.nodes[first].bounds.distance_to_point(point));
+            let right = (
+                first + 1,
+                self.nodes[first + 1].bounds.distance_to_point(point),
+            );
+            let (near, far) = if left.1 <= right.1 {
+                (left, right)
+            } else {
+                (right, left)
+            };
+            stack.push(far);
+            stack.push(near);
+        }
+        closest
+    }
+
+    // Visits the leaves the ray passes through, nearest box first, until `visit` returns true.
+    // `visit` may lower the limit to prune boxes further along the ray.
+    fn traverse_ray(
+        &self,
+        ray: &Ray,
+        limit: &mut f32,
+        visit: &mut dyn FnMut(usize, &mut f32) -> bool,
+    ) -> bool {
+        let Some(root) = self.nodes.first() else {
+            return false;
+        };
+        let inverse_direction = ray.direction.recip();
+        let Some(entry) = root
+            .bounds
+            .intersect_ray_inverse(ray.origin, inverse_direction, *limit)
+        else {
+            return false;
+        };
+
+        let mut stack = vec![(0, entry)];
+        while let Some((index, entry)) = stack.pop() {
+            if entry > *limit {
+                continue;
+            }
+            let node = &self.nodes[index];
+            let (first, count) = (node.first as usize, node.count as usize);
+            if count > 0 {
+                for &primitive in &self.indices[first..first + count] {
+                    let hit = self.bounds[primitive as usize].intersect_ray_inverse(
+                        ray.origin,
+                        inverse_direction,
+                        *limit,
+                    );
+                    if hit.is_some() && visit(primitive as usize, limit) {
+                        return true;
+                    }
+                }
+                continue;
+            }
+            let hit = |child: usize| {
+                self.nodes[child]
+                    .bounds
+                    .intersect_ray_inverse(ray.origin, inverse_direction, *limit)
+                    .map(|entry| (child, entry))
+            };
+            match (hit(first), hit(first + 1)) {
+                (Some(left), Some(right)) => {
+                    let (near, far) = if left.1 <= right.1 {
+                        (left, right)
+                    } else {
+                        (right, left)
+                    };
+                    stack.push(far);
+                    stack.push(near);
+                }
+                (Some(child), None) | (None, Some(child)) => stack.push(child),
+                (None, None) => {}
+            }
+        }
+        false
+    }
+
+    fn leaf_bounds(&self, first: usize, count: usize, bounds: &[Aabb]) -> Aabb {
+        self.indices[first..first + count]
+            .iter()
+            .map(|&primitive| bounds[primitive as usize])
+            .reduce(|a, b| a.union(&b))
+            .unwrap()
+    }
+
+    fn subdivide(&mut self, index: usize, bounds: &[Aabb], centroids: &[Vec3]) {
+        let first = self.nodes[index].first as usize;
+        let count = self.nodes[index].count as usize;
+        let node_bounds = self.leaf_bounds(first, count, bounds);
+        self.nodes[index].bounds = node_bounds;
+        if count <= MIN_LEAF_SIZE {
+            return;
+        }
+
+        let primitives = &mut self.indices[first..first + count];
+        let Some(split) = find_split(primitives, bounds, centroids) else {
+            return; // every centroid in the same place; nothing to split on
+        };
+        let leaf_cost = count as f32 * node_bounds.get_surface_area();
+        if split.cost >= leaf_cost && count <= MAX_LEAF_SIZE {
+            return;
+        }
+
+        // Partition the node's primitives around the split plane
+        let mut left_count = 0;
+        for i in 0..primitives.len() {
+            if split.bin(centroids[primitives[i] as usize]) <= split.plane {
+                primitives.swap(i, left_count);
+                left_count += 1;
+            }
+        }
+
+        let left = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bounds: node_bounds,
+            first: first as u32,
+            count: left_count as u32,
+        });
+        self.nodes.push(BvhNode {
+            bounds: node_bounds,
+            first: (first + left_count) as u32,
+            count: (count - left_count) as u32,
+        });
+        self.nodes[index].first = left as u32;
+        self.nodes[index].count = 0;
+
+        self.subdivide(left, bounds, centroids);
+        self.subdivide(left + 1, bounds, centroids);
+    }
+}
+
+// A plane between two bins of centroids along one axis
+struct Split {
+    axis: usize,
+    min: f32,     // lowest centroid along the axis
+    scale: f32,   // bins per unit along the axis
+    plane: usize, // bins up to and including this one go left
+    cost: f32,    // surface area times primitive count, summed over both sides
+}
+
+impl Split {
+    fn bin(&self, centroid: Vec3) -> usize {
+        (((centroid[self.axis] - self.min) * self.scale) as usize).min(SAH_BINS - 1)
+    }
+}
+
+// Cheapest split by the surface area heuristic over all three axes, with the centroids
+// sorted into bins. Never puts everything on one side.
+fn find_split(primitives: &[u32], bounds: &[Aabb], centroids: &[Vec3]) -> Option<Split> {
+    let centroid_bounds = Aabb::from_points(
+        primitives
+            .iter()
+            .map(|&primitive| centroids[primitive as usize]),
+    );
+    let extent = centroid_bounds.get_size();
+
+    let mut best: Option<Split> = None;
+    for axis in 0..3 {
+        if extent[axis] <= f32::EPSILON {
+            continue;
+        }
+        let mut candidate = Split {
+            axis,
+            min: centroid_bounds.min[axis],
+            scale: SAH_BINS as f32 / extent[axis],
+            plane: 0,
+            cost: 0.0,
+        };
+
+        let mut bin_bounds: [Option<Aabb>; SAH_BINS] = [None; SAH_BINS];
+        let mut bin_counts = [0usize; SAH_BINS];
+        for &primitive in primitives {
+            let bin = candidate.bin(centroids[primitive as usize]);
+            bin_counts[bin] += 1;
+            bin_bounds[bin] = union_option(bin_bounds[bin], Some(bounds[primitive as usize]));
+        }
+
+        // Sweep from the left to get the count and area left of each plane, then from the
+        // right to finish the cost
+        let mut left_counts = [0usize; SAH_BINS - 1];
+        let mut left_areas = [0.0; SAH_BINS - 1];
+        let mut running = (0, None);
+        for plane in 0..SAH_BINS - 1 {
+            running.0 += bin_counts[plane];
+            running.1 = union_option(running.1, bin_bounds[plane]);
+            left_counts[plane] = running.0;
+            left_areas[plane] = running.1.map_or(0.0, |aabb| aabb.get_surface_area());
+        }
+        let mut running = (0, None);
+        for plane in (0..SAH_BINS - 1).rev() {
+            running.0 += bin_counts[plane + 1];
+            running.1 = union_option(running.1, bin_bounds[plane + 1]);
+            if left_counts[plane] == 0 || running.0 == 0 {
+                continue;
+            }
+            let right_area = running.1.map_or(0.0, |aabb| aabb.get_surface_area());
+            let cost =
+                left_counts[plane] as f32 * left_areas[plane] + running.0 as f32 * right_area;
+            if best.as_ref().is_none_or(|best| cost < best.cost) {
+                candidate.plane = plane;
+                candidate.cost = cost;
+                best = Some(Split { ..candidate });
+            }
+        }
+    }
+    best
+}
+
+fn union_option(a: Option<Aabb>, b: Option<Aabb>) -> Option<Aabb> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(&b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
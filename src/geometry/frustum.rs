@@ -0,0 +1,66 @@
+use crate::geometry::bounds::{Aabb, BoundingSphere};
+use glam::{Mat4, Vec3, Vec4};
+
+// Points with normal.dot(point) + distance >= 0 are in front of the plane
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: Vec3, // unit length
+    pub distance: f32,
+}
+
+impl Plane {
+    pub fn new(normal: Vec3, distance: f32) -> Self {
+        Self { normal, distance }
+    }
+
+    // Plane from the (a, b, c, d) of ax + by + cz + d = 0, normalized
+    pub fn from_coefficients(coefficients: Vec4) -> Self {
+        let length = coefficients.truncate().length();
+        Self::new(coefficients.truncate() / length, coefficients.w / length)
+    }
+
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+// The six planes of a view frustum, facing inwards: left, right, bottom, top, near, far
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    // Extracts the planes from a view-projection matrix with depth from 0 to 1, as wgpu uses
+    pub fn from_matrix(view_projection: Mat4) -> Self {
+        let [x, y, z, w] = [0, 1, 2, 3].map(|i| view_projection.row(i));
+        Self {
+            planes: [w + x, w - x, w + y, w - y, z, w - z].map(Plane::from_coefficients),
+        }
+    }
+
+    pub fn get_planes(&self) -> &[Plane; 6] {
+        &self.planes
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    // Conservative: a box near a corner of the frustum can pass while lying just outside
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            // The corner furthest along the plane's normal
+            let corner = Vec3::select(plane.normal.cmpge(Vec3::ZERO), aabb.max, aabb.min);
+            plane.signed_distance(corner) >= 0.0
+        })
+    }
+
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+}
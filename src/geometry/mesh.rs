@@ -1,14 +1,33 @@
-use crate::geometry::triangle::Triangle;
-use glam::Vec3;
+use crate::geometry::bounds::{Aabb, BoundingSphere};
+use crate::geometry::bvh::Bvh;
+use crate::geometry::ray::Ray;
+use crate::geometry::triangle::{Triangle, orthonormalize_tangent};
+use glam::{Vec2, Vec3};
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
 #[derive(Clone)]
 pub struct Mesh {
     triangles: Vec<Triangle>,
+    bounds: OnceLock<(Aabb, BoundingSphere)>, // local-space bounds, computed on first use
+    bvh: OnceLock<Bvh>,                       // over the triangles, built on first use
+}
+
+// Where a ray hits a mesh, in the mesh's own space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeshHit {
+    pub triangle: usize, // index into get_triangles()
+    pub distance: f32,
+    pub barycentric: Vec3, // weights of the triangle's three vertices at the hit
 }
 
 impl Mesh {
     pub fn new(triangles: Vec<Triangle>) -> Self {
-        Mesh { triangles }
+        Mesh {
+            triangles,
+            bounds: OnceLock::new(),
+            bvh: OnceLock::new(),
+        }
     }
 
     pub fn from_raw_coordinates(triangles: Vec<[f32; 9]>) -> Self {
@@ -22,7 +41,114 @@ impl Mesh {
                 ])
             })
             .collect();
-        Mesh { triangles }
+        Mesh::new(triangles)
+    }
+
+    // Same as `from_raw_coordinates`, with one [u0, v0, u1, v1, u2, v2] entry per triangle
+    pub fn from_raw_coordinates_with_uvs(triangles: Vec<[f32; 9]>, uvs: Vec<[f32; 6]>) -> Self {
+        let triangles = triangles
+            .into_iter()
+            .zip(uvs)
+            .map(|(coords, uv)| {
+                Triangle::with_uvs(
+                    [
+                        Vec3::new(coords[0], coords[1], coords[2]),
+                        Vec3::new(coords[3], coords[4], coords[5]),
+                        Vec3::new(coords[6], coords[7], coords[8]),
+                    ],
+                    [
+                        Vec2::new(uv[0], uv[1]),
+                        Vec2::new(uv[2], uv[3]),
+                        Vec2::new(uv[4], uv[5]),
+                    ],
+                )
+            })
+            .collect();
+        Mesh::new(triangles)
+    }
+
+    // Box-projects texture coordinates: every triangle is mapped onto the side of the
+    // mesh's bounding box its normal points at, so each flat face gets the full [0, 1] range
+    // upright and unmirrored when seen from outside. v runs downwards, as in image space.
+    pub fn generate_planar_uvs(&mut self) {
+        let aabb = self.get_aabb();
+        let min = aabb.min;
+        let size = aabb.get_size().max(Vec3::splat(f32::EPSILON));
+
+        for triangle in &mut self.triangles {
+            let normal = triangle.get_normal();
+            let abs = normal.abs();
+            let vertices = triangle.get_vertices();
+            let uvs = vertices.map(|vertex| {
+                let t = (vertex - min) / size; // 0..1 inside the bounding box
+                if abs.x >= abs.y && abs.x >= abs.z {
+                    if normal.x > 0.0 {
+                        Vec2::new(t.z, 1.0 - t.y)
+                    } else {
+                        Vec2::new(1.0 - t.z, 1.0 - t.y)
+                    }
+                } else if abs.y >= abs.z {
+                    if normal.y > 0.0 {
+                        Vec2::new(t.x, 1.0 - t.z)
+                    } else {
+                        Vec2::new(1.0 - t.x, 1.0 - t.z)
+                    }
+                } else if normal.z > 0.0 {
+                    Vec2::new(1.0 - t.x, 1.0 - t.y)
+                } else {
+                    Vec2::new(t.x, 1.0 - t.y)
+                }
+            });
+            triangle.set_uvs(uvs);
+        }
+    }
+
+    // Generates per-vertex tangents in the spirit of MikkTSpace: face tangents are weighted by
+    // the corner angle and summed over every corner sharing position, normal, texture coordinate
+    // and handedness, then orthogonalized against the normal. Triangles that already carry
+    // tangents keep them.
+    pub fn generate_tangents(&mut self) {
+        let mut accumulated: HashMap<[u32; 9], (Vec3, Vec3)> = HashMap::new();
+        let mut corner_keys = Vec::with_capacity(self.triangles.len());
+
+        for triangle in &self.triangles {
+            let mut keys = [None; 3];
+            if let Some((tangent, bitangent)) = triangle.calculate_uv_basis() {
+                let normal = triangle.get_normal();
+                let vertices = triangle.get_vertices();
+                let uvs = triangle.get_uvs();
+                let flipped = normal.cross(tangent).dot(bitangent) < 0.0;
+                let tangent = tangent.normalize_or_zero();
+                let bitangent = bitangent.normalize_or_zero();
+
+                for (i, key) in keys.iter_mut().enumerate() {
+                    let corner = corner_key(vertices[i], normal, uvs[i], flipped);
+                    let angle = corner_angle(vertices, i);
+                    let sum = accumulated
+                        .entry(corner)
+                        .or_insert((Vec3::ZERO, Vec3::ZERO));
+                    sum.0 += tangent * angle;
+                    sum.1 += bitangent * angle;
+                    *key = Some(corner);
+                }
+            }
+            corner_keys.push(keys);
+        }
+
+        for (triangle, keys) in self.triangles.iter_mut().zip(corner_keys) {
+            if triangle.has_tangents() {
+                continue;
+            }
+            let normal = triangle.get_normal();
+            let tangents = keys.map(|key| match key {
+                Some(key) => {
+                    let (tangent, bitangent) = accumulated[&key];
+                    orthonormalize_tangent(normal, tangent, bitangent)
+                }
+                None => normal.any_orthonormal_vector().extend(1.0),
+            });
+            triangle.set_tangents(Some(tangents));
+        }
     }
 
     pub fn get_vertices(&self) -> Vec<Vec3> {
@@ -46,5 +172,90 @@ impl Mesh {
 
     pub fn set_triangles(&mut self, triangles: Vec<Triangle>) {
         self.triangles = triangles;
+        self.bounds = OnceLock::new();
+        self.bvh = OnceLock::new();
+    }
+
+    // Local-space bounding box; zero-size at the origin for an empty mesh
+    pub fn get_aabb(&self) -> Aabb {
+        self.get_bounds().0
+    }
+
+    pub fn get_bounding_sphere(&self) -> BoundingSphere {
+        self.get_bounds().1
     }
+
+    pub fn get_bvh(&self) -> &Bvh {
+        self.bvh.get_or_init(|| {
+            let bounds: Vec<Aabb> = self.triangles.iter().map(Triangle::get_aabb).collect();
+            Bvh::new(&bounds)
+        })
+    }
+
+    // Closest triangle hit by a ray given in the mesh's space; both sides of a triangle count
+    pub fn raycast(&self, ray: &Ray, max_distance: f32) -> Option<MeshHit> {
+        let mut barycentric = Vec3::ZERO;
+        let (triangle, distance) = self.get_bvh().raycast(ray, max_distance, |index, limit| {
+            let (distance, u, v) = ray.intersect_triangle(self.triangles[index].get_vertices())?;
+            if distance < limit {
+                barycentric = Vec3::new(1.0 - u - v, u, v);
+            }
+            Some(distance)
+        })?;
+        Some(MeshHit {
+            triangle,
+            distance,
+            barycentric,
+        })
+    }
+
+    // Indices of the triangles whose bounding boxes overlap `aabb`
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<usize> {
+        let mut triangles = Vec::new();
+        self.get_bvh()
+            .query_aabb(aabb, |index| triangles.push(index));
+        triangles
+    }
+
+    // Closest point on the surface to `point` and the triangle it lies on
+    pub fn closest_point(&self, point: Vec3) -> Option<(usize, Vec3)> {
+        let (triangle, _) = self.get_bvh().nearest(point, f32::INFINITY, |index| {
+            self.triangles[index].closest_point(point).distance(point)
+        })?;
+        Some((triangle, self.triangles[triangle].closest_point(point)))
+    }
+
+    fn get_bounds(&self) -> &(Aabb, BoundingSphere) {
+        self.bounds.get_or_init(|| {
+            let points = self
+                .triangles
+                .iter()
+                .flat_map(|triangle| triangle.get_vertices());
+            (
+                Aabb::from_points(points.clone()),
+                BoundingSphere::from_points(points),
+            )
+        })
+    }
+}
+
+fn corner_key(position: Vec3, normal: Vec3, uv: Vec2, flipped: bool) -> [u32; 9] {
+    [
+        position.x.to_bits(),
+        position.y.to_bits(),
+        position.z.to_bits(),
+        normal.x.to_bits(),
+        normal.y.to_bits(),
+        normal.z.to_bits(),
+        uv.x.to_bits(),
+        uv.y.to_bits(),
+        flipped as u32,
+    ]
+}
+
+fn corner_angle(vertices: [Vec3; 3], corner: usize) -> f32 {
+    let origin = vertices[corner];
+    let a = vertices[(corner + 1) % 3] - origin;
+    let b = vertices[(corner + 2) % 3] - origin;
+    a.angle_between(b)
 }
@@ -1,3 +1,7 @@
+pub mod bounds;
+pub mod bvh;
+pub mod frustum;
 pub mod mesh;
 pub mod primitives;
+pub mod ray;
 pub mod triangle;
@@ -4,9 +4,10 @@ use crate::geometry::mesh::Mesh;
 pub struct Cube;
 
 impl Cube {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(size: f32) -> Object {
         let half_size = size * 0.5;
-        let mesh = Mesh::from_raw_coordinates(vec![
+        let mut mesh = Mesh::from_raw_coordinates(vec![
             // Front face (z = 0.5)
             [
                 -half_size, -half_size, half_size, half_size, -half_size, half_size, half_size,
@@ -62,6 +63,8 @@ impl Cube {
                 -half_size, half_size,
             ], // Triangle 12
         ]);
+        mesh.generate_planar_uvs();
+        mesh.generate_tangents();
         Object::new(mesh)
     }
 }
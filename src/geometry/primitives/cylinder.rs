@@ -5,8 +5,10 @@ use std::f32::consts::PI;
 pub struct Cylinder;
 
 impl Cylinder {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(radius: f32, height: f32, segments: u32) -> Object {
         let mut triangles = Vec::new();
+        let mut uvs = Vec::new();
         let angle_step = 2.0 * PI / segments as f32;
 
         // Generate vertices for top and bottom circles
@@ -19,16 +21,28 @@ impl Cylinder {
             let x2 = radius * angle2.cos();
             let z2 = radius * angle2.sin();
 
+            // Caps are mapped onto a disc inscribed in the texture
+            let (cu1, cv1) = (0.5 + x1 / (2.0 * radius), 0.5 - z1 / (2.0 * radius));
+            let (cu2, cv2) = (0.5 + x2 / (2.0 * radius), 0.5 - z2 / (2.0 * radius));
+            // The side wraps around the texture once
+            let su1 = i as f32 / segments as f32;
+            let su2 = (i + 1) as f32 / segments as f32;
+
             // Top face
             triangles.push([0.0, height, 0.0, x1, height, z1, x2, height, z2]);
+            uvs.push([0.5, 0.5, cu1, cv1, cu2, cv2]);
             // Bottom face
             triangles.push([0.0, 0.0, 0.0, x2, 0.0, z2, x1, 0.0, z1]);
+            uvs.push([0.5, 0.5, 1.0 - cu2, cv2, 1.0 - cu1, cv1]);
             // Side face
             triangles.push([x1, height, z1, x1, 0.0, z1, x2, 0.0, z2]);
+            uvs.push([su1, 0.0, su1, 1.0, su2, 1.0]);
             triangles.push([x1, height, z1, x2, 0.0, z2, x2, height, z2]);
+            uvs.push([su1, 0.0, su2, 1.0, su2, 0.0]);
         }
 
-        let mesh = Mesh::from_raw_coordinates(triangles);
+        let mut mesh = Mesh::from_raw_coordinates_with_uvs(triangles, uvs);
+        mesh.generate_tangents();
         Object::new(mesh)
     }
 }
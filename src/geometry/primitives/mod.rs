@@ -1,5 +1,6 @@
 pub mod cube;
 pub mod cylinder;
+pub mod plane;
 pub mod pyramid;
 pub mod rectangular_prism;
 pub mod sphere;
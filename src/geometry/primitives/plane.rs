@@ -0,0 +1,41 @@
+use crate::core::object::Object;
+use crate::geometry::mesh::Mesh;
+
+pub struct Plane;
+
+impl Plane {
+    // Flat grid facing up, centered on the origin, split into `segments_x` by `segments_z`
+    // squares of two triangles each so it can bend, as cloth does
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(width: f32, depth: f32, segments_x: u32, segments_z: u32) -> Object {
+        let (segments_x, segments_z) = (segments_x.max(1), segments_z.max(1));
+        let mut triangles = Vec::new();
+        let mut uvs = Vec::new();
+        let point = |i: u32, j: u32| {
+            let (u, v) = (i as f32 / segments_x as f32, j as f32 / segments_z as f32);
+            ([(u - 0.5) * width, 0.0, (v - 0.5) * depth], [u, v])
+        };
+
+        for j in 0..segments_z {
+            for i in 0..segments_x {
+                let (p00, t00) = point(i, j);
+                let (p10, t10) = point(i + 1, j);
+                let (p01, t01) = point(i, j + 1);
+                let (p11, t11) = point(i + 1, j + 1);
+                // Wound counterclockwise seen from above
+                triangles.push([
+                    p00[0], p00[1], p00[2], p01[0], p01[1], p01[2], p11[0], p11[1], p11[2],
+                ]);
+                uvs.push([t00[0], t00[1], t01[0], t01[1], t11[0], t11[1]]);
+                triangles.push([
+                    p00[0], p00[1], p00[2], p11[0], p11[1], p11[2], p10[0], p10[1], p10[2],
+                ]);
+                uvs.push([t00[0], t00[1], t11[0], t11[1], t10[0], t10[1]]);
+            }
+        }
+
+        let mut mesh = Mesh::from_raw_coordinates_with_uvs(triangles, uvs);
+        mesh.generate_tangents();
+        Object::new(mesh)
+    }
+}
@@ -4,9 +4,10 @@ use crate::geometry::mesh::Mesh;
 pub struct Pyramid;
 
 impl Pyramid {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(base_size: f32, height: f32) -> Object {
         let half_base = base_size * 0.5;
-        let mesh = Mesh::from_raw_coordinates(vec![
+        let mut mesh = Mesh::from_raw_coordinates(vec![
             // Base
             [
                 -half_base, 0.0, -half_base, half_base, 0.0, -half_base, half_base, 0.0, half_base,
@@ -31,6 +32,8 @@ impl Pyramid {
                 0.0, height, 0.0, half_base, 0.0, half_base, half_base, 0.0, -half_base,
             ],
         ]);
+        mesh.generate_planar_uvs();
+        mesh.generate_tangents();
         Object::new(mesh)
     }
 }
@@ -4,12 +4,13 @@ use crate::geometry::mesh::Mesh;
 pub struct RectangularPrism;
 
 impl RectangularPrism {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(width: f32, height: f32, depth: f32) -> Object {
         let half_width = width * 0.5;
         let half_height = height * 0.5;
         let half_depth = depth * 0.5;
 
-        let mesh = Mesh::from_raw_coordinates(vec![
+        let mut mesh = Mesh::from_raw_coordinates(vec![
             // Front face (z = half_depth)
             [
                 -half_width,
@@ -149,6 +150,8 @@ impl RectangularPrism {
                 half_depth,
             ], // Triangle 12
         ]);
+        mesh.generate_planar_uvs();
+        mesh.generate_tangents();
         Object::new(mesh)
     }
 }
@@ -5,8 +5,10 @@ use std::f32::consts::PI;
 pub struct Sphere;
 
 impl Sphere {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(radius: f32, segments: u32) -> Object {
         let mut triangles = Vec::new();
+        let mut uvs = Vec::new();
         let angle_step = 2.0 * PI / segments as f32;
         let height_step = PI / segments as f32;
 
@@ -40,17 +42,26 @@ impl Sphere {
                     radius * phi2.sin() * theta2.sin(),
                 ];
 
+                // Equirectangular texture coordinates
+                let u1 = theta1 / (2.0 * PI);
+                let u2 = theta2 / (2.0 * PI);
+                let t1 = phi1 / PI;
+                let t2 = phi2 / PI;
+
                 // Add triangles
                 triangles.push([
                     v1[0], v1[1], v1[2], v2[0], v2[1], v2[2], v3[0], v3[1], v3[2],
                 ]);
+                uvs.push([u1, t1, u2, t1, u1, t2]);
                 triangles.push([
                     v2[0], v2[1], v2[2], v4[0], v4[1], v4[2], v3[0], v3[1], v3[2],
                 ]);
+                uvs.push([u2, t1, u2, t2, u1, t2]);
             }
         }
 
-        let mesh = Mesh::from_raw_coordinates(triangles);
+        let mut mesh = Mesh::from_raw_coordinates_with_uvs(triangles, uvs);
+        mesh.generate_tangents();
         Object::new(mesh)
     }
 }
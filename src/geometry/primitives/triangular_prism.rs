@@ -4,10 +4,11 @@ use crate::geometry::mesh::Mesh;
 pub struct TriangularPrism;
 
 impl TriangularPrism {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(base_width: f32, height: f32, depth: f32) -> Object {
         let half_width = base_width * 0.5;
         let half_depth = depth * 0.5;
-        let mesh = Mesh::from_raw_coordinates(vec![
+        let mut mesh = Mesh::from_raw_coordinates(vec![
             // Front face
             [
                 -half_width,
@@ -102,6 +103,8 @@ impl TriangularPrism {
                 -half_depth,
             ],
         ]);
+        mesh.generate_planar_uvs();
+        mesh.generate_tangents();
         Object::new(mesh)
     }
 }
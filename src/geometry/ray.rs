@@ -0,0 +1,49 @@
+use glam::Vec3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3, // normalized
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    // Möller-Trumbore intersection with both sides of a triangle. Returns the distance along
+    // the ray and the barycentric weights of the second and third vertex.
+    pub fn intersect_triangle(&self, vertices: [Vec3; 3]) -> Option<(f32, f32, f32)> {
+        let e1 = vertices[1] - vertices[0];
+        let e2 = vertices[2] - vertices[0];
+        let p = self.direction.cross(e2);
+        let determinant = e1.dot(p);
+        if determinant.abs() < 1e-9 {
+            return None; // parallel to the triangle
+        }
+        let inverse = 1.0 / determinant;
+
+        let s = self.origin - vertices[0];
+        let u = s.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(e1);
+        let v = self.direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = e2.dot(q) * inverse;
+        if distance <= 0.0 {
+            return None;
+        }
+        Some((distance, u, v))
+    }
+}
@@ -1,15 +1,24 @@
-use glam::Vec3;
+use crate::geometry::bounds::Aabb;
+use glam::{Vec2, Vec3, Vec4};
 
 #[derive(Clone)]
 pub struct Triangle {
     vertices: [Vec3; 3],
+    uvs: [Vec2; 3],
+    tangents: Option<[Vec4; 3]>,
     normal: Vec3,
 }
 
 impl Triangle {
     pub fn new(vertices: [Vec3; 3]) -> Self {
+        Self::with_uvs(vertices, [Vec2::ZERO; 3])
+    }
+
+    pub fn with_uvs(vertices: [Vec3; 3], uvs: [Vec2; 3]) -> Self {
         let mut triangle = Self {
             vertices,
+            uvs,
+            tangents: None,
             normal: Vec3::ZERO,
         };
         triangle.calculate_normal();
@@ -25,16 +34,138 @@ impl Triangle {
         self.normal = normal.normalize();
     }
 
+    // Unnormalized tangent (direction of increasing u) and bitangent (direction of decreasing
+    // v, i.e. "up" in the image) of the triangle's texture mapping. Bitangents follow glTF's
+    // normal map convention where green points up.
+    pub fn calculate_uv_basis(&self) -> Option<(Vec3, Vec3)> {
+        let e1 = self.vertices[1] - self.vertices[0];
+        let e2 = self.vertices[2] - self.vertices[0];
+        let du1 = self.uvs[1].x - self.uvs[0].x;
+        let du2 = self.uvs[2].x - self.uvs[0].x;
+        let dv1 = self.uvs[0].y - self.uvs[1].y;
+        let dv2 = self.uvs[0].y - self.uvs[2].y;
+
+        let determinant = du1 * dv2 - du2 * dv1;
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+        let r = 1.0 / determinant;
+        let tangent = (e1 * dv2 - e2 * dv1) * r;
+        let bitangent = (e2 * du1 - e1 * du2) * r;
+        Some((tangent, bitangent))
+    }
+
+    // Per-face tangent used when no tangents were provided or generated
+    fn calculate_tangent(&self) -> Vec4 {
+        match self.calculate_uv_basis() {
+            Some((tangent, bitangent)) => orthonormalize_tangent(self.normal, tangent, bitangent),
+            None => self.normal.any_orthonormal_vector().extend(1.0),
+        }
+    }
+
     pub fn get_vertices(&self) -> [Vec3; 3] {
         self.vertices
     }
 
+    pub fn get_uvs(&self) -> [Vec2; 3] {
+        self.uvs
+    }
+
+    // xyz is the tangent, w the bitangent sign: bitangent = cross(normal, tangent) * w
+    pub fn get_tangents(&self) -> [Vec4; 3] {
+        self.tangents
+            .unwrap_or_else(|| [self.calculate_tangent(); 3])
+    }
+
+    pub fn has_tangents(&self) -> bool {
+        self.tangents.is_some()
+    }
+
     pub fn get_normal(&self) -> Vec3 {
         self.normal
     }
 
+    pub fn get_aabb(&self) -> Aabb {
+        Aabb::from_points(self.vertices)
+    }
+
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        closest_point_on_triangle(self.vertices, point)
+    }
+
     pub fn set_vertices(&mut self, vertices: [Vec3; 3]) {
         self.vertices = vertices;
+        self.tangents = None;
         self.calculate_normal();
     }
+
+    pub fn set_uvs(&mut self, uvs: [Vec2; 3]) {
+        self.uvs = uvs;
+        self.tangents = None;
+    }
+
+    pub fn set_tangents(&mut self, tangents: Option<[Vec4; 3]>) {
+        self.tangents = tangents;
+    }
+}
+
+// Gram-Schmidt orthogonalizes `tangent` against `normal` and stores the handedness of the
+// (tangent, bitangent, normal) frame in w
+pub fn orthonormalize_tangent(normal: Vec3, tangent: Vec3, bitangent: Vec3) -> Vec4 {
+    let orthogonal = tangent - normal * normal.dot(tangent);
+    let tangent = orthogonal
+        .try_normalize()
+        .unwrap_or_else(|| normal.any_orthonormal_vector());
+    let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    tangent.extend(handedness)
+}
+
+// Closest point to `point` on the triangle, edges and inside included, by finding the Voronoi
+// region of the triangle the point falls in (Ericson, Real-Time Collision Detection 5.1.5)
+pub fn closest_point_on_triangle(vertices: [Vec3; 3], point: Vec3) -> Vec3 {
+    let [a, b, c] = vertices;
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let denominator = 1.0 / (va + vb + vc);
+    a + ab * (vb * denominator) + ac * (vc * denominator)
 }
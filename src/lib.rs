@@ -0,0 +1,4 @@
+pub mod core;
+pub mod engine;
+pub mod geometry;
+pub mod physics;
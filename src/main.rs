@@ -1,13 +1,14 @@
-mod core;
-mod engine;
-mod geometry;
-
-use crate::core::camera::Camera;
-use crate::core::light::DirectionalLight;
-use crate::core::scene::Scene;
-use crate::engine::Engine;
-use crate::geometry::primitives::cube::Cube;
-use glam::Vec3;
+use glam::{Vec3, Vec4};
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::light::{DirectionalLight, PointLight, ShadowSettings};
+use three_d::core::material::Material;
+use three_d::core::path_tracer::PathTracer;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::engine::Engine;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::rectangular_prism::RectangularPrism;
 
 fn main() {
     let mut scene = Scene::new();
@@ -16,11 +17,27 @@ fn main() {
     let mut light = DirectionalLight::new(Vec3::new(1.0, -1.0, 1.0));
     light.set_color(Vec3::new(1.0, 1.0, 1.0));
     light.set_intensity(1.0);
+    light.set_shadow_settings(Some(ShadowSettings::new()));
     scene.add_light(light);
 
+    // And a warm point light beside the cube with a smaller shadow cube map
+    let mut point_light = PointLight::new(Vec3::new(-2.0, 1.0, 4.0), 10.0);
+    point_light.set_color(Vec3::new(1.0, 0.7, 0.4));
+    point_light.set_intensity(8.0);
+    point_light.set_shadow_settings(Some(ShadowSettings {
+        resolution: 512,
+        ..ShadowSettings::new()
+    }));
+    // with a gizmo showing where it is, kept for good
+    scene
+        .get_debug_draw_mut()
+        .light(&point_light, Some(f32::INFINITY));
+    scene.add_light(point_light);
+
     // Create a rotating cube
     let mut cube = Cube::new(1.0);
     cube.set_position(Vec3::new(0.0, 0.0, 5.0));
+    cube.set_material(Material::pbr(Vec4::new(0.9, 0.6, 0.2, 1.0), 0.8, 0.35));
     cube.set_update(|obj, delta_time| {
         let current_rotation = *obj.get_rotation();
         obj.set_rotation(Vec3::new(
@@ -28,10 +45,53 @@ fn main() {
             current_rotation.y + delta_time * 0.5,
             current_rotation.z,
         ));
+        let transform = obj.get_transform();
+        obj.get_debug_draw_mut().axes(transform, 0.8, None);
     });
     scene.add_object(cube);
 
+    // A floor for the cube to cast its shadow on
+    let mut floor = RectangularPrism::new(8.0, 0.2, 8.0);
+    floor.set_position(Vec3::new(0.0, -1.5, 6.0));
+    scene.add_object(floor);
+
+    // and a debug grid just above it
+    scene.get_debug_draw_mut().grid(
+        Vec3::new(0.0, -1.39, 6.0),
+        8.0,
+        16,
+        Vec3::new(0.3, 0.3, 0.3),
+        Some(f32::INFINITY),
+    );
+
     let camera = Camera::new();
     let engine = Engine::new(scene, camera);
+
+    // `three-d --headless <path>` renders one frame to a PNG on the GPU without opening a
+    // window, `three-d --software <path>` does the same on the CPU, and
+    // `three-d --path-trace <path>` renders a path-traced reference, as linear floats for a
+    // .exr or .pfm path
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 3 && args[1] == "--headless" {
+        let mut renderer = pollster::block_on(HeadlessRenderer::new(800, 600));
+        engine.render_to_png(&mut renderer, &args[2]).unwrap();
+        return;
+    }
+    if args.len() == 3 && args[1] == "--software" {
+        let mut renderer = SoftwareRenderer::new(800, 600);
+        engine.render_to_png(&mut renderer, &args[2]).unwrap();
+        return;
+    }
+    if args.len() == 3 && args[1] == "--path-trace" {
+        let mut tracer = PathTracer::new(800, 600);
+        tracer.accumulate(engine.get_scene(), engine.get_camera(), 32);
+        let image = tracer.get_image();
+        match args[2].rsplit('.').next() {
+            Some("exr") => image.save_exr(&args[2]).unwrap(),
+            Some("pfm") => image.save_pfm(&args[2]).unwrap(),
+            _ => image.to_rgba8().save(&args[2]).unwrap(),
+        }
+        return;
+    }
     engine.run();
 }
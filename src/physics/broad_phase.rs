@@ -0,0 +1,149 @@
+use crate::core::object::Object;
+use crate::geometry::bounds::Aabb;
+use crate::physics::collider::Pose;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PairEventKind {
+    Enter, // the boxes started overlapping this update
+    Stay,  // they overlapped last update too
+    Exit,  // they stopped overlapping, or one lost its collider
+}
+
+// Change in whether two objects' boxes overlap, by their indices with a < b
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PairEvent {
+    pub a: usize,
+    pub b: usize,
+    pub kind: PairEventKind,
+}
+
+// World box of one object's collider
+#[derive(Clone, Copy)]
+struct Proxy {
+    index: usize,
+    aabb: Aabb,
+}
+
+// Finds the pairs of colliders whose world boxes overlap by sweep and prune: the boxes are
+// sorted by where they start along the axis they're most spread over, and each is only
+// checked against the ones that start before it ends. Objects move little between updates,
+// so the order is nearly sorted already and sorting stays close to linear.
+pub struct BroadPhase {
+    proxies: Vec<Proxy>,
+    pairs: Vec<(usize, usize)>, // sorted
+    events: Vec<PairEvent>,
+}
+
+impl BroadPhase {
+    pub fn new() -> Self {
+        Self {
+            proxies: Vec::new(),
+            pairs: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    // Finds the overlapping pairs among the objects with colliders, their boxes grown by
+    // `margin`, and the events since the last update
+    pub fn update(&mut self, objects: &[Object], margin: f32) {
+        // Keep last update's order, which is probably still nearly sorted, then add the rest
+        let mut order: Vec<usize> = self
+            .proxies
+            .iter()
+            .map(|proxy| proxy.index)
+            .filter(|&index| index < objects.len())
+            .collect();
+        let mut listed = vec![false; objects.len()];
+        for &index in &order {
+            listed[index] = true;
+        }
+        order.extend((0..objects.len()).filter(|&index| !listed[index]));
+        let mut proxies: Vec<Proxy> = order
+            .into_iter()
+            .filter_map(|index| {
+                let object = &objects[index];
+                let aabb = object
+                    .get_collider()?
+                    .get_world_aabb(&Pose::from_object(object));
+                Some(Proxy {
+                    index,
+                    aabb: Aabb::new(aabb.min - margin, aabb.max + margin),
+                })
+            })
+            .collect();
+
+        // Sweep along the axis the boxes' centers vary most over
+        let count = proxies.len().max(1) as f32;
+        let mean = proxies
+            .iter()
+            .map(|proxy| proxy.aabb.get_center())
+            .sum::<glam::Vec3>()
+            / count;
+        let variance = proxies
+            .iter()
+            .map(|proxy| (proxy.aabb.get_center() - mean).powf(2.0))
+            .sum::<glam::Vec3>();
+        let axis = variance.max_position();
+        proxies.sort_by(|a, b| a.aabb.min[axis].total_cmp(&b.aabb.min[axis]));
+
+        let mut pairs = Vec::new();
+        for (position, proxy) in proxies.iter().enumerate() {
+            for other in &proxies[position + 1..] {
+                if other.aabb.min[axis] > proxy.aabb.max[axis] {
+                    break;
+                }
+                let collider = objects[proxy.index].get_collider().unwrap();
+                let other_collider = objects[other.index].get_collider().unwrap();
+                if proxy.aabb.intersects(&other.aabb) && collider.interacts_with(other_collider) {
+                    pairs.push((proxy.index.min(other.index), proxy.index.max(other.index)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        self.proxies = proxies;
+
+        let previous: HashSet<(usize, usize)> = self.pairs.iter().copied().collect();
+        let current: HashSet<(usize, usize)> = pairs.iter().copied().collect();
+        self.events = pairs
+            .iter()
+            .map(|&(a, b)| PairEvent {
+                a,
+                b,
+                kind: if previous.contains(&(a, b)) {
+                    PairEventKind::Stay
+                } else {
+                    PairEventKind::Enter
+                },
+            })
+            .chain(
+                self.pairs
+                    .iter()
+                    .filter(|pair| !current.contains(pair))
+                    .map(|&(a, b)| PairEvent {
+                        a,
+                        b,
+                        kind: PairEventKind::Exit,
+                    }),
+            )
+            .collect();
+        self.pairs = pairs;
+    }
+
+    // Indices of the objects whose boxes overlap, each pair once with the lower index first,
+    // in order
+    pub fn get_pairs(&self) -> &Vec<(usize, usize)> {
+        &self.pairs
+    }
+
+    // What changed at the last update: enters and stays in pair order, then exits
+    pub fn get_events(&self) -> &Vec<PairEvent> {
+        &self.events
+    }
+}
+
+impl Default for BroadPhase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,323 @@
+use crate::physics::collider::{Collider, Pose};
+use crate::physics::contact::{Contact, collide_with_margin};
+use glam::Vec3;
+
+// Gap kept between the capsule and whatever it touches, so each move starts clear of it
+const SKIN_WIDTH: f32 = 0.01;
+// How far above the edge of a ledge to look for a top to stand on
+const PROBE_HEIGHT: f32 = 0.05;
+// Times the capsule is pushed out of what it overlaps after each part of a move
+const RESOLVE_ITERATIONS: usize = 4;
+
+// Walks an object around as an upright capsule centered on its position. Rather than being
+// pushed around by forces, it goes where it's told and slides along whatever it runs into:
+// it climbs ledges up to step_height, stands on ground no steeper than max_slope and slides
+// down anything steeper, and falls with the physics world's gravity when nothing holds it up.
+// Other colliders stop it, but it doesn't push them.
+#[derive(Clone, Debug)]
+pub struct CharacterController {
+    radius: f32,
+    half_height: f32, // of the capsule's middle segment
+    step_height: f32,
+    max_slope: f32, // radians from flat
+    jump_speed: f32,
+    walk_velocity: Vec3, // wanted, along the ground; y is ignored
+    velocity: Vec3,      // how it really moved over the last step
+    vertical_speed: f32,
+    grounded: bool,
+    ground_normal: Vec3,
+    jump_requested: bool,
+}
+
+impl CharacterController {
+    pub fn new(radius: f32, half_height: f32) -> Self {
+        Self {
+            radius,
+            half_height,
+            step_height: 0.3,
+            max_slope: 45f32.to_radians(),
+            jump_speed: 5.0,
+            walk_velocity: Vec3::ZERO,
+            velocity: Vec3::ZERO,
+            vertical_speed: 0.0,
+            grounded: false,
+            ground_normal: Vec3::Y,
+            jump_requested: false,
+        }
+    }
+
+    pub fn get_radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub fn get_half_height(&self) -> f32 {
+        self.half_height
+    }
+
+    pub fn get_step_height(&self) -> f32 {
+        self.step_height
+    }
+
+    pub fn get_max_slope(&self) -> f32 {
+        self.max_slope
+    }
+
+    pub fn get_jump_speed(&self) -> f32 {
+        self.jump_speed
+    }
+
+    pub fn get_walk_velocity(&self) -> Vec3 {
+        self.walk_velocity
+    }
+
+    pub fn get_velocity(&self) -> Vec3 {
+        self.velocity
+    }
+
+    // Whether it stood on walkable ground at the end of the last step
+    pub fn get_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    // Of the ground it stands on, or straight up in the air
+    pub fn get_ground_normal(&self) -> Vec3 {
+        self.ground_normal
+    }
+
+    pub fn set_step_height(&mut self, step_height: f32) {
+        self.step_height = step_height.max(0.0);
+    }
+
+    pub fn set_max_slope(&mut self, max_slope: f32) {
+        self.max_slope = max_slope.clamp(0.0, std::f32::consts::FRAC_PI_2);
+    }
+
+    pub fn set_jump_speed(&mut self, jump_speed: f32) {
+        self.jump_speed = jump_speed;
+    }
+
+    // Keeps walking at this velocity until told otherwise
+    pub fn set_walk_velocity(&mut self, walk_velocity: Vec3) {
+        self.walk_velocity = Vec3::new(walk_velocity.x, 0.0, walk_velocity.z);
+    }
+
+    // Jumps at the next step if it's on the ground by then
+    pub fn jump(&mut self) {
+        self.jump_requested = true;
+    }
+
+    // Capsule of the character, for giving its object a matching collider
+    pub fn get_collider(&self) -> Collider {
+        Collider::capsule(self.radius, self.half_height)
+    }
+
+    // Moves the capsule from `position` for `dt` seconds among the obstacles, returning where
+    // it ends up
+    pub(crate) fn step(
+        &mut self,
+        position: Vec3,
+        obstacles: &[(&Collider, Pose)],
+        gravity: f32,
+        dt: f32,
+    ) -> Vec3 {
+        let shape = self.get_collider();
+        if self.jump_requested && self.grounded {
+            self.vertical_speed = self.jump_speed;
+            self.grounded = false;
+        }
+        self.jump_requested = false;
+        if self.grounded {
+            self.vertical_speed = 0.0;
+        } else {
+            self.vertical_speed += gravity * dt;
+        }
+
+        // Walking and falling slide separately, so walking into a wall doesn't slow a fall
+        let walk = self.walk_velocity * dt;
+        let (mut moved, _) = self.move_and_slide(&shape, position, walk, obstacles);
+        let progress = |to: Vec3| (to - position).with_y(0.0).length();
+        if self.grounded && self.step_height > 0.0 && progress(moved) < walk.length() * 0.9 {
+            // Blocked: try again from step_height up, then come back down onto the ledge
+            let up = Vec3::Y * self.step_height;
+            let (raised, _) = self.move_and_slide(&shape, position, up, obstacles);
+            let (across, _) = self.move_and_slide(&shape, raised, walk, obstacles);
+            let (stepped, _) = self.move_and_slide(&shape, across, -up, obstacles);
+            let on_ground = self.ground_below(&shape, stepped, obstacles, SKIN_WIDTH * 2.0);
+            if on_ground.is_some() && progress(stepped) > progress(moved) + SKIN_WIDTH {
+                moved = stepped;
+            }
+        }
+
+        let fall = Vec3::Y * self.vertical_speed * dt;
+        let (mut moved, normals) = self.move_and_slide(&shape, moved, fall, obstacles);
+        if self.vertical_speed > 0.0 && normals.iter().any(|normal| normal.y < -0.5) {
+            // Hit its head
+            self.vertical_speed = 0.0;
+        }
+
+        // Stay on the ground walking down slopes and stairs, unless it's going up
+        let reach = if self.grounded {
+            self.step_height
+        } else {
+            SKIN_WIDTH * 2.0
+        };
+        let ground = if self.vertical_speed > 0.0 {
+            None
+        } else {
+            self.ground_below(&shape, moved, obstacles, reach)
+        };
+        self.grounded = ground.is_some();
+        self.ground_normal = Vec3::Y;
+        if let Some((gap, normal)) = ground {
+            moved.y -= (gap - SKIN_WIDTH).max(0.0);
+            self.ground_normal = normal;
+            self.vertical_speed = 0.0;
+        }
+        self.velocity = (moved - position) / dt;
+        moved
+    }
+
+    fn walkable(&self, normal: Vec3) -> bool {
+        normal.y >= self.max_slope.cos() - 1e-4
+    }
+
+    // Moves by `displacement` in pieces short enough not to pass through anything, pushing
+    // out of overlaps after each and dropping the rest of the move into whatever it hit.
+    // Returns where it ended up and the normals of the surfaces it touched.
+    fn move_and_slide(
+        &self,
+        shape: &Collider,
+        mut position: Vec3,
+        displacement: Vec3,
+        obstacles: &[(&Collider, Pose)],
+    ) -> (Vec3, Vec<Vec3>) {
+        let pieces = (displacement.length() / (self.radius * 0.5))
+            .ceil()
+            .max(1.0) as usize;
+        let mut remaining = displacement;
+        let mut normals = Vec::new();
+        for piece in 0..pieces {
+            let delta = remaining / (pieces - piece) as f32;
+            position += delta;
+            remaining -= delta;
+            for (normal, supported) in self.resolve(shape, &mut position, obstacles) {
+                // Only slide up what it can stand on
+                let normal = if supported || normal.y < 0.0 {
+                    normal
+                } else {
+                    normal.with_y(0.0).normalize_or(normal)
+                };
+                let into = remaining.dot(normal);
+                if into < 0.0 {
+                    remaining -= normal * into;
+                }
+                normals.push(normal);
+            }
+        }
+        (position, normals)
+    }
+
+    // Pushes the capsule out of what it overlaps: straight up off what it can stand on, so
+    // it doesn't creep downhill, and never up steep slopes. Returns the normals of the
+    // surfaces, and whether each could be stood on.
+    fn resolve(
+        &self,
+        shape: &Collider,
+        position: &mut Vec3,
+        obstacles: &[(&Collider, Pose)],
+    ) -> Vec<(Vec3, bool)> {
+        let mut normals = Vec::new();
+        for _ in 0..RESOLVE_ITERATIONS {
+            let pose = Pose::from_position(*position);
+            let deepest = obstacles
+                .iter()
+                .flat_map(|(collider, obstacle)| {
+                    collide_with_margin(shape, &pose, collider, obstacle, 0.0)
+                        .into_iter()
+                        .map(move |contact| (contact, *collider, obstacle))
+                })
+                .filter(|(contact, _, _)| contact.depth > 0.0)
+                .max_by(|a, b| a.0.depth.total_cmp(&b.0.depth));
+            let Some((contact, collider, obstacle)) = deepest else {
+                break;
+            };
+            // Facing the capsule
+            let normal = -contact.normal;
+            let depth = contact.depth + SKIN_WIDTH;
+            let supported = self
+                .support(*position, &contact, collider, obstacle)
+                .is_some();
+            let push = if supported {
+                Vec3::Y * (depth / normal.y).min(self.radius)
+            } else if normal.y > 0.0 {
+                let across = normal.with_y(0.0).normalize_or(normal);
+                across * depth / across.dot(normal).max(0.1)
+            } else {
+                normal * depth
+            };
+            *position += push;
+            normals.push((normal, supported));
+        }
+        normals
+    }
+
+    // Normal of the ground the capsule at `position` could stand on where `contact` touches
+    // it: the contact's own when that isn't too steep, or else, for the edge of a ledge low
+    // enough to step onto, that of the surface just above where they touch
+    fn support(
+        &self,
+        position: Vec3,
+        contact: &Contact,
+        collider: &Collider,
+        obstacle: &Pose,
+    ) -> Option<Vec3> {
+        let normal = -contact.normal;
+        if normal.y <= 0.0 {
+            return None;
+        }
+        if self.walkable(normal) {
+            return Some(normal);
+        }
+        let lowest = contact
+            .points
+            .iter()
+            .map(|point| point.point)
+            .min_by(|a, b| a.y.total_cmp(&b.y))?;
+        let feet = position.y - self.half_height - self.radius;
+        if lowest.y - feet > self.step_height {
+            return None;
+        }
+        let probe = Collider::sphere(SKIN_WIDTH);
+        let above = Pose::from_position(lowest + Vec3::Y * (contact.depth.max(0.0) + PROBE_HEIGHT));
+        collide_with_margin(&probe, &above, collider, obstacle, PROBE_HEIGHT * 2.0)
+            .into_iter()
+            .map(|contact| -contact.normal)
+            .filter(|normal| self.walkable(*normal))
+            .max_by(|a, b| a.y.total_cmp(&b.y))
+    }
+
+    // Closest ground it could stand on within `reach` straight down: how far down it is, and
+    // its normal
+    fn ground_below(
+        &self,
+        shape: &Collider,
+        position: Vec3,
+        obstacles: &[(&Collider, Pose)],
+        reach: f32,
+    ) -> Option<(f32, Vec3)> {
+        let pose = Pose::from_position(position);
+        obstacles
+            .iter()
+            .flat_map(|(collider, obstacle)| {
+                collide_with_margin(shape, &pose, collider, obstacle, reach + SKIN_WIDTH)
+                    .into_iter()
+                    .filter_map(|contact| {
+                        let normal = self.support(position, &contact, collider, obstacle)?;
+                        let gap = (-contact.depth).max(0.0) / -contact.normal.y;
+                        Some((gap, normal))
+                    })
+            })
+            .filter(|(gap, _)| *gap <= reach + SKIN_WIDTH)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+}
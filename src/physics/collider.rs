@@ -0,0 +1,189 @@
+use crate::core::object::Object;
+use crate::geometry::bounds::Aabb;
+use crate::geometry::mesh::Mesh;
+use crate::physics::convex_hull::ConvexHull;
+use crate::physics::mass_properties::MassProperties;
+use glam::{Mat3, Vec3};
+
+// Where a collider is in the world: its local space moved by `rotation`, then `position`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub position: Vec3,
+    pub rotation: Mat3,
+}
+
+impl Pose {
+    pub fn new(position: Vec3, rotation: Mat3) -> Self {
+        Self { position, rotation }
+    }
+
+    pub fn from_position(position: Vec3) -> Self {
+        Self::new(position, Mat3::IDENTITY)
+    }
+
+    pub fn from_object(object: &Object) -> Self {
+        Self::new(*object.get_position(), object.get_rotation_matrix())
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.rotation * point + self.position
+    }
+
+    pub fn inverse_transform_point(&self, point: Vec3) -> Vec3 {
+        self.rotation.transpose() * (point - self.position)
+    }
+}
+
+#[derive(Clone)]
+pub enum ColliderShape {
+    Sphere { radius: f32 },
+    Box { half_extents: Vec3 },
+    Capsule { radius: f32, half_height: f32 }, // segment running half_height up and down y
+    ConvexHull(ConvexHull),
+    TriangleMesh(Mesh), // for static geometry; only collides with the convex shapes
+}
+
+// Shape an object collides with, in the object's local space, and how its surface behaves
+#[derive(Clone)]
+pub struct Collider {
+    shape: ColliderShape,
+    friction: f32,
+    restitution: f32, // 0 stops dead, 1 bounces back at full speed
+    // Bits for the groups the collider is in, and for the groups it collides with; two
+    // colliders meet only when each is in a group the other collides with
+    collision_layers: u32,
+    collision_mask: u32,
+    trigger: bool, // reports what overlaps it instead of pushing it away
+}
+
+impl Collider {
+    pub fn new(shape: ColliderShape) -> Self {
+        Self {
+            shape,
+            friction: 0.5,
+            restitution: 0.0,
+            collision_layers: 1,
+            collision_mask: u32::MAX,
+            trigger: false,
+        }
+    }
+
+    pub fn sphere(radius: f32) -> Self {
+        Self::new(ColliderShape::Sphere { radius })
+    }
+
+    pub fn cuboid(half_extents: Vec3) -> Self {
+        Self::new(ColliderShape::Box { half_extents })
+    }
+
+    pub fn capsule(radius: f32, half_height: f32) -> Self {
+        Self::new(ColliderShape::Capsule {
+            radius,
+            half_height,
+        })
+    }
+
+    // Hull around the mesh's vertices; None when they're all on one plane
+    pub fn convex_hull(mesh: &Mesh) -> Option<Self> {
+        Some(Self::new(ColliderShape::ConvexHull(ConvexHull::from_mesh(
+            mesh,
+        )?)))
+    }
+
+    pub fn triangle_mesh(mesh: &Mesh) -> Self {
+        Self::new(ColliderShape::TriangleMesh(mesh.clone()))
+    }
+
+    pub fn get_shape(&self) -> &ColliderShape {
+        &self.shape
+    }
+
+    pub fn get_friction(&self) -> f32 {
+        self.friction
+    }
+
+    pub fn get_restitution(&self) -> f32 {
+        self.restitution
+    }
+
+    pub fn get_collision_layers(&self) -> u32 {
+        self.collision_layers
+    }
+
+    pub fn get_collision_mask(&self) -> u32 {
+        self.collision_mask
+    }
+
+    pub fn get_trigger(&self) -> bool {
+        self.trigger
+    }
+
+    // Whether the two colliders' groups let them collide
+    pub fn interacts_with(&self, other: &Collider) -> bool {
+        self.collision_layers & other.collision_mask != 0
+            && other.collision_layers & self.collision_mask != 0
+    }
+
+    pub fn set_shape(&mut self, shape: ColliderShape) {
+        self.shape = shape;
+    }
+
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction.max(0.0);
+    }
+
+    pub fn set_restitution(&mut self, restitution: f32) {
+        self.restitution = restitution.clamp(0.0, 1.0);
+    }
+
+    pub fn set_collision_layers(&mut self, collision_layers: u32) {
+        self.collision_layers = collision_layers;
+    }
+
+    pub fn set_collision_mask(&mut self, collision_mask: u32) {
+        self.collision_mask = collision_mask;
+    }
+
+    pub fn set_trigger(&mut self, trigger: bool) {
+        self.trigger = trigger;
+    }
+
+    pub fn get_local_aabb(&self) -> Aabb {
+        match &self.shape {
+            ColliderShape::Sphere { radius } => {
+                Aabb::new(Vec3::splat(-radius), Vec3::splat(*radius))
+            }
+            ColliderShape::Box { half_extents } => Aabb::new(-*half_extents, *half_extents),
+            ColliderShape::Capsule {
+                radius,
+                half_height,
+            } => {
+                let extents = Vec3::new(*radius, radius + half_height, *radius);
+                Aabb::new(-extents, extents)
+            }
+            ColliderShape::ConvexHull(hull) => Aabb::from_points(hull.get_vertices().clone()),
+            ColliderShape::TriangleMesh(mesh) => mesh.get_aabb(),
+        }
+    }
+
+    pub fn get_world_aabb(&self, pose: &Pose) -> Aabb {
+        self.get_local_aabb()
+            .transformed(pose.rotation, pose.position)
+    }
+
+    // Mass properties of the shape as a solid of uniform density
+    pub fn get_mass_properties(&self, mass: f32) -> MassProperties {
+        match &self.shape {
+            ColliderShape::Sphere { radius } => MassProperties::from_sphere(*radius, mass),
+            ColliderShape::Box { half_extents } => MassProperties::from_box(*half_extents, mass),
+            ColliderShape::Capsule {
+                radius,
+                half_height,
+            } => MassProperties::from_capsule(*radius, *half_height, mass),
+            ColliderShape::ConvexHull(hull) => {
+                MassProperties::from_triangles(hull.get_triangles(), mass)
+            }
+            ColliderShape::TriangleMesh(mesh) => MassProperties::from_mesh(mesh, mass),
+        }
+    }
+}
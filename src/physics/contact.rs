@@ -0,0 +1,482 @@
+use crate::geometry::bounds::Aabb;
+use crate::physics::collider::{Collider, ColliderShape, Pose};
+use crate::physics::convex_hull::ConvexHull;
+use crate::physics::gjk::{self, Gjk};
+use glam::Vec3;
+
+// Most points a manifold keeps; four spread-out points hold a face steady
+const MAX_MANIFOLD_POINTS: usize = 4;
+// How far off perpendicular a capsule can lean and still rest on its whole side
+const SEGMENT_FLAT_TOLERANCE: f32 = 0.02;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactPoint {
+    pub point: Vec3, // world space, halfway between the two surfaces
+    pub depth: f32,  // how far the surfaces overlap here; negative when they're apart
+}
+
+// Where two colliders touch
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contact {
+    pub normal: Vec3, // from the first collider toward the second
+    pub depth: f32,   // distance to move them apart along the normal
+    pub points: Vec<ContactPoint>,
+}
+
+// Contacts between two colliders, empty when they don't touch. Convex shapes touch in at
+// most one contact; a triangle mesh gives one for each of its triangles that touches.
+pub fn collide(a: &Collider, pose_a: &Pose, b: &Collider, pose_b: &Pose) -> Vec<Contact> {
+    collide_with_margin(a, pose_a, b, pose_b, 0.0)
+}
+
+// Whether two colliders overlap, without working out how
+pub fn intersects(a: &Collider, pose_a: &Pose, b: &Collider, pose_b: &Pose) -> bool {
+    match (Convex::new(a, pose_a), Convex::new(b, pose_b)) {
+        (Some(a), Some(b)) => overlaps(&a, &b),
+        (None, Some(convex)) => mesh_triangles(a, pose_a, &convex, 0.0)
+            .iter()
+            .any(|triangle| overlaps(triangle, &convex)),
+        (Some(convex), None) => mesh_triangles(b, pose_b, &convex, 0.0)
+            .iter()
+            .any(|triangle| overlaps(&convex, triangle)),
+        (None, None) => false,
+    }
+}
+
+// Like collide(), but also reports surfaces up to `margin` apart, with negative depths, so
+// the solver can stop bodies before they meet
+pub(crate) fn collide_with_margin(
+    a: &Collider,
+    pose_a: &Pose,
+    b: &Collider,
+    pose_b: &Pose,
+    margin: f32,
+) -> Vec<Contact> {
+    match (Convex::new(a, pose_a), Convex::new(b, pose_b)) {
+        (Some(a), Some(b)) => collide_convex(&a, &b, margin).into_iter().collect(),
+        (None, Some(convex)) => mesh_triangles(a, pose_a, &convex, margin)
+            .iter()
+            .filter_map(|triangle| collide_convex(triangle, &convex, margin))
+            .collect(),
+        (Some(convex), None) => mesh_triangles(b, pose_b, &convex, margin)
+            .iter()
+            .filter_map(|triangle| collide_convex(&convex, triangle, margin))
+            .collect(),
+        // Meshes are for static geometry, which never needs to collide with itself
+        (None, None) => Vec::new(),
+    }
+}
+
+// The innermost part of a convex shape, which the shape surrounds by its radius
+#[derive(Clone, Copy)]
+enum Core<'a> {
+    Point,
+    Segment(f32), // half height along local y
+    Box(Vec3),    // half extents
+    Hull(&'a ConvexHull),
+    Triangle([Vec3; 3]), // local space
+}
+
+// Convex shape placed in the world
+struct Convex<'a> {
+    core: Core<'a>,
+    radius: f32,
+    pose: Pose,
+}
+
+impl<'a> Convex<'a> {
+    // None for triangle meshes, which aren't convex
+    fn new(collider: &'a Collider, pose: &Pose) -> Option<Self> {
+        let (core, radius) = match collider.get_shape() {
+            ColliderShape::Sphere { radius } => (Core::Point, *radius),
+            ColliderShape::Box { half_extents } => (Core::Box(*half_extents), 0.0),
+            ColliderShape::Capsule {
+                radius,
+                half_height,
+            } => (Core::Segment(*half_height), *radius),
+            ColliderShape::ConvexHull(hull) => (Core::Hull(hull), 0.0),
+            ColliderShape::TriangleMesh(_) => return None,
+        };
+        Some(Self {
+            core,
+            radius,
+            pose: *pose,
+        })
+    }
+
+    // Point of the core furthest along a world-space direction
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let local = self.pose.rotation.transpose() * direction;
+        let point = match self.core {
+            Core::Point => Vec3::ZERO,
+            Core::Segment(half_height) => Vec3::new(0.0, half_height.copysign(local.y), 0.0),
+            Core::Box(half_extents) => Vec3::new(
+                half_extents.x.copysign(local.x),
+                half_extents.y.copysign(local.y),
+                half_extents.z.copysign(local.z),
+            ),
+            Core::Hull(hull) => hull.support(local),
+            Core::Triangle(vertices) => vertices
+                .into_iter()
+                .max_by(|a, b| a.dot(local).total_cmp(&b.dot(local)))
+                .unwrap(),
+        };
+        self.pose.transform_point(point)
+    }
+
+    // World-space corners of the part of the core facing `direction` (a face, an edge or a
+    // single point) and the normal of that part when it's a face
+    fn feature(&self, direction: Vec3) -> (Vec<Vec3>, Vec3) {
+        let local = self.pose.rotation.transpose() * direction;
+        let (points, normal) = match self.core {
+            Core::Point => (vec![Vec3::ZERO], Vec3::ZERO),
+            Core::Segment(half_height) => {
+                let ends = [
+                    Vec3::new(0.0, -half_height, 0.0),
+                    Vec3::new(0.0, half_height, 0.0),
+                ];
+                if local.normalize_or_zero().y.abs() <= SEGMENT_FLAT_TOLERANCE {
+                    (ends.to_vec(), Vec3::ZERO)
+                } else {
+                    (vec![ends[(local.y > 0.0) as usize]], Vec3::ZERO)
+                }
+            }
+            Core::Box(half_extents) => {
+                let axis = local.abs().max_position();
+                let sign = local[axis].signum();
+                let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+                let corner = |s: f32, t: f32| {
+                    let mut corner = Vec3::ZERO;
+                    corner[axis] = sign * half_extents[axis];
+                    corner[u] = s * half_extents[u];
+                    corner[v] = t * half_extents[v];
+                    corner
+                };
+                let mut normal = Vec3::ZERO;
+                normal[axis] = sign;
+                (
+                    vec![
+                        corner(1.0, 1.0),
+                        corner(-1.0, 1.0),
+                        corner(-1.0, -1.0),
+                        corner(1.0, -1.0),
+                    ],
+                    normal,
+                )
+            }
+            Core::Hull(hull) => {
+                let face = hull.support_face(local);
+                let points = face
+                    .vertices
+                    .iter()
+                    .map(|&vertex| hull.get_vertices()[vertex])
+                    .collect();
+                (points, face.normal)
+            }
+            Core::Triangle(vertices) => {
+                let normal = (vertices[1] - vertices[0])
+                    .cross(vertices[2] - vertices[0])
+                    .normalize_or_zero();
+                let normal = if normal.dot(local) < 0.0 {
+                    -normal
+                } else {
+                    normal
+                };
+                (vertices.to_vec(), normal)
+            }
+        };
+        (
+            points
+                .into_iter()
+                .map(|point| self.pose.transform_point(point))
+                .collect(),
+            self.pose.rotation * normal,
+        )
+    }
+
+    // Directions worth trying as separating axes when the penetration can't be found exactly
+    fn axes(&self) -> Vec<Vec3> {
+        let columns = [
+            self.pose.rotation.x_axis,
+            self.pose.rotation.y_axis,
+            self.pose.rotation.z_axis,
+        ];
+        match self.core {
+            Core::Point => Vec::new(),
+            Core::Segment(_) | Core::Box(_) => columns.to_vec(),
+            Core::Hull(hull) => hull
+                .get_faces()
+                .iter()
+                .map(|face| self.pose.rotation * face.normal)
+                .collect(),
+            Core::Triangle(_) => vec![self.feature(Vec3::ONE).1],
+        }
+    }
+
+    fn get_center(&self) -> Vec3 {
+        match self.core {
+            Core::Triangle(vertices) => self
+                .pose
+                .transform_point((vertices[0] + vertices[1] + vertices[2]) / 3.0),
+            _ => self.pose.position,
+        }
+    }
+
+    fn get_world_aabb(&self, margin: f32) -> Aabb {
+        let min = Vec3::new(
+            self.support(-Vec3::X).x,
+            self.support(-Vec3::Y).y,
+            self.support(-Vec3::Z).z,
+        );
+        let max = Vec3::new(
+            self.support(Vec3::X).x,
+            self.support(Vec3::Y).y,
+            self.support(Vec3::Z).z,
+        );
+        let padding = Vec3::splat(self.radius + margin);
+        Aabb::new(min - padding, max + padding)
+    }
+}
+
+fn overlaps(a: &Convex, b: &Convex) -> bool {
+    let support_a = |direction| a.support(direction);
+    let support_b = |direction| b.support(direction);
+    match gjk::gjk(&support_a, &support_b, a.get_center() - b.get_center()) {
+        Gjk::Separated { distance, .. } => distance <= a.radius + b.radius,
+        Gjk::Overlapping(_) => true,
+    }
+}
+
+// The mesh's triangles near `convex`, as world-space convex shapes
+fn mesh_triangles<'a>(
+    mesh: &Collider,
+    pose: &Pose,
+    convex: &Convex,
+    margin: f32,
+) -> Vec<Convex<'a>> {
+    let ColliderShape::TriangleMesh(mesh) = mesh.get_shape() else {
+        return Vec::new();
+    };
+    let inverse_rotation = pose.rotation.transpose();
+    let local_aabb = convex
+        .get_world_aabb(margin)
+        .transformed(inverse_rotation, -(inverse_rotation * pose.position));
+    mesh.query_aabb(&local_aabb)
+        .into_iter()
+        .map(|index| Convex {
+            core: Core::Triangle(mesh.get_triangles()[index].get_vertices()),
+            radius: 0.0,
+            pose: *pose,
+        })
+        .collect()
+}
+
+// Contact between two convex shapes when they're closer than `margin`. The cores' closest
+// points come from GJK when they're apart and from EPA when they overlap; the manifold then
+// clips the facing features against each other.
+fn collide_convex(a: &Convex, b: &Convex, margin: f32) -> Option<Contact> {
+    let support_a = |direction| a.support(direction);
+    let support_b = |direction| b.support(direction);
+    let radius = a.radius + b.radius;
+
+    let (normal, depth, a_point, b_point) =
+        match gjk::gjk(&support_a, &support_b, a.get_center() - b.get_center()) {
+            Gjk::Separated {
+                distance,
+                a: a_point,
+                b: b_point,
+            } if distance > 1e-6 => {
+                if distance > radius + margin {
+                    return None;
+                }
+                let normal = (b_point - a_point) / distance;
+                (normal, radius - distance, a_point, b_point)
+            }
+            Gjk::Overlapping(simplex) => match gjk::epa(&support_a, &support_b, simplex) {
+                Some(penetration) if penetration.normal != Vec3::ZERO => (
+                    penetration.normal,
+                    penetration.depth + radius,
+                    penetration.a,
+                    penetration.b,
+                ),
+                _ => separating_axis(a, b, radius),
+            },
+            Gjk::Separated { .. } => separating_axis(a, b, radius),
+        };
+    let a_point = a_point + normal * a.radius;
+    let b_point = b_point - normal * b.radius;
+
+    let points = manifold(a, b, normal, margin).unwrap_or_else(|| {
+        vec![ContactPoint {
+            point: (a_point + b_point) * 0.5,
+            depth,
+        }]
+    });
+    Some(Contact {
+        normal,
+        depth,
+        points,
+    })
+}
+
+// Axis of least overlap among the shapes' own axes, for overlaps EPA can't resolve, like a
+// sphere's center sitting exactly on a triangle
+fn separating_axis(a: &Convex, b: &Convex, radius: f32) -> (Vec3, f32, Vec3, Vec3) {
+    let mut axes = a.axes();
+    axes.extend(b.axes());
+    axes.push((b.get_center() - a.get_center()).normalize_or_zero());
+    axes.push(Vec3::Y);
+    let (normal, depth) = axes
+        .into_iter()
+        .filter(|axis| *axis != Vec3::ZERO)
+        .flat_map(|axis| [axis, -axis])
+        .map(|axis| {
+            let overlap = a.support(axis).dot(axis) - b.support(-axis).dot(axis) + radius;
+            (axis, overlap)
+        })
+        .min_by(|x, y| x.1.total_cmp(&y.1))
+        .unwrap();
+    (normal, depth, a.support(normal), b.support(-normal))
+}
+
+// Contact points from clipping the feature of one shape facing the other against the
+// feature facing back; None when either feature is a single point
+fn manifold(a: &Convex, b: &Convex, normal: Vec3, margin: f32) -> Option<Vec<ContactPoint>> {
+    let (a_feature, a_normal) = a.feature(normal);
+    let (b_feature, b_normal) = b.feature(-normal);
+    if a_feature.len() < 2 || b_feature.len() < 2 {
+        return None;
+    }
+    let a_feature: Vec<Vec3> = a_feature
+        .into_iter()
+        .map(|point| point + normal * a.radius)
+        .collect();
+    let b_feature: Vec<Vec3> = b_feature
+        .into_iter()
+        .map(|point| point - normal * b.radius)
+        .collect();
+
+    // The face more square to the normal is the reference the other is clipped against;
+    // a slight bias toward A keeps the choice from flipping between frames
+    let a_alignment = a_normal.dot(normal);
+    let b_alignment = -b_normal.dot(normal);
+    let (reference, incident, reference_normal) = if a_alignment + 1e-3 >= b_alignment {
+        let reference_normal = if a_normal == Vec3::ZERO {
+            normal
+        } else {
+            a_normal
+        };
+        (a_feature, b_feature, reference_normal)
+    } else {
+        (b_feature, a_feature, b_normal)
+    };
+
+    let mut clipped = incident;
+    for (plane_point, plane_normal) in side_planes(&reference, reference_normal) {
+        clipped = clip(&clipped, plane_point, plane_normal);
+        if clipped.is_empty() {
+            return None;
+        }
+    }
+
+    let origin = reference[0];
+    let mut points: Vec<ContactPoint> = Vec::new();
+    for point in clipped {
+        let separation = reference_normal.dot(point - origin);
+        let depth = -separation;
+        let duplicate = points
+            .iter()
+            .any(|existing| existing.point.distance_squared(point) <= 1e-8);
+        if depth >= -margin && !duplicate {
+            points.push(ContactPoint {
+                point: point - reference_normal * (separation * 0.5),
+                depth,
+            });
+        }
+    }
+    if points.is_empty() {
+        return None;
+    }
+    Some(reduce_points(points))
+}
+
+// Planes through the reference feature's edges, facing out, that bound where contacts go
+fn side_planes(reference: &[Vec3], normal: Vec3) -> Vec<(Vec3, Vec3)> {
+    if let [start, end] = *reference {
+        let direction = (end - start).normalize_or_zero();
+        return vec![(start, -direction), (end, direction)];
+    }
+    let center = reference.iter().sum::<Vec3>() / reference.len() as f32;
+    (0..reference.len())
+        .map(|index| {
+            let start = reference[index];
+            let end = reference[(index + 1) % reference.len()];
+            let outward = (end - start).cross(normal).normalize_or_zero();
+            let outward = if outward.dot(center - start) > 0.0 {
+                -outward
+            } else {
+                outward
+            };
+            (start, outward)
+        })
+        .collect()
+}
+
+// Part of a polygon, or of a segment, behind a plane (Sutherland-Hodgman)
+fn clip(points: &[Vec3], plane_point: Vec3, plane_normal: Vec3) -> Vec<Vec3> {
+    let distance = |point: Vec3| plane_normal.dot(point - plane_point);
+    let crossing = |start: Vec3, end: Vec3| {
+        let (start_distance, end_distance) = (distance(start), distance(end));
+        start + (end - start) * (start_distance / (start_distance - end_distance))
+    };
+
+    if let [start, end] = *points {
+        return match (distance(start) <= 0.0, distance(end) <= 0.0) {
+            (true, true) => vec![start, end],
+            (true, false) => vec![start, crossing(start, end)],
+            (false, true) => vec![crossing(start, end), end],
+            (false, false) => Vec::new(),
+        };
+    }
+
+    let mut clipped = Vec::new();
+    for index in 0..points.len() {
+        let start = points[index];
+        let end = points[(index + 1) % points.len()];
+        let start_inside = distance(start) <= 0.0;
+        let end_inside = distance(end) <= 0.0;
+        if start_inside {
+            clipped.push(start);
+        }
+        if start_inside != end_inside {
+            clipped.push(crossing(start, end));
+        }
+    }
+    clipped
+}
+
+// Keeps the deepest point and the ones spreading the manifold widest
+fn reduce_points(mut points: Vec<ContactPoint>) -> Vec<ContactPoint> {
+    if points.len() <= MAX_MANIFOLD_POINTS {
+        return points;
+    }
+    let mut kept = Vec::with_capacity(MAX_MANIFOLD_POINTS);
+    let deepest = (0..points.len())
+        .max_by(|&a, &b| points[a].depth.total_cmp(&points[b].depth))
+        .unwrap();
+    kept.push(points.swap_remove(deepest));
+    while kept.len() < MAX_MANIFOLD_POINTS {
+        let furthest = (0..points.len())
+            .max_by(|&a, &b| {
+                let distance = |point: &ContactPoint| {
+                    kept.iter()
+                        .map(|kept: &ContactPoint| kept.point.distance_squared(point.point))
+                        .fold(f32::INFINITY, f32::min)
+                };
+                distance(&points[a]).total_cmp(&distance(&points[b]))
+            })
+            .unwrap();
+        kept.push(points.swap_remove(furthest));
+    }
+    kept
+}
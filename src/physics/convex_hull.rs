@@ -0,0 +1,215 @@
+use crate::geometry::mesh::Mesh;
+use glam::Vec3;
+use std::collections::HashSet;
+
+// Flat side of a hull, its vertices wound counterclockwise seen from outside
+#[derive(Clone, Debug)]
+pub struct HullFace {
+    pub normal: Vec3,         // outward
+    pub vertices: Vec<usize>, // indices into the hull's vertices
+}
+
+// Smallest convex polyhedron holding a set of points
+#[derive(Clone, Debug)]
+pub struct ConvexHull {
+    vertices: Vec<Vec3>,
+    faces: Vec<HullFace>,
+}
+
+impl ConvexHull {
+    // Built incrementally: start from a tetrahedron of extreme points, then add each point
+    // outside the hull so far, replacing the faces it can see with a fan from their horizon.
+    // None when the points are all on one plane.
+    pub fn new(points: &[Vec3]) -> Option<Self> {
+        let scale = points
+            .iter()
+            .fold(0.0f32, |scale, point| scale.max(point.abs().max_element()));
+        let tolerance = scale.max(1.0) * 1e-5;
+        let [a, b, c, d] = initial_tetrahedron(points, tolerance)?;
+
+        // Triangles as vertex indices into `points`, wound counterclockwise from outside
+        let mut triangles = vec![[a, b, c], [a, c, d], [a, d, b], [b, d, c]];
+        if plane_distance(points, [a, b, c], points[d]) > 0.0 {
+            for triangle in &mut triangles {
+                triangle.swap(1, 2);
+            }
+        }
+
+        for (index, &point) in points.iter().enumerate() {
+            let visible: Vec<bool> = triangles
+                .iter()
+                .map(|&triangle| plane_distance(points, triangle, point) > tolerance)
+                .collect();
+            if !visible.contains(&true) {
+                continue;
+            }
+            // Edges of visible triangles whose neighbor across them isn't visible
+            let visible_edges: HashSet<(usize, usize)> = triangles
+                .iter()
+                .zip(&visible)
+                .filter(|(_, visible)| **visible)
+                .flat_map(|(&[a, b, c], _)| [(a, b), (b, c), (c, a)])
+                .collect();
+            let horizon: Vec<(usize, usize)> = visible_edges
+                .iter()
+                .filter(|&&(a, b)| !visible_edges.contains(&(b, a)))
+                .copied()
+                .collect();
+            let mut kept = visible.iter();
+            triangles.retain(|_| !*kept.next().unwrap());
+            triangles.extend(horizon.into_iter().map(|(a, b)| [a, b, index]));
+        }
+
+        Some(Self::from_triangles(points, &triangles))
+    }
+
+    // Hull of a mesh's vertices
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        Self::new(&mesh.get_vertices())
+    }
+
+    pub fn get_vertices(&self) -> &Vec<Vec3> {
+        &self.vertices
+    }
+
+    pub fn get_faces(&self) -> &Vec<HullFace> {
+        &self.faces
+    }
+
+    // Vertex furthest along `direction`
+    pub fn support(&self, direction: Vec3) -> Vec3 {
+        self.vertices
+            .iter()
+            .copied()
+            .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+            .unwrap()
+    }
+
+    // Face whose normal is closest to `direction`
+    pub fn support_face(&self, direction: Vec3) -> &HullFace {
+        self.faces
+            .iter()
+            .max_by(|a, b| a.normal.dot(direction).total_cmp(&b.normal.dot(direction)))
+            .unwrap()
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.faces
+            .iter()
+            .all(|face| face.normal.dot(point - self.vertices[face.vertices[0]]) <= 0.0)
+    }
+
+    // Fans of the faces, wound counterclockwise from outside
+    pub fn get_triangles(&self) -> Vec<[Vec3; 3]> {
+        self.faces
+            .iter()
+            .flat_map(|face| {
+                let first = self.vertices[face.vertices[0]];
+                face.vertices
+                    .windows(2)
+                    .skip(1)
+                    .map(move |pair| [first, self.vertices[pair[0]], self.vertices[pair[1]]])
+            })
+            .collect()
+    }
+
+    // Merges the hull's triangles into flat faces and keeps only the points they use
+    fn from_triangles(points: &[Vec3], triangles: &[[usize; 3]]) -> Self {
+        let normal = |&[a, b, c]: &[usize; 3]| {
+            (points[b] - points[a])
+                .cross(points[c] - points[a])
+                .normalize_or_zero()
+        };
+
+        let mut remap = vec![usize::MAX; points.len()];
+        let mut vertices = Vec::new();
+        let mut faces: Vec<HullFace> = Vec::new();
+        let mut merged = vec![false; triangles.len()];
+        for (index, triangle) in triangles.iter().enumerate() {
+            if merged[index] {
+                continue;
+            }
+            let face_normal = normal(triangle);
+            let offset = face_normal.dot(points[triangle[0]]);
+            let mut corners: Vec<usize> = Vec::new();
+            for (other, other_triangle) in triangles.iter().enumerate().skip(index) {
+                let coplanar = normal(other_triangle).dot(face_normal) > 1.0 - 1e-4
+                    && other_triangle
+                        .iter()
+                        .all(|&vertex| (face_normal.dot(points[vertex]) - offset).abs() < 1e-4);
+                if !merged[other] && coplanar {
+                    merged[other] = true;
+                    for &vertex in other_triangle {
+                        if !corners.contains(&vertex) {
+                            corners.push(vertex);
+                        }
+                    }
+                }
+            }
+
+            // Wind the corners counterclockwise around the normal
+            let center =
+                corners.iter().map(|&vertex| points[vertex]).sum::<Vec3>() / corners.len() as f32;
+            let (tangent, bitangent) = face_normal.any_orthonormal_pair();
+            corners.sort_by(|&a, &b| {
+                let angle = |vertex: usize| {
+                    let offset = points[vertex] - center;
+                    offset.dot(bitangent).atan2(offset.dot(tangent))
+                };
+                angle(a).total_cmp(&angle(b))
+            });
+
+            let corners = corners
+                .into_iter()
+                .map(|vertex| {
+                    if remap[vertex] == usize::MAX {
+                        remap[vertex] = vertices.len();
+                        vertices.push(points[vertex]);
+                    }
+                    remap[vertex]
+                })
+                .collect();
+            faces.push(HullFace {
+                normal: face_normal,
+                vertices: corners,
+            });
+        }
+
+        Self { vertices, faces }
+    }
+}
+
+// How far `point` is in front of the plane of a counterclockwise triangle
+fn plane_distance(points: &[Vec3], [a, b, c]: [usize; 3], point: Vec3) -> f32 {
+    let normal = (points[b] - points[a])
+        .cross(points[c] - points[a])
+        .normalize_or_zero();
+    normal.dot(point - points[a])
+}
+
+// Four points spanning as much volume as a quick search finds, or None when they're flat
+fn initial_tetrahedron(points: &[Vec3], tolerance: f32) -> Option<[usize; 4]> {
+    let furthest = |score: &dyn Fn(Vec3) -> f32| {
+        (0..points.len()).max_by(|&a, &b| score(points[a]).total_cmp(&score(points[b])))
+    };
+
+    let a = furthest(&|point| point.x)?;
+    let b = furthest(&|point| point.distance_squared(points[a]))?;
+    let axis = (points[b] - points[a]).normalize_or_zero();
+    if axis == Vec3::ZERO {
+        return None;
+    }
+    let c = furthest(&|point| {
+        let offset = point - points[a];
+        (offset - axis * offset.dot(axis)).length_squared()
+    })?;
+    let normal = axis.cross(points[c] - points[a]).normalize_or_zero();
+    if normal == Vec3::ZERO {
+        return None;
+    }
+    let d = furthest(&|point| normal.dot(point - points[a]).abs())?;
+    if normal.dot(points[d] - points[a]).abs() <= tolerance {
+        return None;
+    }
+    Some([a, b, c, d])
+}
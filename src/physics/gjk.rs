@@ -0,0 +1,390 @@
+use glam::Vec3;
+use std::collections::HashSet;
+
+const MAX_ITERATIONS: usize = 64;
+const RELATIVE_TOLERANCE: f32 = 1e-6;
+const EPA_TOLERANCE: f32 = 1e-4;
+const OVERLAP_TOLERANCE: f32 = 1e-5;
+
+// Point of the Minkowski difference A - B and the points of A and B it came from
+#[derive(Clone, Copy, Debug)]
+pub struct SupportPoint {
+    pub point: Vec3,
+    pub a: Vec3,
+    pub b: Vec3,
+}
+
+pub enum Gjk {
+    // Closest points of the two shapes
+    Separated { distance: f32, a: Vec3, b: Vec3 },
+    // Simplex of the Minkowski difference that touches or holds the origin, for epa()
+    Overlapping(Vec<SupportPoint>),
+}
+
+// How far two overlapping shapes must move apart along `normal`, which points from A to B,
+// and the deepest points of each
+pub struct Penetration {
+    pub normal: Vec3,
+    pub depth: f32,
+    pub a: Vec3,
+    pub b: Vec3,
+}
+
+// A convex shape given by the point of it furthest along a direction
+pub type Support<'a> = &'a dyn Fn(Vec3) -> Vec3;
+
+fn support(support_a: Support, support_b: Support, direction: Vec3) -> SupportPoint {
+    let a = support_a(direction);
+    let b = support_b(-direction);
+    SupportPoint { point: a - b, a, b }
+}
+
+// Gilbert-Johnson-Keerthi distance: walks a simplex of the Minkowski difference toward the
+// origin until it can't get closer (separated) or encloses it (overlapping)
+pub fn gjk(support_a: Support, support_b: Support, direction: Vec3) -> Gjk {
+    let direction = if direction.length_squared() > 0.0 {
+        direction
+    } else {
+        Vec3::X
+    };
+    let mut simplex = vec![support(support_a, support_b, direction)];
+    let mut weights = vec![1.0];
+    let mut closest = simplex[0].point;
+
+    for _ in 0..MAX_ITERATIONS {
+        // Closer than rounding error in the simplex's coordinates counts as touching, since
+        // the direction toward the origin is only noise by then
+        let scale = simplex.iter().fold(0.0f32, |scale, point| {
+            scale.max(point.point.abs().max_element())
+        });
+        let distance_squared = closest.length_squared();
+        if distance_squared <= (OVERLAP_TOLERANCE * scale.max(1.0)).powi(2) {
+            return Gjk::Overlapping(simplex);
+        }
+        let next = support(support_a, support_b, -closest);
+        let duplicate = simplex
+            .iter()
+            .any(|point| point.point.distance_squared(next.point) <= f32::EPSILON);
+        if duplicate
+            || distance_squared - closest.dot(next.point) <= RELATIVE_TOLERANCE * distance_squared
+        {
+            break;
+        }
+        simplex.push(next);
+        (simplex, weights) = reduce(&simplex);
+        closest = combine(&simplex, &weights, |point| point.point);
+        if simplex.len() == 4 {
+            return Gjk::Overlapping(simplex);
+        }
+    }
+
+    Gjk::Separated {
+        distance: closest.length(),
+        a: combine(&simplex, &weights, |point| point.a),
+        b: combine(&simplex, &weights, |point| point.b),
+    }
+}
+
+// Expanding polytope algorithm: grows the overlapping simplex into a polytope inside the
+// Minkowski difference until the face nearest the origin is on its surface. None when the
+// difference is flat, as between a point and a triangle.
+pub fn epa(
+    support_a: Support,
+    support_b: Support,
+    simplex: Vec<SupportPoint>,
+) -> Option<Penetration> {
+    let mut vertices = simplex;
+    expand_to_tetrahedron(support_a, support_b, &mut vertices)?;
+
+    let center = vertices.iter().map(|vertex| vertex.point).sum::<Vec3>() / 4.0;
+    let mut faces = vec![[0, 1, 2], [0, 2, 3], [0, 3, 1], [1, 3, 2]];
+    for face in &mut faces {
+        let (normal, _) = face_plane(&vertices, *face);
+        if normal.dot(vertices[face[0]].point - center) < 0.0 {
+            face.swap(1, 2);
+        }
+    }
+
+    let mut nearest = (faces[0], Vec3::ZERO, f32::INFINITY);
+    for _ in 0..MAX_ITERATIONS {
+        nearest = faces
+            .iter()
+            .map(|&face| {
+                let (normal, distance) = face_plane(&vertices, face);
+                let distance = if normal == Vec3::ZERO {
+                    f32::INFINITY
+                } else {
+                    distance
+                };
+                (face, normal, distance)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))?;
+        let (_, normal, distance) = nearest;
+        if !distance.is_finite() {
+            return None;
+        }
+
+        let next = support(support_a, support_b, normal);
+        let scale = next.point.length().max(1.0);
+        if next.point.dot(normal) - distance <= EPA_TOLERANCE * scale {
+            break;
+        }
+
+        // Replace the faces the new point sees with a fan from their horizon
+        let index = vertices.len();
+        vertices.push(next);
+        let visible: Vec<bool> = faces
+            .iter()
+            .map(|&face| {
+                let (normal, _) = face_plane(&vertices, face);
+                normal.dot(next.point - vertices[face[0]].point) > 0.0
+            })
+            .collect();
+        let edges: HashSet<(usize, usize)> = faces
+            .iter()
+            .zip(&visible)
+            .filter(|(_, visible)| **visible)
+            .flat_map(|(&[a, b, c], _)| [(a, b), (b, c), (c, a)])
+            .collect();
+        if edges.is_empty() {
+            break;
+        }
+        let mut kept = visible.iter();
+        faces.retain(|_| !*kept.next().unwrap());
+        faces.extend(
+            edges
+                .iter()
+                .filter(|&&(a, b)| !edges.contains(&(b, a)))
+                .map(|&(a, b)| [a, b, index]),
+        );
+    }
+
+    let (face, normal, depth) = nearest;
+    let [a, b, c] = face.map(|index| vertices[index]);
+    let weights = barycentric(normal * depth, a.point, b.point, c.point);
+    Some(Penetration {
+        normal,
+        depth: depth.max(0.0),
+        a: a.a * weights.x + b.a * weights.y + c.a * weights.z,
+        b: a.b * weights.x + b.b * weights.y + c.b * weights.z,
+    })
+}
+
+fn combine(
+    simplex: &[SupportPoint],
+    weights: &[f32],
+    part: impl Fn(&SupportPoint) -> Vec3,
+) -> Vec3 {
+    simplex
+        .iter()
+        .zip(weights)
+        .map(|(point, weight)| part(point) * *weight)
+        .sum()
+}
+
+// Smallest part of the simplex holding its point closest to the origin, with the weights
+// that make that point
+fn reduce(simplex: &[SupportPoint]) -> (Vec<SupportPoint>, Vec<f32>) {
+    match *simplex {
+        [a] => (vec![a], vec![1.0]),
+        [a, b] => reduce_segment(a, b),
+        [a, b, c] => reduce_triangle(a, b, c),
+        [a, b, c, d] => reduce_tetrahedron(a, b, c, d),
+        _ => unreachable!(),
+    }
+}
+
+fn reduce_segment(a: SupportPoint, b: SupportPoint) -> (Vec<SupportPoint>, Vec<f32>) {
+    let ab = b.point - a.point;
+    let length_squared = ab.length_squared();
+    if length_squared <= f32::EPSILON {
+        return (vec![a], vec![1.0]);
+    }
+    let t = -a.point.dot(ab) / length_squared;
+    if t <= 0.0 {
+        (vec![a], vec![1.0])
+    } else if t >= 1.0 {
+        (vec![b], vec![1.0])
+    } else {
+        (vec![a, b], vec![1.0 - t, t])
+    }
+}
+
+// Region tests from Ericson, "Real-Time Collision Detection", 5.1.5, with the origin as the
+// query point
+fn reduce_triangle(
+    a: SupportPoint,
+    b: SupportPoint,
+    c: SupportPoint,
+) -> (Vec<SupportPoint>, Vec<f32>) {
+    let ab = b.point - a.point;
+    let ac = c.point - a.point;
+    if ab.cross(ac).length_squared() <= f32::EPSILON * ab.length_squared() * ac.length_squared() {
+        return closest_of([
+            reduce_segment(a, b),
+            reduce_segment(a, c),
+            reduce_segment(b, c),
+        ]);
+    }
+
+    let d1 = ab.dot(-a.point);
+    let d2 = ac.dot(-a.point);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (vec![a], vec![1.0]);
+    }
+    let d3 = ab.dot(-b.point);
+    let d4 = ac.dot(-b.point);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (vec![b], vec![1.0]);
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let t = d1 / (d1 - d3);
+        return (vec![a, b], vec![1.0 - t, t]);
+    }
+    let d5 = ab.dot(-c.point);
+    let d6 = ac.dot(-c.point);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (vec![c], vec![1.0]);
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let t = d2 / (d2 - d6);
+        return (vec![a, c], vec![1.0 - t, t]);
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (vec![b, c], vec![1.0 - t, t]);
+    }
+    let denominator = 1.0 / (va + vb + vc);
+    let v = vb * denominator;
+    let w = vc * denominator;
+    (vec![a, b, c], vec![1.0 - v - w, v, w])
+}
+
+fn reduce_tetrahedron(
+    a: SupportPoint,
+    b: SupportPoint,
+    c: SupportPoint,
+    d: SupportPoint,
+) -> (Vec<SupportPoint>, Vec<f32>) {
+    let flat = (b.point - a.point)
+        .cross(c.point - a.point)
+        .dot(d.point - a.point)
+        .abs()
+        <= f32::EPSILON;
+    // Each face with the vertex opposite it; the origin is past a face when it's on the
+    // other side from that vertex
+    let faces = [(a, b, c, d), (a, c, d, b), (a, d, b, c), (b, d, c, a)];
+    let outside: Vec<_> = faces
+        .iter()
+        .filter(|(a, b, c, opposite)| {
+            let normal = (b.point - a.point).cross(c.point - a.point);
+            flat || normal.dot(-a.point) * normal.dot(opposite.point - a.point) < 0.0
+        })
+        .map(|&(a, b, c, _)| reduce_triangle(a, b, c))
+        .collect();
+    if outside.is_empty() {
+        return (vec![a, b, c, d], vec![0.25; 4]);
+    }
+    closest_of(outside)
+}
+
+fn closest_of(
+    candidates: impl IntoIterator<Item = (Vec<SupportPoint>, Vec<f32>)>,
+) -> (Vec<SupportPoint>, Vec<f32>) {
+    candidates
+        .into_iter()
+        .min_by(|(a, a_weights), (b, b_weights)| {
+            let a = combine(a, a_weights, |point| point.point).length_squared();
+            let b = combine(b, b_weights, |point| point.point).length_squared();
+            a.total_cmp(&b)
+        })
+        .unwrap()
+}
+
+// Adds support points until the simplex has volume; None when the difference is flat
+fn expand_to_tetrahedron(
+    support_a: Support,
+    support_b: Support,
+    vertices: &mut Vec<SupportPoint>,
+) -> Option<()> {
+    let tolerance = 1e-6;
+    let try_add = |vertices: &mut Vec<SupportPoint>, directions: &[Vec3]| {
+        for &direction in directions {
+            let next = support(support_a, support_b, direction);
+            let adds_dimension = match vertices[..] {
+                [a] => next.point.distance(a.point) > tolerance,
+                [a, b] => {
+                    let axis = (b.point - a.point).normalize();
+                    (next.point - a.point).cross(axis).length() > tolerance
+                }
+                [a, b, c] => {
+                    let normal = (b.point - a.point).cross(c.point - a.point).normalize();
+                    normal.dot(next.point - a.point).abs() > tolerance
+                }
+                _ => false,
+            };
+            if adds_dimension {
+                vertices.push(next);
+                return true;
+            }
+        }
+        false
+    };
+
+    vertices.truncate(4);
+    while vertices.len() < 4 {
+        let directions: Vec<Vec3> = match vertices[..] {
+            [_] => vec![Vec3::X, -Vec3::X, Vec3::Y, -Vec3::Y, Vec3::Z, -Vec3::Z],
+            [a, b] => {
+                let axis = (b.point - a.point).normalize();
+                let (tangent, bitangent) = axis.any_orthonormal_pair();
+                (0..6)
+                    .map(|step| {
+                        let angle = step as f32 * std::f32::consts::FRAC_PI_3;
+                        tangent * angle.cos() + bitangent * angle.sin()
+                    })
+                    .collect()
+            }
+            [a, b, c] => {
+                let normal = (b.point - a.point).cross(c.point - a.point).normalize();
+                vec![normal, -normal]
+            }
+            _ => unreachable!(),
+        };
+        if !try_add(vertices, &directions) {
+            return None;
+        }
+    }
+    Some(())
+}
+
+// Outward normal of a face and its distance from the origin
+fn face_plane(vertices: &[SupportPoint], [a, b, c]: [usize; 3]) -> (Vec3, f32) {
+    let a = vertices[a].point;
+    let normal = (vertices[b].point - a)
+        .cross(vertices[c].point - a)
+        .normalize_or_zero();
+    (normal, normal.dot(a))
+}
+
+// Weights of a, b and c that make `point`, which lies in their plane
+fn barycentric(point: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d00 = ab.dot(ab);
+    let d01 = ab.dot(ac);
+    let d11 = ac.dot(ac);
+    let d20 = ap.dot(ab);
+    let d21 = ap.dot(ac);
+    let denominator = d00 * d11 - d01 * d01;
+    if denominator.abs() <= f32::EPSILON {
+        return Vec3::new(1.0, 0.0, 0.0);
+    }
+    let v = (d11 * d20 - d01 * d21) / denominator;
+    let w = (d00 * d21 - d01 * d20) / denominator;
+    Vec3::new(1.0 - v - w, v, w)
+}
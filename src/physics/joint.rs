@@ -0,0 +1,343 @@
+use crate::physics::solver::{Jacobian, Row, SolverBody};
+use glam::{Quat, Vec3};
+
+// Drives a hinge's angle or a slider's offset at a steady speed, pushing with at most
+// max_force to do it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JointMotor {
+    pub target_velocity: f32, // radians per second for hinges, meters per second for sliders
+    pub max_force: f32,       // newton meters for hinges, newtons for sliders
+}
+
+impl JointMotor {
+    pub fn new(target_velocity: f32, max_force: f32) -> Self {
+        Self {
+            target_velocity,
+            max_force,
+        }
+    }
+}
+
+// How a joint holds its two objects together. Axes are in world space, as the objects stand
+// when the joint first steps, and limits count from where they stood then.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JointKind {
+    Fixed,      // the second object keeps its pose relative to the first
+    BallSocket, // the anchors stay together, and the objects turn freely about them
+    Hinge {
+        axis: Vec3,                 // through the anchor
+        limits: Option<(f32, f32)>, // lowest and highest angle, in radians
+        motor: Option<JointMotor>,
+    },
+    Slider {
+        axis: Vec3, // the only direction the second object can move, without turning
+        limits: Option<(f32, f32)>, // lowest and highest offset along the axis
+        motor: Option<JointMotor>,
+    },
+    Distance {
+        min_length: f32, // between the anchors; equal lengths make a rod, a min of 0 a rope
+        max_length: f32,
+    },
+    Spring {
+        rest_length: f32,
+        stiffness: f32, // newtons per meter
+        damping: f32,   // newton seconds per meter
+    },
+}
+
+// The joint's anchors and axis on each body, and how the bodies were turned relative to
+// each other, taken from their poses the first time the joint steps
+#[derive(Clone, Copy, Debug)]
+struct JointFrame {
+    local_anchor_a: Vec3, // from each body's center of mass, in its own space
+    local_anchor_b: Vec3,
+    local_axis_a: Vec3,
+    local_axis_b: Vec3,
+    reference: Quat, // the second body's rotation in the first's space
+}
+
+// Constraint between two objects, by their indices in the scene. A static object, or one
+// without a rigid body, holds the other in place.
+#[derive(Clone, Debug)]
+pub struct Joint {
+    a: usize,
+    b: usize,
+    anchor_a: Vec3, // world space, as the objects stand when the joint first steps
+    anchor_b: Vec3,
+    kind: JointKind,
+    collide_connected: bool, // whether the two objects still collide with each other
+    frame: Option<JointFrame>,
+    impulses: Vec<f32>, // of each row at the last step, to warm start the next
+}
+
+impl Joint {
+    pub fn new(a: usize, b: usize, anchor_a: Vec3, anchor_b: Vec3, kind: JointKind) -> Self {
+        Self {
+            a,
+            b,
+            anchor_a,
+            anchor_b,
+            kind,
+            collide_connected: false,
+            frame: None,
+            impulses: Vec::new(),
+        }
+    }
+
+    pub fn fixed(a: usize, b: usize, anchor: Vec3) -> Self {
+        Self::new(a, b, anchor, anchor, JointKind::Fixed)
+    }
+
+    pub fn ball_socket(a: usize, b: usize, anchor: Vec3) -> Self {
+        Self::new(a, b, anchor, anchor, JointKind::BallSocket)
+    }
+
+    pub fn hinge(a: usize, b: usize, anchor: Vec3, axis: Vec3) -> Self {
+        let kind = JointKind::Hinge {
+            axis,
+            limits: None,
+            motor: None,
+        };
+        Self::new(a, b, anchor, anchor, kind)
+    }
+
+    pub fn slider(a: usize, b: usize, anchor: Vec3, axis: Vec3) -> Self {
+        let kind = JointKind::Slider {
+            axis,
+            limits: None,
+            motor: None,
+        };
+        Self::new(a, b, anchor, anchor, kind)
+    }
+
+    // Keeps the anchors as far apart as they are now
+    pub fn distance(a: usize, b: usize, anchor_a: Vec3, anchor_b: Vec3) -> Self {
+        let length = anchor_a.distance(anchor_b);
+        let kind = JointKind::Distance {
+            min_length: length,
+            max_length: length,
+        };
+        Self::new(a, b, anchor_a, anchor_b, kind)
+    }
+
+    // Rests at the anchors' current distance apart
+    pub fn spring(
+        a: usize,
+        b: usize,
+        anchor_a: Vec3,
+        anchor_b: Vec3,
+        stiffness: f32,
+        damping: f32,
+    ) -> Self {
+        let kind = JointKind::Spring {
+            rest_length: anchor_a.distance(anchor_b),
+            stiffness,
+            damping,
+        };
+        Self::new(a, b, anchor_a, anchor_b, kind)
+    }
+
+    pub fn get_objects(&self) -> (usize, usize) {
+        (self.a, self.b)
+    }
+
+    pub fn get_anchors(&self) -> (Vec3, Vec3) {
+        (self.anchor_a, self.anchor_b)
+    }
+
+    pub fn get_kind(&self) -> &JointKind {
+        &self.kind
+    }
+
+    pub fn get_collide_connected(&self) -> bool {
+        self.collide_connected
+    }
+
+    // The joint takes the objects' poses afresh at its next step
+    pub fn set_kind(&mut self, kind: JointKind) {
+        self.kind = kind;
+        self.frame = None;
+        self.impulses.clear();
+    }
+
+    // Only hinges and sliders have limits
+    pub fn set_limits(&mut self, limits: Option<(f32, f32)>) {
+        if let JointKind::Hinge {
+            limits: current, ..
+        }
+        | JointKind::Slider {
+            limits: current, ..
+        } = &mut self.kind
+        {
+            *current = limits;
+        }
+    }
+
+    // Only hinges and sliders have motors
+    pub fn set_motor(&mut self, motor: Option<JointMotor>) {
+        if let JointKind::Hinge { motor: current, .. } | JointKind::Slider { motor: current, .. } =
+            &mut self.kind
+        {
+            *current = motor;
+        }
+    }
+
+    pub fn set_collide_connected(&mut self, collide_connected: bool) {
+        self.collide_connected = collide_connected;
+    }
+
+    // The rows that hold the bodies together this step, starting from last step's impulses
+    pub(crate) fn build_rows(&mut self, bodies: &[SolverBody], dt: f32) -> Vec<Row> {
+        let (a, b) = (self.a, self.b);
+        if a == b || a >= bodies.len() || b >= bodies.len() {
+            return Vec::new();
+        }
+        let (body_a, body_b) = (&bodies[a], &bodies[b]);
+        let axis = match self.kind {
+            JointKind::Hinge { axis, .. } | JointKind::Slider { axis, .. } => {
+                axis.normalize_or(Vec3::Y)
+            }
+            _ => Vec3::Y,
+        };
+        let frame = *self.frame.get_or_insert_with(|| JointFrame {
+            local_anchor_a: body_a.rotation.inverse() * (self.anchor_a - body_a.center),
+            local_anchor_b: body_b.rotation.inverse() * (self.anchor_b - body_b.center),
+            local_axis_a: body_a.rotation.inverse() * axis,
+            local_axis_b: body_b.rotation.inverse() * axis,
+            reference: body_a.rotation.inverse() * body_b.rotation,
+        });
+
+        let offset_a = body_a.rotation * frame.local_anchor_a;
+        let offset_b = body_b.rotation * frame.local_anchor_b;
+        let separation = (body_b.center + offset_b) - (body_a.center + offset_a);
+        let axis_a = body_a.rotation * frame.local_axis_a;
+        // How far the second body has turned from where the first would have it, as an axis
+        // scaled by the angle
+        let mut turn = body_b.rotation * (body_a.rotation * frame.reference).inverse();
+        if turn.w < 0.0 {
+            turn = -turn;
+        }
+
+        let linear = |direction: Vec3| Jacobian::Linear {
+            direction,
+            offset_a,
+            offset_b,
+        };
+        let angular = |axis: Vec3| Jacobian::Angular { axis };
+        let lock_point = |rows: &mut Vec<Row>| {
+            for direction in [Vec3::X, Vec3::Y, Vec3::Z] {
+                let error = separation.dot(direction);
+                rows.push(Row::bilateral(a, b, linear(direction), error, dt));
+            }
+        };
+        let lock_rotation = |rows: &mut Vec<Row>| {
+            let error = turn.to_scaled_axis();
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                rows.push(Row::bilateral(a, b, angular(axis), error.dot(axis), dt));
+            }
+        };
+
+        let mut rows = Vec::new();
+        match self.kind {
+            JointKind::Fixed => {
+                lock_point(&mut rows);
+                lock_rotation(&mut rows);
+            }
+            JointKind::BallSocket => lock_point(&mut rows),
+            JointKind::Hinge { limits, motor, .. } => {
+                lock_point(&mut rows);
+                // Keep the second body's copy of the axis on the first's
+                let error = axis_a.cross(body_b.rotation * frame.local_axis_b);
+                let (tangent, bitangent) = axis_a.any_orthonormal_pair();
+                for axis in [tangent, bitangent] {
+                    rows.push(Row::bilateral(a, b, angular(axis), error.dot(axis), dt));
+                }
+                let angle = 2.0 * turn.xyz().dot(axis_a).atan2(turn.w);
+                if let Some((lower, upper)) = limits {
+                    rows.push(Row::one_sided(a, b, angular(axis_a), angle - lower, dt));
+                    rows.push(Row::one_sided(a, b, angular(-axis_a), upper - angle, dt));
+                }
+                if let Some(motor) = motor {
+                    let max_impulse = motor.max_force * dt;
+                    let speed = motor.target_velocity;
+                    rows.push(Row::motor(a, b, angular(axis_a), speed, max_impulse));
+                }
+            }
+            JointKind::Slider { limits, motor, .. } => {
+                lock_rotation(&mut rows);
+                // Measured at the second body's anchor, so turning the first body swings it
+                let sliding = |direction: Vec3| Jacobian::Linear {
+                    direction,
+                    offset_a: offset_a + separation,
+                    offset_b,
+                };
+                let (tangent, bitangent) = axis_a.any_orthonormal_pair();
+                for direction in [tangent, bitangent] {
+                    let error = separation.dot(direction);
+                    rows.push(Row::bilateral(a, b, sliding(direction), error, dt));
+                }
+                let offset = separation.dot(axis_a);
+                if let Some((lower, upper)) = limits {
+                    rows.push(Row::one_sided(a, b, sliding(axis_a), offset - lower, dt));
+                    rows.push(Row::one_sided(a, b, sliding(-axis_a), upper - offset, dt));
+                }
+                if let Some(motor) = motor {
+                    let max_impulse = motor.max_force * dt;
+                    let speed = motor.target_velocity;
+                    rows.push(Row::motor(a, b, sliding(axis_a), speed, max_impulse));
+                }
+            }
+            JointKind::Distance {
+                min_length,
+                max_length,
+            } => {
+                let length = separation.length();
+                let direction = separation.normalize_or(Vec3::Y);
+                rows.push(Row::one_sided(
+                    a,
+                    b,
+                    linear(direction),
+                    length - min_length,
+                    dt,
+                ));
+                rows.push(Row::one_sided(
+                    a,
+                    b,
+                    linear(-direction),
+                    max_length - length,
+                    dt,
+                ));
+            }
+            JointKind::Spring {
+                rest_length,
+                stiffness,
+                damping,
+            } => {
+                let length = separation.length();
+                let direction = separation.normalize_or(Vec3::Y);
+                let error = length - rest_length;
+                rows.push(Row::soft(
+                    a,
+                    b,
+                    linear(direction),
+                    error,
+                    stiffness,
+                    damping,
+                    dt,
+                ));
+            }
+        }
+
+        // Rows come in the same order every step while the kind stays the same
+        if self.impulses.len() == rows.len() {
+            for (row, impulse) in rows.iter_mut().zip(&self.impulses) {
+                row.impulse = *impulse;
+            }
+        }
+        rows
+    }
+
+    pub(crate) fn set_impulses(&mut self, impulses: Vec<f32>) {
+        self.impulses = impulses;
+    }
+}
@@ -0,0 +1,113 @@
+use crate::geometry::bounds::Aabb;
+use crate::geometry::mesh::Mesh;
+use glam::{Mat3, Vec3};
+
+// Mass, where it's centered and how it resists turning, in a body's local space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MassProperties {
+    pub mass: f32,
+    pub center_of_mass: Vec3,
+    pub inertia: Mat3, // inertia tensor about the center of mass
+}
+
+impl MassProperties {
+    pub fn new(mass: f32, center_of_mass: Vec3, inertia: Mat3) -> Self {
+        Self {
+            mass,
+            center_of_mass,
+            inertia,
+        }
+    }
+
+    // Solid box centered on the origin
+    pub fn from_box(half_extents: Vec3, mass: f32) -> Self {
+        let size = half_extents * 2.0;
+        let squared = size * size;
+        let inertia = Mat3::from_diagonal(
+            Vec3::new(
+                squared.y + squared.z,
+                squared.x + squared.z,
+                squared.x + squared.y,
+            ) * (mass / 12.0),
+        );
+        Self::new(mass, Vec3::ZERO, inertia)
+    }
+
+    // Solid sphere centered on the origin
+    pub fn from_sphere(radius: f32, mass: f32) -> Self {
+        let inertia = Mat3::from_diagonal(Vec3::splat(0.4 * mass * radius * radius));
+        Self::new(mass, Vec3::ZERO, inertia)
+    }
+
+    // Solid capsule centered on the origin, its segment running `half_height` up and down y
+    pub fn from_capsule(radius: f32, half_height: f32, mass: f32) -> Self {
+        // Split the mass between the cylinder and the two hemispheres by volume
+        let height = half_height * 2.0;
+        let cylinder_volume = std::f32::consts::PI * radius * radius * height;
+        let sphere_volume = 4.0 / 3.0 * std::f32::consts::PI * radius * radius * radius;
+        let cylinder_mass = mass * cylinder_volume / (cylinder_volume + sphere_volume);
+        let sphere_mass = mass - cylinder_mass;
+
+        let squared = radius * radius;
+        let axial = cylinder_mass * squared / 2.0 + sphere_mass * 0.4 * squared;
+        let transverse = cylinder_mass * (squared / 4.0 + height * height / 12.0)
+            + sphere_mass * (0.4 * squared + height * height / 4.0 + 3.0 * height * radius / 8.0);
+        let inertia = Mat3::from_diagonal(Vec3::new(transverse, axial, transverse));
+        Self::new(mass, Vec3::ZERO, inertia)
+    }
+
+    // Solid shape enclosed by a closed mesh with outward-facing triangles, of uniform density
+    pub fn from_mesh(mesh: &Mesh, mass: f32) -> Self {
+        Self::from_triangles(
+            mesh.get_triangles()
+                .iter()
+                .map(|triangle| triangle.get_vertices()),
+            mass,
+        )
+    }
+
+    // Solid shape enclosed by closed, consistently wound triangles, of uniform density. Each
+    // triangle makes a tetrahedron with the origin, and their signed volumes and second
+    // moments add up to the solid's (Blow and Binstock, "How to find the inertia tensor (or
+    // other mass properties) of a 3D solid body represented by a triangle mesh"). Triangles
+    // that enclose no volume, like a single quad, fall back to their bounding box.
+    pub fn from_triangles(triangles: impl IntoIterator<Item = [Vec3; 3]>, mass: f32) -> Self {
+        // Second moment of the tetrahedron (0, x, y, z) with unit determinant
+        let canonical = Mat3::from_cols(
+            Vec3::new(2.0, 1.0, 1.0),
+            Vec3::new(1.0, 2.0, 1.0),
+            Vec3::new(1.0, 1.0, 2.0),
+        ) * (1.0 / 120.0);
+
+        let mut volume = 0.0;
+        let mut first_moment = Vec3::ZERO;
+        let mut covariance = Mat3::ZERO;
+        let mut points = Vec::new();
+        for [a, b, c] in triangles {
+            let corners = Mat3::from_cols(a, b, c);
+            let determinant = corners.determinant();
+            volume += determinant / 6.0;
+            first_moment += (a + b + c) * (determinant / 24.0);
+            covariance += corners * canonical * corners.transpose() * determinant;
+            points.extend([a, b, c]);
+        }
+
+        let aabb = Aabb::from_points(points);
+        if volume.abs() <= f32::EPSILON * aabb.get_size().length_squared().max(1.0) {
+            let box_mass = Self::from_box(aabb.get_extents(), mass);
+            return Self::new(mass, aabb.get_center(), box_mass.inertia);
+        }
+
+        // Move the covariance to the center of mass, then scale from unit density to `mass`
+        let center_of_mass = first_moment / volume;
+        let covariance = covariance - outer(center_of_mass, center_of_mass) * volume;
+        let inertia = Mat3::from_diagonal(Vec3::splat(
+            covariance.x_axis.x + covariance.y_axis.y + covariance.z_axis.z,
+        )) - covariance;
+        Self::new(mass, center_of_mass, inertia * (mass / volume))
+    }
+}
+
+fn outer(a: Vec3, b: Vec3) -> Mat3 {
+    Mat3::from_cols(a * b.x, a * b.y, a * b.z)
+}
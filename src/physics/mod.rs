@@ -0,0 +1,12 @@
+pub mod broad_phase;
+pub mod character_controller;
+pub mod collider;
+pub mod contact;
+pub mod convex_hull;
+mod gjk;
+pub mod joint;
+pub mod mass_properties;
+pub mod rigid_body;
+pub mod soft_body;
+mod solver;
+pub mod world;
@@ -0,0 +1,201 @@
+use crate::physics::mass_properties::MassProperties;
+use glam::{Mat3, Vec3};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyType {
+    Dynamic,   // moved by gravity, forces, impulses and collisions
+    Kinematic, // moved only by its velocity, which the game sets; pushes dynamic bodies
+    Static,    // never moves
+}
+
+// Motion state of an object under physics. Attach one with Object::set_rigid_body; the
+// scene's PhysicsWorld moves it on a fixed timestep and writes the result back into the
+// object's position and rotation.
+#[derive(Clone, Debug)]
+pub struct RigidBody {
+    body_type: BodyType,
+    mass: f32,
+    mass_properties: Option<MassProperties>, // None until derived from the object's shape
+    linear_velocity: Vec3,                   // of the center of mass, in world space
+    angular_velocity: Vec3,                  // world space, in radians per second
+    force: Vec3,                             // accumulated until the next step
+    torque: Vec3,
+    linear_damping: f32, // fraction of velocity lost per second
+    angular_damping: f32,
+    gravity_scale: f32,
+    mass_properties_derived: bool, // false once set explicitly, so the shape can't replace them
+}
+
+impl RigidBody {
+    pub fn new(body_type: BodyType, mass: f32) -> Self {
+        Self {
+            body_type,
+            mass: mass.max(f32::EPSILON),
+            mass_properties: None,
+            linear_velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            force: Vec3::ZERO,
+            torque: Vec3::ZERO,
+            linear_damping: 0.0,
+            angular_damping: 0.05,
+            gravity_scale: 1.0,
+            mass_properties_derived: false,
+        }
+    }
+
+    pub fn dynamic(mass: f32) -> Self {
+        Self::new(BodyType::Dynamic, mass)
+    }
+
+    pub fn kinematic() -> Self {
+        Self::new(BodyType::Kinematic, 1.0)
+    }
+
+    pub fn fixed() -> Self {
+        Self::new(BodyType::Static, 1.0)
+    }
+
+    pub fn get_body_type(&self) -> BodyType {
+        self.body_type
+    }
+
+    pub fn set_body_type(&mut self, body_type: BodyType) {
+        self.body_type = body_type;
+    }
+
+    pub fn get_mass(&self) -> f32 {
+        self.mass
+    }
+
+    // Keeps the center of mass and scales the inertia to match
+    pub fn set_mass(&mut self, mass: f32) {
+        let mass = mass.max(f32::EPSILON);
+        if let Some(properties) = &mut self.mass_properties {
+            properties.inertia *= mass / properties.mass;
+            properties.mass = mass;
+        }
+        self.mass = mass;
+    }
+
+    // Zero for kinematic and static bodies, which nothing can push
+    pub fn get_inverse_mass(&self) -> f32 {
+        match self.body_type {
+            BodyType::Dynamic => 1.0 / self.mass,
+            _ => 0.0,
+        }
+    }
+
+    pub fn get_mass_properties(&self) -> Option<MassProperties> {
+        self.mass_properties
+    }
+
+    // Overrides the center of mass and inertia, and the mass with them. They stay when the
+    // object is given a new collider.
+    pub fn set_mass_properties(&mut self, mass_properties: MassProperties) {
+        self.mass = mass_properties.mass.max(f32::EPSILON);
+        self.mass_properties = Some(mass_properties);
+        self.mass_properties_derived = false;
+    }
+
+    // Whether the mass properties come from the object's shape rather than set_mass_properties
+    pub fn has_derived_mass_properties(&self) -> bool {
+        self.mass_properties_derived
+    }
+
+    pub(crate) fn set_derived_mass_properties(&mut self, mass_properties: MassProperties) {
+        self.set_mass_properties(mass_properties);
+        self.mass_properties_derived = true;
+    }
+
+    // In the object's local space; the origin until the mass properties are known
+    pub fn get_local_center_of_mass(&self) -> Vec3 {
+        self.mass_properties
+            .map_or(Vec3::ZERO, |properties| properties.center_of_mass)
+    }
+
+    // Inverse inertia tensor in world space for a body turned by `rotation`; zero for bodies
+    // that can't be pushed
+    pub fn get_world_inverse_inertia(&self, rotation: Mat3) -> Mat3 {
+        if self.body_type != BodyType::Dynamic {
+            return Mat3::ZERO;
+        }
+        let inertia = self.mass_properties.map_or_else(
+            || MassProperties::from_sphere(0.5, self.mass).inertia,
+            |properties| properties.inertia,
+        );
+        rotation * inertia.inverse() * rotation.transpose()
+    }
+
+    pub fn get_linear_velocity(&self) -> Vec3 {
+        self.linear_velocity
+    }
+
+    pub fn set_linear_velocity(&mut self, linear_velocity: Vec3) {
+        self.linear_velocity = linear_velocity;
+    }
+
+    pub fn get_angular_velocity(&self) -> Vec3 {
+        self.angular_velocity
+    }
+
+    pub fn set_angular_velocity(&mut self, angular_velocity: Vec3) {
+        self.angular_velocity = angular_velocity;
+    }
+
+    pub fn get_linear_damping(&self) -> f32 {
+        self.linear_damping
+    }
+
+    pub fn set_linear_damping(&mut self, linear_damping: f32) {
+        self.linear_damping = linear_damping.max(0.0);
+    }
+
+    pub fn get_angular_damping(&self) -> f32 {
+        self.angular_damping
+    }
+
+    pub fn set_angular_damping(&mut self, angular_damping: f32) {
+        self.angular_damping = angular_damping.max(0.0);
+    }
+
+    pub fn get_gravity_scale(&self) -> f32 {
+        self.gravity_scale
+    }
+
+    pub fn set_gravity_scale(&mut self, gravity_scale: f32) {
+        self.gravity_scale = gravity_scale;
+    }
+
+    // Force through the center of mass, applied over the next step
+    pub fn apply_force(&mut self, force: Vec3) {
+        self.force += force;
+    }
+
+    // World-space torque, applied over the next step
+    pub fn apply_torque(&mut self, torque: Vec3) {
+        self.torque += torque;
+    }
+
+    // Instant change in momentum through the center of mass
+    pub fn apply_impulse(&mut self, impulse: Vec3) {
+        self.linear_velocity += impulse * self.get_inverse_mass();
+    }
+
+    // Instant change in angular momentum, for a body turned by `rotation`
+    pub fn apply_angular_impulse(&mut self, impulse: Vec3, rotation: Mat3) {
+        self.angular_velocity += self.get_world_inverse_inertia(rotation) * impulse;
+    }
+
+    pub(crate) fn get_force(&self) -> Vec3 {
+        self.force
+    }
+
+    pub(crate) fn get_torque(&self) -> Vec3 {
+        self.torque
+    }
+
+    pub(crate) fn clear_forces(&mut self) {
+        self.force = Vec3::ZERO;
+        self.torque = Vec3::ZERO;
+    }
+}
@@ -0,0 +1,530 @@
+use crate::core::object::Object;
+use crate::geometry::bounds::Aabb;
+use crate::geometry::mesh::Mesh;
+use crate::physics::collider::{Collider, ColliderShape, Pose};
+use crate::physics::contact::collide_with_margin;
+use glam::Vec3;
+use std::collections::HashMap;
+
+// Vertices closer than this become one particle, closing seams left by rounding
+const WELD_DISTANCE: f32 = 1e-5;
+
+// Two particles held at a set distance
+#[derive(Clone, Copy, Debug)]
+struct DistanceConstraint {
+    a: usize,
+    b: usize,
+    rest_length: f32,
+}
+
+// Particle pushed out of a collider during a substep, kept to apply friction afterwards
+struct Touch {
+    particle: usize,
+    normal: Vec3,
+    depth: f32,
+    friction: f32,
+}
+
+// Deforms an object's mesh as cloth, or as a squishy solid for closed meshes, with extended
+// position based dynamics. Each vertex becomes a particle; the mesh's edges keep their
+// lengths and the pairs of triangles on each edge resist folding. The particles live in
+// world space, and the object's position and rotation stay put while the mesh moves with
+// them. Other objects' colliders push the particles out, but the particles don't push back.
+#[derive(Clone, Debug)]
+pub struct SoftBody {
+    positions: Vec<Vec3>, // world space
+    velocities: Vec<Vec3>,
+    inverse_masses: Vec<f32>, // 0 for pinned particles
+    particle_mass: f32,       // to restore when unpinned
+    corners: Vec<[usize; 3]>, // particle at each corner of each of the mesh's triangles
+    stretch: Vec<DistanceConstraint>,
+    bend: Vec<DistanceConstraint>,
+    rest_volume: f32,
+    stretch_compliance: f32, // meters per newton; 0 doesn't stretch at all
+    bend_compliance: f32,
+    volume_compliance: Option<f32>, // None lets the volume change, as cloth does
+    damping: f32,
+    wind: Vec3,     // velocity of the air
+    drag: f32,      // newton seconds per cubic meter, of air moving across the surface
+    friction: f32,  // mixed with each collider's
+    thickness: f32, // how far the particles stay from colliders
+    substeps: u32,  // per physics step, each with one pass over the constraints
+}
+
+impl SoftBody {
+    // Made from the object's mesh where the object stands now, with `mass` spread evenly
+    // over the vertices
+    pub fn new(object: &Object, mass: f32) -> Self {
+        let pose = Pose::from_object(object);
+        let mut positions = Vec::new();
+        let mut welded = HashMap::new();
+        let corners: Vec<[usize; 3]> = object
+            .get_mesh()
+            .get_triangles()
+            .iter()
+            .map(|triangle| {
+                triangle.get_vertices().map(|vertex| {
+                    let key = (vertex / WELD_DISTANCE).round().as_ivec3();
+                    *welded.entry(key).or_insert_with(|| {
+                        positions.push(pose.transform_point(vertex));
+                        positions.len() - 1
+                    })
+                })
+            })
+            .collect();
+
+        // Triangles on each edge, by the corner across from it
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for &[a, b, c] in &corners {
+            for (from, to, across) in [(a, b, c), (b, c, a), (c, a, b)] {
+                edges
+                    .entry((from.min(to), from.max(to)))
+                    .or_default()
+                    .push(across);
+            }
+        }
+        let mut edges: Vec<_> = edges.into_iter().collect();
+        edges.sort_unstable_by_key(|(edge, _)| *edge);
+        let constraint = |a: usize, b: usize| DistanceConstraint {
+            a,
+            b,
+            rest_length: positions[a].distance(positions[b]),
+        };
+        let stretch = edges.iter().map(|((a, b), _)| constraint(*a, *b)).collect();
+        let bend = edges
+            .iter()
+            .filter_map(|(_, across)| match across[..] {
+                [a, b] if a != b => Some(constraint(a, b)),
+                _ => None,
+            })
+            .collect();
+
+        let mass = mass / positions.len().max(1) as f32;
+        let mut soft_body = Self {
+            velocities: vec![Vec3::ZERO; positions.len()],
+            inverse_masses: vec![1.0 / mass; positions.len()],
+            particle_mass: mass,
+            positions,
+            corners,
+            stretch,
+            bend,
+            rest_volume: 0.0,
+            stretch_compliance: 0.0,
+            bend_compliance: 0.01,
+            volume_compliance: None,
+            damping: 0.1,
+            wind: Vec3::ZERO,
+            drag: 1.0,
+            friction: 0.5,
+            thickness: 0.02,
+            substeps: 10,
+        };
+        soft_body.rest_volume = soft_body.volume();
+        soft_body
+    }
+
+    // World space positions of the particles, one for each distinct vertex of the mesh
+    pub fn get_particles(&self) -> &Vec<Vec3> {
+        &self.positions
+    }
+
+    pub fn get_velocities(&self) -> &Vec<Vec3> {
+        &self.velocities
+    }
+
+    // Particle at each corner of each of the mesh's triangles
+    pub fn get_corners(&self) -> &Vec<[usize; 3]> {
+        &self.corners
+    }
+
+    pub fn get_pinned(&self, particle: usize) -> bool {
+        self.inverse_masses[particle] == 0.0
+    }
+
+    // Enclosed by the particles, for closed meshes wound counterclockwise from outside
+    pub fn get_volume(&self) -> f32 {
+        self.volume()
+    }
+
+    pub fn get_rest_volume(&self) -> f32 {
+        self.rest_volume
+    }
+
+    pub fn get_stretch_compliance(&self) -> f32 {
+        self.stretch_compliance
+    }
+
+    pub fn get_bend_compliance(&self) -> f32 {
+        self.bend_compliance
+    }
+
+    pub fn get_volume_compliance(&self) -> Option<f32> {
+        self.volume_compliance
+    }
+
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    pub fn get_wind(&self) -> Vec3 {
+        self.wind
+    }
+
+    pub fn get_drag(&self) -> f32 {
+        self.drag
+    }
+
+    pub fn get_friction(&self) -> f32 {
+        self.friction
+    }
+
+    pub fn get_thickness(&self) -> f32 {
+        self.thickness
+    }
+
+    pub fn get_substeps(&self) -> u32 {
+        self.substeps
+    }
+
+    // A pinned particle stays where it is, or where it's moved to, whatever pulls on it
+    pub fn pin(&mut self, particle: usize) {
+        self.inverse_masses[particle] = 0.0;
+        self.velocities[particle] = Vec3::ZERO;
+    }
+
+    pub fn unpin(&mut self, particle: usize) {
+        self.inverse_masses[particle] = 1.0 / self.particle_mass;
+    }
+
+    // Pins every particle inside `region`, returning how many
+    pub fn pin_within(&mut self, region: &Aabb) -> usize {
+        let inside: Vec<usize> = (0..self.positions.len())
+            .filter(|particle| region.contains_point(self.positions[*particle]))
+            .collect();
+        for particle in &inside {
+            self.pin(*particle);
+        }
+        inside.len()
+    }
+
+    // Moves a particle, to drag pinned ones around
+    pub fn set_particle_position(&mut self, particle: usize, position: Vec3) {
+        self.positions[particle] = position;
+    }
+
+    pub fn set_stretch_compliance(&mut self, stretch_compliance: f32) {
+        self.stretch_compliance = stretch_compliance.max(0.0);
+    }
+
+    pub fn set_bend_compliance(&mut self, bend_compliance: f32) {
+        self.bend_compliance = bend_compliance.max(0.0);
+    }
+
+    // Some(0.0) keeps a closed mesh's volume exactly; larger values let it squash
+    pub fn set_volume_compliance(&mut self, volume_compliance: Option<f32>) {
+        self.volume_compliance = volume_compliance.map(|compliance| compliance.max(0.0));
+    }
+
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.max(0.0);
+    }
+
+    pub fn set_wind(&mut self, wind: Vec3) {
+        self.wind = wind;
+    }
+
+    pub fn set_drag(&mut self, drag: f32) {
+        self.drag = drag.max(0.0);
+    }
+
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction.max(0.0);
+    }
+
+    pub fn set_thickness(&mut self, thickness: f32) {
+        self.thickness = thickness.max(0.0);
+    }
+
+    pub fn set_substeps(&mut self, substeps: u32) {
+        self.substeps = substeps.max(1);
+    }
+
+    // Moves the particles on by `dt` seconds among the obstacles
+    pub(crate) fn step(&mut self, obstacles: &[(&Collider, Pose)], gravity: Vec3, dt: f32) {
+        let h = dt / self.substeps as f32;
+        let wind = self.wind_accelerations();
+        for _ in 0..self.substeps {
+            let previous = self.positions.clone();
+            for (((position, velocity), inverse_mass), wind) in self
+                .positions
+                .iter_mut()
+                .zip(&mut self.velocities)
+                .zip(&self.inverse_masses)
+                .zip(&wind)
+            {
+                if *inverse_mass > 0.0 {
+                    *velocity += (gravity + *wind) * h;
+                    *position += *velocity * h;
+                }
+            }
+
+            let stretch_compliance = self.stretch_compliance;
+            let bend_compliance = self.bend_compliance;
+            for (constraint, compliance) in self
+                .stretch
+                .iter()
+                .map(|constraint| (constraint, stretch_compliance))
+                .chain(
+                    self.bend
+                        .iter()
+                        .map(|constraint| (constraint, bend_compliance)),
+                )
+            {
+                solve_distance(
+                    &mut self.positions,
+                    &self.inverse_masses,
+                    constraint,
+                    compliance,
+                    h,
+                );
+            }
+            if let Some(compliance) = self.volume_compliance {
+                self.solve_volume(compliance, h);
+            }
+            let touches = self.collide(obstacles);
+
+            for (((velocity, position), previous), inverse_mass) in self
+                .velocities
+                .iter_mut()
+                .zip(&self.positions)
+                .zip(&previous)
+                .zip(&self.inverse_masses)
+            {
+                *velocity = if *inverse_mass > 0.0 {
+                    (*position - *previous) / h
+                } else {
+                    Vec3::ZERO
+                };
+            }
+            for touch in touches {
+                let velocity = &mut self.velocities[touch.particle];
+                let into = velocity.dot(touch.normal);
+                if into < 0.0 {
+                    *velocity -= touch.normal * into;
+                }
+                // Coulomb friction: the push out is the normal impulse, per unit mass
+                let sliding = *velocity - touch.normal * velocity.dot(touch.normal);
+                let speed = sliding.length();
+                if speed > 0.0 {
+                    let slowed = (touch.friction * touch.depth / h).min(speed);
+                    *velocity -= sliding * (slowed / speed);
+                }
+            }
+            let damping = 1.0 / (1.0 + self.damping * h);
+            for velocity in &mut self.velocities {
+                *velocity *= damping;
+            }
+        }
+    }
+
+    // The mesh with its vertices moved to the particles, in the space of an object at `pose`
+    pub(crate) fn deform(&self, mesh: &Mesh, pose: &Pose) -> Mesh {
+        let triangles = mesh.get_triangles();
+        if triangles.len() != self.corners.len() {
+            return mesh.clone();
+        }
+        let had_tangents = triangles.iter().any(|triangle| triangle.has_tangents());
+        let local: Vec<Vec3> = self
+            .positions
+            .iter()
+            .map(|position| pose.inverse_transform_point(*position))
+            .collect();
+        let triangles = triangles
+            .iter()
+            .zip(&self.corners)
+            .map(|(triangle, corners)| {
+                let mut triangle = triangle.clone();
+                triangle.set_vertices(corners.map(|particle| local[particle]));
+                triangle
+            })
+            .collect();
+        let mut mesh = mesh.clone();
+        mesh.set_triangles(triangles);
+        if had_tangents {
+            mesh.generate_tangents();
+        }
+        mesh
+    }
+
+    // Where the particles are, grown by how far they could reach
+    pub(crate) fn get_aabb(&self, reach: f32) -> Aabb {
+        let bounds = Aabb::from_points(self.positions.iter().copied());
+        Aabb::new(bounds.min - reach, bounds.max + reach)
+    }
+
+    // Air pushing on each triangle by how fast it flows through it, shared among the corners
+    fn wind_accelerations(&self) -> Vec<Vec3> {
+        let mut accelerations = vec![Vec3::ZERO; self.positions.len()];
+        if self.drag == 0.0 {
+            return accelerations;
+        }
+        for corners in &self.corners {
+            let [a, b, c] = corners.map(|particle| self.positions[particle]);
+            let area_normal = (b - a).cross(c - a) * 0.5;
+            let velocity = corners
+                .iter()
+                .map(|particle| self.velocities[*particle])
+                .sum::<Vec3>()
+                / 3.0;
+            let Some(normal) = area_normal.try_normalize() else {
+                continue;
+            };
+            let force = normal * (self.wind - velocity).dot(area_normal) * self.drag;
+            for particle in corners {
+                accelerations[*particle] += force * (self.inverse_masses[*particle] / 3.0);
+            }
+        }
+        accelerations
+    }
+
+    fn volume(&self) -> f32 {
+        self.corners
+            .iter()
+            .map(|corners| {
+                let [a, b, c] = corners.map(|particle| self.positions[particle]);
+                a.cross(b).dot(c) / 6.0
+            })
+            .sum()
+    }
+
+    fn solve_volume(&mut self, compliance: f32, h: f32) {
+        let mut gradients = vec![Vec3::ZERO; self.positions.len()];
+        for &[a, b, c] in &self.corners {
+            let [pa, pb, pc] = [a, b, c].map(|particle| self.positions[particle]);
+            gradients[a] += pb.cross(pc) / 6.0;
+            gradients[b] += pc.cross(pa) / 6.0;
+            gradients[c] += pa.cross(pb) / 6.0;
+        }
+        let weight: f32 = gradients
+            .iter()
+            .zip(&self.inverse_masses)
+            .map(|(gradient, inverse_mass)| gradient.length_squared() * inverse_mass)
+            .sum();
+        let denominator = weight + compliance / (h * h);
+        if denominator <= 0.0 {
+            return;
+        }
+        let lambda = (self.rest_volume - self.volume()) / denominator;
+        for ((position, gradient), inverse_mass) in self
+            .positions
+            .iter_mut()
+            .zip(&gradients)
+            .zip(&self.inverse_masses)
+        {
+            *position += *gradient * (lambda * inverse_mass);
+        }
+    }
+
+    // Pushes the free particles out of the obstacles
+    fn collide(&mut self, obstacles: &[(&Collider, Pose)]) -> Vec<Touch> {
+        let mut touches = Vec::new();
+        let regions: Vec<Aabb> = obstacles
+            .iter()
+            .map(|(collider, pose)| {
+                let bounds = collider.get_world_aabb(pose);
+                Aabb::new(bounds.min - self.thickness, bounds.max + self.thickness)
+            })
+            .collect();
+        for particle in 0..self.positions.len() {
+            if self.inverse_masses[particle] == 0.0 {
+                continue;
+            }
+            for ((collider, pose), region) in obstacles.iter().zip(&regions) {
+                let position = self.positions[particle];
+                if !region.contains_point(position) {
+                    continue;
+                }
+                if let Some((normal, depth)) = push_out(position, self.thickness, collider, pose) {
+                    self.positions[particle] += normal * depth;
+                    touches.push(Touch {
+                        particle,
+                        normal,
+                        depth,
+                        friction: (self.friction * collider.get_friction()).sqrt(),
+                    });
+                }
+            }
+        }
+        touches
+    }
+}
+
+fn solve_distance(
+    positions: &mut [Vec3],
+    inverse_masses: &[f32],
+    constraint: &DistanceConstraint,
+    compliance: f32,
+    h: f32,
+) {
+    let (a, b) = (constraint.a, constraint.b);
+    let weight = inverse_masses[a] + inverse_masses[b];
+    let offset = positions[a] - positions[b];
+    let length = offset.length();
+    if weight == 0.0 || length == 0.0 {
+        return;
+    }
+    let direction = offset / length;
+    let lambda = (constraint.rest_length - length) / (weight + compliance / (h * h));
+    positions[a] += direction * (lambda * inverse_masses[a]);
+    positions[b] -= direction * (lambda * inverse_masses[b]);
+}
+
+// Which way and how far to move a point so it's at least `thickness` outside the collider
+fn push_out(point: Vec3, thickness: f32, collider: &Collider, pose: &Pose) -> Option<(Vec3, f32)> {
+    let local = pose.inverse_transform_point(point);
+    let (normal, depth) = match collider.get_shape() {
+        ColliderShape::Sphere { radius } => outside_ball(local, Vec3::ZERO, radius + thickness)?,
+        ColliderShape::Capsule {
+            radius,
+            half_height,
+        } => {
+            let center = Vec3::new(0.0, local.y.clamp(-half_height, *half_height), 0.0);
+            outside_ball(local, center, radius + thickness)?
+        }
+        ColliderShape::Box { half_extents } => {
+            let closest = local.clamp(-*half_extents, *half_extents);
+            if closest != local {
+                outside_ball(local, closest, thickness)?
+            } else {
+                // Inside: out through the nearest face
+                let gaps = *half_extents - local.abs();
+                let axis = if gaps.x <= gaps.y && gaps.x <= gaps.z {
+                    0
+                } else if gaps.y <= gaps.z {
+                    1
+                } else {
+                    2
+                };
+                let mut normal = Vec3::ZERO;
+                normal[axis] = if local[axis] < 0.0 { -1.0 } else { 1.0 };
+                (normal, gaps[axis] + thickness)
+            }
+        }
+        ColliderShape::ConvexHull(_) | ColliderShape::TriangleMesh(_) => {
+            let probe = Collider::sphere(thickness.max(1e-3));
+            return collide_with_margin(&probe, &Pose::from_position(point), collider, pose, 0.0)
+                .into_iter()
+                .filter(|contact| contact.depth > 0.0)
+                .max_by(|a, b| a.depth.total_cmp(&b.depth))
+                .map(|contact| (-contact.normal, contact.depth));
+        }
+    };
+    Some((pose.rotation * normal, depth))
+}
+
+// Out of a ball around `center`, straight away from it
+fn outside_ball(point: Vec3, center: Vec3, radius: f32) -> Option<(Vec3, f32)> {
+    let offset = point - center;
+    let distance = offset.length();
+    (distance < radius).then(|| (offset.normalize_or(Vec3::Y), radius - distance))
+}
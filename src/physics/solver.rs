@@ -0,0 +1,373 @@
+use crate::physics::contact::Contact;
+use glam::{Mat3, Quat, Vec3};
+
+// Fraction of the remaining overlap pushed out each step
+const BAUMGARTE: f32 = 0.2;
+// Overlap left alone so resting contacts don't jitter in and out of touching
+const PENETRATION_SLOP: f32 = 0.005;
+// Slowest approach, in meters per second, that bounces
+const RESTITUTION_THRESHOLD: f32 = 1.0;
+
+// The part of a body the solver changes, with everything it needs to do that; static bodies
+// and objects without a rigid body have zero inverse mass and inertia
+#[derive(Clone, Copy, Debug)]
+pub struct SolverBody {
+    pub inverse_mass: f32,
+    pub inverse_inertia: Mat3, // world space
+    pub linear_velocity: Vec3,
+    pub angular_velocity: Vec3,
+    pub center: Vec3,   // world-space center of mass
+    pub rotation: Quat, // world orientation
+}
+
+impl SolverBody {
+    // Velocity of the point `offset` from the center of mass
+    fn velocity_at(&self, offset: Vec3) -> Vec3 {
+        self.linear_velocity + self.angular_velocity.cross(offset)
+    }
+
+    fn apply_impulse(&mut self, impulse: Vec3, offset: Vec3) {
+        self.linear_velocity += impulse * self.inverse_mass;
+        self.angular_velocity += self.inverse_inertia * offset.cross(impulse);
+    }
+
+    fn apply_angular_impulse(&mut self, impulse: Vec3) {
+        self.angular_velocity += self.inverse_inertia * impulse;
+    }
+
+    // How much an impulse along `direction` at `offset` changes that point's velocity along it
+    fn response(&self, direction: Vec3, offset: Vec3) -> f32 {
+        let angular = (self.inverse_inertia * offset.cross(direction)).cross(offset);
+        self.inverse_mass + angular.dot(direction)
+    }
+}
+
+// Contact between two bodies, with surface properties mixed from both colliders
+pub struct ContactInput {
+    pub a: usize, // indices into the solver's bodies
+    pub b: usize,
+    pub contact: Contact,
+    pub friction: f32,
+    pub restitution: f32,
+    // World-space impulse on B at each point: last step's to start from, this step's after
+    pub impulses: Vec<Vec3>,
+}
+
+// Direction a row constrains: the bodies' relative velocity at two points along a
+// direction, or their relative angular velocity about an axis
+#[derive(Clone, Copy, Debug)]
+pub enum Jacobian {
+    Linear {
+        direction: Vec3,
+        offset_a: Vec3, // from each body's center of mass to its point
+        offset_b: Vec3,
+    },
+    Angular {
+        axis: Vec3,
+    },
+}
+
+// One degree of freedom a joint takes away. Each iteration pushes the relative velocity
+// along the row toward target_velocity, keeping the accumulated impulse within its bounds.
+pub struct Row {
+    pub a: usize, // indices into the solver's bodies
+    pub b: usize,
+    pub jacobian: Jacobian,
+    pub target_velocity: f32,
+    pub softness: f32, // lets the row give like a spring; 0 is rigid
+    pub min_impulse: f32,
+    pub max_impulse: f32,
+    pub impulse: f32, // last step's to start from, this step's after
+}
+
+impl Row {
+    // Holds `error` at zero, taking out a fraction of it each step
+    pub fn bilateral(a: usize, b: usize, jacobian: Jacobian, error: f32, dt: f32) -> Self {
+        Self::new(a, b, jacobian, -BAUMGARTE * error / dt, f32::MIN, f32::MAX)
+    }
+
+    // Keeps `gap` from going below zero. Like contacts, a row still apart may close only as
+    // fast as closes the gap this step.
+    pub fn one_sided(a: usize, b: usize, jacobian: Jacobian, gap: f32, dt: f32) -> Self {
+        let target_velocity = if gap >= 0.0 {
+            -gap / dt
+        } else {
+            -BAUMGARTE * gap / dt
+        };
+        Self::new(a, b, jacobian, target_velocity, 0.0, f32::MAX)
+    }
+
+    // Drives the relative velocity to `speed` with at most `max_impulse` each step
+    pub fn motor(a: usize, b: usize, jacobian: Jacobian, speed: f32, max_impulse: f32) -> Self {
+        Self::new(a, b, jacobian, speed, -max_impulse, max_impulse)
+    }
+
+    // Pulls `error` toward zero like a damped spring, in newtons per meter and newton
+    // seconds per meter, solved implicitly so stiff springs stay stable (Catto, "Soft
+    // Constraints")
+    pub fn soft(
+        a: usize,
+        b: usize,
+        jacobian: Jacobian,
+        error: f32,
+        stiffness: f32,
+        damping: f32,
+        dt: f32,
+    ) -> Self {
+        let compliance = dt * (damping + dt * stiffness);
+        if compliance <= 0.0 {
+            return Self::new(a, b, jacobian, 0.0, 0.0, 0.0);
+        }
+        let softness = 1.0 / compliance;
+        let mut row = Self::new(
+            a,
+            b,
+            jacobian,
+            -error * dt * stiffness * softness,
+            f32::MIN,
+            f32::MAX,
+        );
+        row.softness = softness;
+        row
+    }
+
+    fn new(
+        a: usize,
+        b: usize,
+        jacobian: Jacobian,
+        target_velocity: f32,
+        min_impulse: f32,
+        max_impulse: f32,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            jacobian,
+            target_velocity,
+            softness: 0.0,
+            min_impulse,
+            max_impulse,
+            impulse: 0.0,
+        }
+    }
+
+    fn inverse_mass(&self, body_a: &SolverBody, body_b: &SolverBody) -> f32 {
+        match self.jacobian {
+            Jacobian::Linear {
+                direction,
+                offset_a,
+                offset_b,
+            } => body_a.response(direction, offset_a) + body_b.response(direction, offset_b),
+            Jacobian::Angular { axis } => {
+                axis.dot(body_a.inverse_inertia * axis) + axis.dot(body_b.inverse_inertia * axis)
+            }
+        }
+    }
+
+    fn velocity(&self, body_a: &SolverBody, body_b: &SolverBody) -> f32 {
+        match self.jacobian {
+            Jacobian::Linear {
+                direction,
+                offset_a,
+                offset_b,
+            } => (body_b.velocity_at(offset_b) - body_a.velocity_at(offset_a)).dot(direction),
+            Jacobian::Angular { axis } => {
+                (body_b.angular_velocity - body_a.angular_velocity).dot(axis)
+            }
+        }
+    }
+
+    fn apply(&self, body_a: &mut SolverBody, body_b: &mut SolverBody, lambda: f32) {
+        match self.jacobian {
+            Jacobian::Linear {
+                direction,
+                offset_a,
+                offset_b,
+            } => {
+                body_a.apply_impulse(-direction * lambda, offset_a);
+                body_b.apply_impulse(direction * lambda, offset_b);
+            }
+            Jacobian::Angular { axis } => {
+                body_a.apply_angular_impulse(-axis * lambda);
+                body_b.apply_angular_impulse(axis * lambda);
+            }
+        }
+    }
+}
+
+struct ConstraintPoint {
+    offset_a: Vec3, // from each body's center of mass to the contact
+    offset_b: Vec3,
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    target_velocity: f32, // separating speed the normal impulse aims for
+    normal_impulse: f32,  // accumulated over the iterations
+    tangent_impulse: [f32; 2],
+}
+
+struct ContactConstraint {
+    a: usize,
+    b: usize,
+    normal: Vec3,
+    tangents: [Vec3; 2],
+    friction: f32,
+    points: Vec<ConstraintPoint>,
+}
+
+impl ContactConstraint {
+    // Accumulated impulse on B at a point, in world space
+    fn impulse(&self, point: &ConstraintPoint) -> Vec3 {
+        self.normal * point.normal_impulse
+            + self.tangents[0] * point.tangent_impulse[0]
+            + self.tangents[1] * point.tangent_impulse[1]
+    }
+}
+
+// Sequential impulses (Catto, "Iterative Dynamics with Temporal Coherence"): each contact
+// point in turn gets the impulse that stops it closing, clamped so the accumulated impulse
+// only ever pushes, with friction bounded by the normal impulse. Overlaps are pushed out a
+// fraction at a time, and surfaces still apart may approach only as fast as closes the gap.
+// Starting from last step's impulses (warm starting) lets stacks settle in few iterations.
+// Joint rows are solved the same way, before the contacts in each iteration.
+pub fn solve(
+    bodies: &mut [SolverBody],
+    contacts: &mut [ContactInput],
+    rows: &mut [Row],
+    dt: f32,
+    iterations: u32,
+) {
+    // Inverse effective mass of each row, softened; rows between immovable bodies do nothing
+    let row_masses: Vec<f32> = rows
+        .iter()
+        .map(|row| {
+            let inverse = row.inverse_mass(&bodies[row.a], &bodies[row.b]) + row.softness;
+            if inverse > 0.0 { 1.0 / inverse } else { 0.0 }
+        })
+        .collect();
+
+    let mut constraints: Vec<ContactConstraint> = contacts
+        .iter()
+        .map(|input| {
+            let (body_a, body_b) = (bodies[input.a], bodies[input.b]);
+            let normal = input.contact.normal;
+            let (tangent, bitangent) = normal.any_orthonormal_pair();
+            let points = input
+                .contact
+                .points
+                .iter()
+                .zip(&input.impulses)
+                .map(|(point, impulse)| {
+                    let offset_a = point.point - body_a.center;
+                    let offset_b = point.point - body_b.center;
+                    let inverse = |direction: Vec3| {
+                        let response = body_a.response(direction, offset_a)
+                            + body_b.response(direction, offset_b);
+                        if response > 0.0 { 1.0 / response } else { 0.0 }
+                    };
+
+                    let approach =
+                        (body_b.velocity_at(offset_b) - body_a.velocity_at(offset_a)).dot(normal);
+                    let mut target_velocity = if point.depth < 0.0 {
+                        point.depth / dt
+                    } else {
+                        BAUMGARTE * (point.depth - PENETRATION_SLOP).max(0.0) / dt
+                    };
+                    if approach < -RESTITUTION_THRESHOLD {
+                        target_velocity = target_velocity.max(-input.restitution * approach);
+                    }
+
+                    ConstraintPoint {
+                        offset_a,
+                        offset_b,
+                        normal_mass: inverse(normal),
+                        tangent_mass: [inverse(tangent), inverse(bitangent)],
+                        target_velocity,
+                        normal_impulse: impulse.dot(normal).max(0.0),
+                        tangent_impulse: [impulse.dot(tangent), impulse.dot(bitangent)],
+                    }
+                })
+                .collect();
+            ContactConstraint {
+                a: input.a,
+                b: input.b,
+                normal,
+                tangents: [tangent, bitangent],
+                friction: input.friction,
+                points,
+            }
+        })
+        .collect();
+
+    for constraint in &constraints {
+        let (mut body_a, mut body_b) = (bodies[constraint.a], bodies[constraint.b]);
+        for point in &constraint.points {
+            let impulse = constraint.impulse(point);
+            body_a.apply_impulse(-impulse, point.offset_a);
+            body_b.apply_impulse(impulse, point.offset_b);
+        }
+        bodies[constraint.a] = body_a;
+        bodies[constraint.b] = body_b;
+    }
+
+    for row in rows.iter_mut() {
+        row.impulse = row.impulse.clamp(row.min_impulse, row.max_impulse);
+        let (mut body_a, mut body_b) = (bodies[row.a], bodies[row.b]);
+        row.apply(&mut body_a, &mut body_b, row.impulse);
+        bodies[row.a] = body_a;
+        bodies[row.b] = body_b;
+    }
+
+    for _ in 0..iterations {
+        for (row, mass) in rows.iter_mut().zip(&row_masses) {
+            if *mass == 0.0 {
+                continue;
+            }
+            let (mut body_a, mut body_b) = (bodies[row.a], bodies[row.b]);
+            let velocity = row.velocity(&body_a, &body_b);
+            let lambda = (row.target_velocity - velocity - row.softness * row.impulse) * mass;
+            let accumulated = (row.impulse + lambda).clamp(row.min_impulse, row.max_impulse);
+            row.apply(&mut body_a, &mut body_b, accumulated - row.impulse);
+            row.impulse = accumulated;
+            bodies[row.a] = body_a;
+            bodies[row.b] = body_b;
+        }
+
+        for constraint in &mut constraints {
+            let (mut body_a, mut body_b) = (bodies[constraint.a], bodies[constraint.b]);
+            for point in &mut constraint.points {
+                // Friction first, bounded by last iteration's normal impulse
+                let limit = constraint.friction * point.normal_impulse;
+                for (axis, tangent) in constraint.tangents.iter().enumerate() {
+                    let relative =
+                        body_b.velocity_at(point.offset_b) - body_a.velocity_at(point.offset_a);
+                    let lambda = -relative.dot(*tangent) * point.tangent_mass[axis];
+                    let accumulated = (point.tangent_impulse[axis] + lambda).clamp(-limit, limit);
+                    let lambda = accumulated - point.tangent_impulse[axis];
+                    point.tangent_impulse[axis] = accumulated;
+                    body_a.apply_impulse(-*tangent * lambda, point.offset_a);
+                    body_b.apply_impulse(*tangent * lambda, point.offset_b);
+                }
+
+                let relative =
+                    body_b.velocity_at(point.offset_b) - body_a.velocity_at(point.offset_a);
+                let lambda =
+                    (point.target_velocity - relative.dot(constraint.normal)) * point.normal_mass;
+                let accumulated = (point.normal_impulse + lambda).max(0.0);
+                let lambda = accumulated - point.normal_impulse;
+                point.normal_impulse = accumulated;
+                body_a.apply_impulse(-constraint.normal * lambda, point.offset_a);
+                body_b.apply_impulse(constraint.normal * lambda, point.offset_b);
+            }
+            bodies[constraint.a] = body_a;
+            bodies[constraint.b] = body_b;
+        }
+    }
+
+    for (input, constraint) in contacts.iter_mut().zip(&constraints) {
+        input.impulses = constraint
+            .points
+            .iter()
+            .map(|point| constraint.impulse(point))
+            .collect();
+    }
+}
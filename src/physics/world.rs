@@ -0,0 +1,508 @@
+use crate::core::object::Object;
+use crate::geometry::bounds::Aabb;
+use crate::physics::broad_phase::{BroadPhase, PairEvent, PairEventKind};
+use crate::physics::collider::{Collider, Pose};
+use crate::physics::contact::{collide_with_margin, intersects};
+use crate::physics::joint::Joint;
+use crate::physics::rigid_body::BodyType;
+use crate::physics::solver::{ContactInput, SolverBody, solve};
+use glam::{EulerRot, Mat3, Quat, Vec3};
+use std::collections::{HashMap, HashSet};
+
+// Surfaces closer than this count as touching, so bodies slow before they meet instead of
+// sinking in and bouncing back
+const CONTACT_MARGIN: f32 = 0.02;
+// Furthest a contact point can move in a step and still count as the same point
+const WARM_START_DISTANCE: f32 = 0.05;
+
+// An object entering, staying in or leaving a trigger, by object index. When both colliders
+// are triggers, `trigger` is the lower index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub trigger: usize,
+    pub other: usize,
+    pub kind: PairEventKind,
+}
+
+// Moves the objects that have rigid bodies. Steps always advance by the same fixed timestep,
+// however long the rendered frames take, so the simulation behaves the same at any frame rate.
+pub struct PhysicsWorld {
+    gravity: Vec3,
+    fixed_timestep: f32,
+    max_substeps: u32, // per advance, so a long frame can't stall the next ones
+    accumulator: f32,  // frame time not yet simulated
+    solver_iterations: u32,
+    contacts: Vec<ContactInput>, // from the last step, to warm start the next
+    broad_phase: BroadPhase,
+    triggered: Vec<(usize, usize)>, // (trigger, other) overlapping at the last step
+    trigger_events: Vec<TriggerEvent>, // from every step since they were last taken
+    joints: Vec<Joint>,
+}
+
+impl PhysicsWorld {
+    pub fn new() -> Self {
+        Self {
+            gravity: Vec3::new(0.0, -9.81, 0.0),
+            fixed_timestep: 1.0 / 60.0,
+            max_substeps: 8,
+            accumulator: 0.0,
+            solver_iterations: 10,
+            contacts: Vec::new(),
+            broad_phase: BroadPhase::new(),
+            triggered: Vec::new(),
+            trigger_events: Vec::new(),
+            joints: Vec::new(),
+        }
+    }
+
+    pub fn get_gravity(&self) -> Vec3 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vec3) {
+        self.gravity = gravity;
+    }
+
+    pub fn get_fixed_timestep(&self) -> f32 {
+        self.fixed_timestep
+    }
+
+    pub fn set_fixed_timestep(&mut self, fixed_timestep: f32) {
+        self.fixed_timestep = fixed_timestep.max(1e-4);
+    }
+
+    pub fn get_max_substeps(&self) -> u32 {
+        self.max_substeps
+    }
+
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.max_substeps = max_substeps.max(1);
+    }
+
+    pub fn get_solver_iterations(&self) -> u32 {
+        self.solver_iterations
+    }
+
+    // More iterations make stacks stiffer at the cost of time
+    pub fn set_solver_iterations(&mut self, solver_iterations: u32) {
+        self.solver_iterations = solver_iterations.max(1);
+    }
+
+    pub fn get_joints(&self) -> &Vec<Joint> {
+        &self.joints
+    }
+
+    pub fn get_joint_mut(&mut self, index: usize) -> &mut Joint {
+        &mut self.joints[index]
+    }
+
+    // Returns the joint's index
+    pub fn add_joint(&mut self, joint: Joint) -> usize {
+        self.joints.push(joint);
+        self.joints.len() - 1
+    }
+
+    // Later joints move down an index
+    pub fn remove_joint(&mut self, index: usize) -> Joint {
+        self.joints.remove(index)
+    }
+
+    // Pairs of objects whose collider boxes overlapped at the last step, by index
+    pub fn get_overlapping_pairs(&self) -> &Vec<(usize, usize)> {
+        self.broad_phase.get_pairs()
+    }
+
+    // Pairs that started, kept or stopped overlapping at the last step
+    pub fn get_pair_events(&self) -> &Vec<PairEvent> {
+        self.broad_phase.get_events()
+    }
+
+    // Hands over the trigger events queued by the steps since the last call, oldest first.
+    // Stay events come once per step.
+    pub fn take_trigger_events(&mut self) -> Vec<TriggerEvent> {
+        std::mem::take(&mut self.trigger_events)
+    }
+
+    // Adds `delta_time` of frame time and runs as many fixed steps as now fit, returning how
+    // many ran
+    pub fn step(&mut self, objects: &mut [Object], delta_time: f32) -> u32 {
+        let steps = self.advance(delta_time);
+        for _ in 0..steps {
+            self.step_fixed(objects, self.fixed_timestep);
+        }
+        steps
+    }
+
+    // Adds `delta_time` of frame time and takes out as many fixed steps as now fit, returning
+    // how many the caller should run with step_fixed. Time past max_substeps steps is dropped
+    // rather than carried over.
+    pub fn advance(&mut self, delta_time: f32) -> u32 {
+        self.accumulator += delta_time.max(0.0);
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_timestep && steps < self.max_substeps {
+            self.accumulator -= self.fixed_timestep;
+            steps += 1;
+        }
+        if steps == self.max_substeps {
+            // Keep only the part of a step, so the next frame doesn't start with one owed
+            self.accumulator %= self.fixed_timestep;
+        }
+        steps
+    }
+
+    // How far the frame time left over after the last fixed step goes toward the next one,
+    // from 0 to 1
+    pub fn get_interpolation_alpha(&self) -> f32 {
+        (self.accumulator / self.fixed_timestep).min(1.0)
+    }
+
+    // Advances every body by exactly `dt` with semi-implicit Euler: velocities first, then
+    // contacts push back on them, then the pose moves by the new velocities, about each
+    // body's center of mass
+    pub fn step_fixed(&mut self, objects: &mut [Object], dt: f32) {
+        for object in objects.iter_mut() {
+            let rotation = object.get_rotation_matrix();
+            let Some(body) = object.get_rigid_body_mut() else {
+                continue;
+            };
+            if body.get_body_type() == BodyType::Dynamic {
+                let inverse_inertia = body.get_world_inverse_inertia(rotation);
+                let acceleration = self.gravity * body.get_gravity_scale()
+                    + body.get_force() * body.get_inverse_mass();
+                let angular_acceleration = inverse_inertia * body.get_torque();
+                let linear_velocity = (body.get_linear_velocity() + acceleration * dt)
+                    * (1.0 / (1.0 + body.get_linear_damping() * dt));
+                let angular_velocity = (body.get_angular_velocity() + angular_acceleration * dt)
+                    * (1.0 / (1.0 + body.get_angular_damping() * dt));
+                body.set_linear_velocity(linear_velocity);
+                body.set_angular_velocity(angular_velocity);
+            }
+            body.clear_forces();
+        }
+
+        self.broad_phase.update(objects, CONTACT_MARGIN);
+        self.update_triggers(objects);
+        let mut contacts = self.find_contacts(objects);
+        self.warm_start(&mut contacts);
+        if !contacts.is_empty() || !self.joints.is_empty() {
+            let mut bodies: Vec<SolverBody> = objects.iter().map(solver_body).collect();
+            let joint_rows: Vec<_> = self
+                .joints
+                .iter_mut()
+                .map(|joint| joint.build_rows(&bodies, dt))
+                .collect();
+            let counts: Vec<usize> = joint_rows.iter().map(Vec::len).collect();
+            let mut rows: Vec<_> = joint_rows.into_iter().flatten().collect();
+            solve(
+                &mut bodies,
+                &mut contacts,
+                &mut rows,
+                dt,
+                self.solver_iterations,
+            );
+            let mut rows = rows.into_iter();
+            for (joint, count) in self.joints.iter_mut().zip(counts) {
+                joint.set_impulses(rows.by_ref().take(count).map(|row| row.impulse).collect());
+            }
+            for (object, solved) in objects.iter_mut().zip(&bodies) {
+                if let Some(body) = object.get_rigid_body_mut()
+                    && body.get_body_type() == BodyType::Dynamic
+                {
+                    body.set_linear_velocity(solved.linear_velocity);
+                    body.set_angular_velocity(solved.angular_velocity);
+                }
+            }
+        }
+        self.contacts = contacts;
+
+        for object in objects.iter_mut() {
+            let rotation = euler_to_quat(*object.get_rotation());
+            let position = *object.get_position();
+            let Some(body) = object.get_rigid_body() else {
+                continue;
+            };
+            if body.get_body_type() == BodyType::Static {
+                continue;
+            }
+            let local_center = body.get_local_center_of_mass();
+            let center = position + rotation * local_center + body.get_linear_velocity() * dt;
+            let rotation = integrate_rotation(rotation, body.get_angular_velocity(), dt);
+            object.set_position(center - rotation * local_center);
+            object.set_rotation(quat_to_euler(rotation));
+        }
+        self.move_characters(objects, dt);
+        self.step_soft_bodies(objects, dt);
+    }
+
+    // Walks each object with a character controller among the other objects' colliders
+    fn move_characters(&self, objects: &mut [Object], dt: f32) {
+        for index in 0..objects.len() {
+            let Some(mut controller) = objects[index].get_character_controller().cloned() else {
+                continue;
+            };
+            let position = *objects[index].get_position();
+            // Anything it could reach this step
+            let speed = controller.get_walk_velocity().length()
+                + controller.get_velocity().length()
+                + self.gravity.length() * dt;
+            let reach = speed * dt + controller.get_step_height() + CONTACT_MARGIN;
+            let extents =
+                Vec3::new(0.0, controller.get_half_height(), 0.0) + controller.get_radius() + reach;
+            let region = Aabb::new(position - extents, position + extents);
+            let own_collider = objects[index].get_collider();
+
+            let obstacles: Vec<(&Collider, Pose)> = objects
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .filter_map(|(_, object)| {
+                    let collider = object.get_collider()?;
+                    let pose = Pose::from_object(object);
+                    let blocks = !collider.get_trigger()
+                        && own_collider.is_none_or(|own| own.interacts_with(collider))
+                        && collider.get_world_aabb(&pose).intersects(&region);
+                    blocks.then_some((collider, pose))
+                })
+                .collect();
+            let position = controller.step(position, &obstacles, self.gravity.y, dt);
+            objects[index].set_position(position);
+            objects[index].set_character_controller(Some(controller));
+        }
+    }
+
+    // Moves each soft body's particles among the other objects' colliders, then its mesh to
+    // match
+    fn step_soft_bodies(&self, objects: &mut [Object], dt: f32) {
+        for index in 0..objects.len() {
+            let Some(mut soft_body) = objects[index].get_soft_body().cloned() else {
+                continue;
+            };
+            // Anything the particles could reach this step
+            let speed = soft_body
+                .get_velocities()
+                .iter()
+                .map(|velocity| velocity.length())
+                .fold(0.0, f32::max)
+                + self.gravity.length() * dt
+                + soft_body.get_wind().length();
+            let region =
+                soft_body.get_aabb(speed * dt + soft_body.get_thickness() + CONTACT_MARGIN);
+            let own_collider = objects[index].get_collider();
+
+            let obstacles: Vec<(&Collider, Pose)> = objects
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .filter_map(|(_, object)| {
+                    let collider = object.get_collider()?;
+                    let pose = Pose::from_object(object);
+                    let blocks = !collider.get_trigger()
+                        && own_collider.is_none_or(|own| own.interacts_with(collider))
+                        && collider.get_world_aabb(&pose).intersects(&region);
+                    blocks.then_some((collider, pose))
+                })
+                .collect();
+            soft_body.step(&obstacles, self.gravity, dt);
+            let mesh = soft_body.deform(
+                objects[index].get_mesh(),
+                &Pose::from_object(&objects[index]),
+            );
+            objects[index].set_mesh(mesh);
+            objects[index].set_soft_body(Some(soft_body));
+        }
+    }
+
+    // Contacts between the broad phase's pairs that could push each other, with surface
+    // properties mixed from both
+    fn find_contacts(&self, objects: &[Object]) -> Vec<ContactInput> {
+        let is_dynamic = |index: usize| {
+            objects[index]
+                .get_rigid_body()
+                .is_some_and(|body| body.get_body_type() == BodyType::Dynamic)
+        };
+
+        // Jointed objects pass through each other unless the joint says otherwise
+        let connected: HashSet<(usize, usize)> = self
+            .joints
+            .iter()
+            .filter(|joint| !joint.get_collide_connected())
+            .map(|joint| {
+                let (a, b) = joint.get_objects();
+                (a.min(b), a.max(b))
+            })
+            .collect();
+
+        let mut contacts = Vec::new();
+        for &(a, b) in self.broad_phase.get_pairs() {
+            let (collider_a, collider_b) = (
+                objects[a].get_collider().unwrap(),
+                objects[b].get_collider().unwrap(),
+            );
+            let solid = !(collider_a.get_trigger() || collider_b.get_trigger());
+            if !solid || !(is_dynamic(a) || is_dynamic(b)) || connected.contains(&(a, b)) {
+                continue;
+            }
+            let (pose_a, pose_b) = (
+                Pose::from_object(&objects[a]),
+                Pose::from_object(&objects[b]),
+            );
+            let friction = (collider_a.get_friction() * collider_b.get_friction()).sqrt();
+            let restitution = collider_a
+                .get_restitution()
+                .max(collider_b.get_restitution());
+            contacts.extend(
+                collide_with_margin(collider_a, &pose_a, collider_b, &pose_b, CONTACT_MARGIN)
+                    .into_iter()
+                    .map(|contact| ContactInput {
+                        a,
+                        b,
+                        contact,
+                        friction,
+                        restitution,
+                        impulses: Vec::new(),
+                    }),
+            );
+        }
+        contacts
+    }
+
+    // Finds which of the broad phase's pairs with a trigger in them really overlap, and
+    // queues what changed since the last step
+    fn update_triggers(&mut self, objects: &[Object]) {
+        let mut triggered = Vec::new();
+        for &(a, b) in self.broad_phase.get_pairs() {
+            let (collider_a, collider_b) = (
+                objects[a].get_collider().unwrap(),
+                objects[b].get_collider().unwrap(),
+            );
+            let pair = if collider_a.get_trigger() {
+                (a, b)
+            } else if collider_b.get_trigger() {
+                (b, a)
+            } else {
+                continue;
+            };
+            let (pose_a, pose_b) = (
+                Pose::from_object(&objects[a]),
+                Pose::from_object(&objects[b]),
+            );
+            if intersects(collider_a, &pose_a, collider_b, &pose_b) {
+                triggered.push(pair);
+            }
+        }
+
+        let previous: HashSet<(usize, usize)> = self.triggered.iter().copied().collect();
+        let current: HashSet<(usize, usize)> = triggered.iter().copied().collect();
+        let event = |(trigger, other): (usize, usize), kind| TriggerEvent {
+            trigger,
+            other,
+            kind,
+        };
+        for &pair in &triggered {
+            let kind = if previous.contains(&pair) {
+                PairEventKind::Stay
+            } else {
+                PairEventKind::Enter
+            };
+            self.trigger_events.push(event(pair, kind));
+        }
+        for &pair in &self.triggered {
+            if !current.contains(&pair) {
+                self.trigger_events.push(event(pair, PairEventKind::Exit));
+            }
+        }
+        self.triggered = triggered;
+    }
+
+    // Starts each contact point from the impulse of last step's point on the same pair of
+    // objects that was closest to it, or from nothing when there wasn't one nearby
+    fn warm_start(&self, contacts: &mut [ContactInput]) {
+        let mut previous: HashMap<(usize, usize), Vec<CachedImpulse>> = HashMap::new();
+        for contact in &self.contacts {
+            let points = contact.contact.points.iter().zip(&contact.impulses);
+            previous
+                .entry((contact.a, contact.b))
+                .or_default()
+                .extend(points.map(|(point, impulse)| CachedImpulse {
+                    normal: contact.contact.normal,
+                    point: point.point,
+                    impulse: *impulse,
+                }));
+        }
+
+        for contact in contacts {
+            let candidates = previous.get(&(contact.a, contact.b));
+            contact.impulses = contact
+                .contact
+                .points
+                .iter()
+                .map(|point| {
+                    candidates
+                        .into_iter()
+                        .flatten()
+                        .filter(|cached| cached.normal.dot(contact.contact.normal) > 0.95)
+                        .map(|cached| (cached.point.distance(point.point), cached.impulse))
+                        .filter(|(distance, _)| *distance < WARM_START_DISTANCE)
+                        .min_by(|a, b| a.0.total_cmp(&b.0))
+                        .map_or(Vec3::ZERO, |(_, impulse)| impulse)
+                })
+                .collect();
+        }
+    }
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// A contact point from the last step and the impulse the solver settled on for it
+struct CachedImpulse {
+    normal: Vec3,
+    point: Vec3,
+    impulse: Vec3,
+}
+
+fn solver_body(object: &Object) -> SolverBody {
+    let rotation = object.get_rotation_matrix();
+    let center = object.get_world_center_of_mass();
+    let orientation = euler_to_quat(*object.get_rotation());
+    match object.get_rigid_body() {
+        Some(body) => SolverBody {
+            inverse_mass: body.get_inverse_mass(),
+            inverse_inertia: body.get_world_inverse_inertia(rotation),
+            linear_velocity: body.get_linear_velocity(),
+            angular_velocity: body.get_angular_velocity(),
+            center,
+            rotation: orientation,
+        },
+        None => SolverBody {
+            inverse_mass: 0.0,
+            inverse_inertia: Mat3::ZERO,
+            linear_velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            center,
+            rotation: orientation,
+        },
+    }
+}
+
+// Object rotations are Euler angles applied x, then y, then z
+pub(crate) fn euler_to_quat(rotation: Vec3) -> Quat {
+    Quat::from_euler(EulerRot::ZYX, rotation.z, rotation.y, rotation.x)
+}
+
+pub(crate) fn quat_to_euler(rotation: Quat) -> Vec3 {
+    let (z, y, x) = rotation.to_euler(EulerRot::ZYX);
+    Vec3::new(x, y, z)
+}
+
+// Turns `rotation` by the world-space `angular_velocity` for `dt` seconds
+pub(crate) fn integrate_rotation(rotation: Quat, angular_velocity: Vec3, dt: f32) -> Quat {
+    let angle = angular_velocity.length() * dt;
+    if angle <= f32::EPSILON {
+        return rotation;
+    }
+    (Quat::from_axis_angle(angular_velocity.normalize(), angle) * rotation).normalize()
+}
@@ -1,18 +1,18 @@
-// Vertex shader for line rendering
+// Vertex shader for unlit lines, such as wireframe edges
 struct VertexInput {
-    @location(0) pos: vec3<f32>,
-    @location(1) normal: vec3<f32>,
+    @location(0) pos: vec4<f32>,
+    @location(1) color: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) pos: vec4<f32>,
-    @location(0) normal: vec3<f32>,
+    @location(0) color: vec4<f32>,
 };
 
 @vertex
 fn vs_main(input: VertexInput) -> VertexOutput {
     var output: VertexOutput;
-    output.pos = vec4<f32>(input.pos, 1.0);
-    output.normal = input.normal;
+    output.pos = input.pos;
+    output.color = input.color;
     return output;
-} 
\ No newline at end of file
+}
@@ -0,0 +1,299 @@
+// Fragment shader for shaded meshes
+struct Light {
+    position: vec3<f32>,
+    light_type: u32,
+    direction: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+    range: f32,
+    shadow_index: i32,
+};
+
+struct Lights {
+    count: u32,
+    lights: array<Light, 8>,
+};
+
+struct Camera {
+    position: vec3<f32>,
+    forward: vec3<f32>,
+};
+
+struct Material {
+    base_color: vec4<f32>,
+    emissive: vec3<f32>,
+    shading_model: u32,
+    metallic: f32,
+    roughness: f32,
+    specular: f32,
+    shininess: f32,
+    normal_scale: f32,
+    receive_shadows: u32,
+    tint: vec4<f32>,
+};
+
+struct PointShadow {
+    near: f32,
+    far: f32,
+    depth_bias: f32,
+    normal_bias: f32,
+    texel_scale: f32,
+    pcf_radius: u32,
+    _padding: vec2<f32>,
+};
+
+struct Shadow {
+    cascades: array<mat4x4<f32>, 4>,
+    splits: vec4<f32>,
+    texel_sizes: vec4<f32>,
+    cascade_count: u32,
+    depth_bias: f32,
+    normal_bias: f32,
+    pcf_radius: u32,
+    point_shadows: array<PointShadow, 4>,
+};
+
+const SHADING_UNLIT: u32 = 0u;
+const SHADING_BLINN_PHONG: u32 = 1u;
+const SHADING_PBR: u32 = 2u;
+
+const LIGHT_DIRECTIONAL: u32 = 0u;
+const LIGHT_POINT: u32 = 1u;
+
+const PI: f32 = 3.14159265359;
+
+@group(0) @binding(0) var<uniform> lights: Lights;
+@group(0) @binding(1) var<uniform> camera: Camera;
+@group(1) @binding(0) var<uniform> material: Material;
+@group(2) @binding(0) var base_color_texture: texture_2d<f32>;
+@group(2) @binding(1) var base_color_sampler: sampler;
+@group(2) @binding(2) var emissive_texture: texture_2d<f32>;
+@group(2) @binding(3) var emissive_sampler: sampler;
+@group(2) @binding(4) var normal_texture: texture_2d<f32>;
+@group(2) @binding(5) var normal_sampler: sampler;
+@group(3) @binding(0) var<uniform> shadow: Shadow;
+@group(3) @binding(1) var shadow_map: texture_depth_2d_array;
+@group(3) @binding(2) var shadow_sampler: sampler_comparison;
+@group(3) @binding(3) var point_shadow_map_0: texture_depth_cube;
+@group(3) @binding(4) var point_shadow_map_1: texture_depth_cube;
+@group(3) @binding(5) var point_shadow_map_2: texture_depth_cube;
+@group(3) @binding(6) var point_shadow_map_3: texture_depth_cube;
+
+// GGX / Trowbridge-Reitz normal distribution
+fn distribution_ggx(n_dot_h: f32, alpha: f32) -> f32 {
+    let alpha2 = alpha * alpha;
+    let d = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    return alpha2 / (PI * d * d);
+}
+
+// Height-correlated Smith visibility term (G / (4 * n_dot_l * n_dot_v)), as used by glTF
+fn visibility_smith_ggx(n_dot_l: f32, n_dot_v: f32, alpha: f32) -> f32 {
+    let alpha2 = alpha * alpha;
+    let ggx_v = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - alpha2) + alpha2);
+    let ggx_l = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - alpha2) + alpha2);
+    let ggx = ggx_v + ggx_l;
+    if (ggx > 0.0) {
+        return 0.5 / ggx;
+    }
+    return 0.0;
+}
+
+fn fresnel_schlick(v_dot_h: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (vec3<f32>(1.0) - f0) * pow(1.0 - v_dot_h, 5.0);
+}
+
+fn shade_blinn_phong(base_color: vec3<f32>, n: vec3<f32>, v: vec3<f32>, l: vec3<f32>, radiance: vec3<f32>) -> vec3<f32> {
+    let n_dot_l = max(dot(n, l), 0.0);
+    let diffuse = base_color * n_dot_l;
+    var specular = 0.0;
+    if (n_dot_l > 0.0) {
+        let h = normalize(l + v);
+        specular = material.specular * pow(max(dot(n, h), 0.0), material.shininess);
+    }
+    return (diffuse + vec3<f32>(specular)) * radiance;
+}
+
+// Cook-Torrance metallic-roughness BRDF following the glTF 2.0 reference implementation
+fn shade_pbr(base_color: vec3<f32>, n: vec3<f32>, v: vec3<f32>, l: vec3<f32>, radiance: vec3<f32>) -> vec3<f32> {
+    let n_dot_l = max(dot(n, l), 0.0);
+    if (n_dot_l <= 0.0) {
+        return vec3<f32>(0.0);
+    }
+    let h = normalize(l + v);
+    let n_dot_v = max(dot(n, v), 1e-4);
+    let n_dot_h = max(dot(n, h), 0.0);
+    let v_dot_h = max(dot(v, h), 0.0);
+
+    let roughness = clamp(material.roughness, 0.04, 1.0);
+    let alpha = roughness * roughness;
+    let f0 = mix(vec3<f32>(0.04), base_color, material.metallic);
+
+    let f = fresnel_schlick(v_dot_h, f0);
+    let specular = f * distribution_ggx(n_dot_h, alpha) * visibility_smith_ggx(n_dot_l, n_dot_v, alpha);
+    let diffuse = (vec3<f32>(1.0) - f) * (1.0 - material.metallic) * base_color / PI;
+
+    return (diffuse + specular) * radiance * n_dot_l;
+}
+
+// Perturbs the interpolated normal with the tangent-space normal map
+fn apply_normal_map(normal: vec3<f32>, tangent: vec4<f32>, sampled: vec3<f32>) -> vec3<f32> {
+    let n = normalize(normal);
+    let t = normalize(tangent.xyz - n * dot(n, tangent.xyz));
+    let b = cross(n, t) * tangent.w;
+    var tangent_normal = sampled * 2.0 - vec3<f32>(1.0);
+    tangent_normal = vec3<f32>(tangent_normal.xy * material.normal_scale, tangent_normal.z);
+    return normalize(t * tangent_normal.x + b * tangent_normal.y + n * tangent_normal.z);
+}
+
+// Fraction of light reaching `world_pos` (0 fully shadowed, 1 fully lit), PCF filtered
+fn shadow_factor(world_pos: vec3<f32>, geometric_normal: vec3<f32>) -> f32 {
+    if (shadow.cascade_count == 0u || material.receive_shadows == 0u) {
+        return 1.0;
+    }
+
+    let view_depth = dot(world_pos - camera.position, camera.forward);
+    var cascade = 0u;
+    loop {
+        if (cascade + 1u >= shadow.cascade_count || view_depth <= shadow.splits[cascade]) {
+            break;
+        }
+        cascade += 1u;
+    }
+    if (view_depth > shadow.splits[cascade]) {
+        return 1.0; // beyond the shadow distance
+    }
+
+    // Offset the receiver along its normal by a few texels against shadow acne
+    let offset_pos = world_pos + geometric_normal * shadow.normal_bias * shadow.texel_sizes[cascade];
+    let clip = shadow.cascades[cascade] * vec4<f32>(offset_pos, 1.0);
+    let ndc = clip.xyz / clip.w;
+    let uv = vec2<f32>(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+    if (any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) || ndc.z > 1.0) {
+        return 1.0;
+    }
+
+    let depth = ndc.z - shadow.depth_bias;
+    let texel = 1.0 / vec2<f32>(textureDimensions(shadow_map));
+    let radius = i32(shadow.pcf_radius);
+    var lit = 0.0;
+    var taps = 0.0;
+    for (var y = -radius; y <= radius; y++) {
+        for (var x = -radius; x <= radius; x++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * texel;
+            lit += textureSampleCompareLevel(shadow_map, shadow_sampler, uv + offset, i32(cascade), depth);
+            taps += 1.0;
+        }
+    }
+    return lit / taps;
+}
+
+fn sample_point_shadow(slot: i32, direction: vec3<f32>, depth: f32) -> f32 {
+    if (slot == 0) {
+        return textureSampleCompareLevel(point_shadow_map_0, shadow_sampler, direction, depth);
+    } else if (slot == 1) {
+        return textureSampleCompareLevel(point_shadow_map_1, shadow_sampler, direction, depth);
+    } else if (slot == 2) {
+        return textureSampleCompareLevel(point_shadow_map_2, shadow_sampler, direction, depth);
+    }
+    return textureSampleCompareLevel(point_shadow_map_3, shadow_sampler, direction, depth);
+}
+
+// Like shadow_factor, for the point light using cube map `slot`
+fn point_shadow_factor(slot: i32, light_pos: vec3<f32>, world_pos: vec3<f32>, geometric_normal: vec3<f32>) -> f32 {
+    if (material.receive_shadows == 0u) {
+        return 1.0;
+    }
+
+    let point_shadow = shadow.point_shadows[slot];
+    let distance = length(world_pos - light_pos);
+    // A texel covers more world space the further the receiver is from the light
+    let texel_size = point_shadow.texel_scale * distance;
+    let to_receiver = world_pos + geometric_normal * point_shadow.normal_bias * texel_size - light_pos;
+
+    // Each face stores perspective depth along its major axis
+    let major = max(abs(to_receiver.x), max(abs(to_receiver.y), abs(to_receiver.z))) * (1.0 - point_shadow.depth_bias);
+    if (major >= point_shadow.far) {
+        return 1.0;
+    }
+    let n = point_shadow.near;
+    let f = point_shadow.far;
+    let depth = f / (f - n) - n * f / ((f - n) * major);
+
+    // PCF over a grid perpendicular to the lookup direction
+    let direction = normalize(to_receiver);
+    var up = vec3<f32>(0.0, 1.0, 0.0);
+    if (abs(direction.y) > 0.99) {
+        up = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    let axis_u = normalize(cross(up, direction)) * point_shadow.texel_scale;
+    let axis_v = cross(direction, axis_u);
+    let radius = i32(point_shadow.pcf_radius);
+    var lit = 0.0;
+    var taps = 0.0;
+    for (var y = -radius; y <= radius; y++) {
+        for (var x = -radius; x <= radius; x++) {
+            let offset = axis_u * f32(x) + axis_v * f32(y);
+            lit += sample_point_shadow(slot, direction + offset, depth);
+            taps += 1.0;
+        }
+    }
+    return lit / taps;
+}
+
+// Mixes in the per-object tint, used to highlight the hovered object
+fn apply_tint(color: vec3<f32>) -> vec3<f32> {
+    return mix(color, material.tint.rgb, material.tint.a);
+}
+
+@fragment
+fn fs_main(
+    @location(0) normal: vec3<f32>,
+    @location(1) world_pos: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
+) -> @location(0) vec4<f32> {
+    // Texture factors multiply the material factors, as in glTF
+    let base_color = material.base_color.rgb * textureSample(base_color_texture, base_color_sampler, uv).rgb;
+    let emissive = material.emissive * textureSample(emissive_texture, emissive_sampler, uv).rgb;
+    let sampled_normal = textureSample(normal_texture, normal_sampler, uv).rgb;
+
+    if (material.shading_model == SHADING_UNLIT) {
+        return vec4<f32>(apply_tint(base_color + emissive), 1.0);
+    }
+
+    let n = apply_normal_map(normal, tangent, sampled_normal);
+    let v = normalize(camera.position - world_pos);
+    let geometric_normal = normalize(normal);
+
+    var color = vec3<f32>(0.0);
+    for (var i = 0u; i < lights.count; i++) {
+        let light = lights.lights[i];
+        var l: vec3<f32>;
+        var radiance = light.color * light.intensity;
+        if (light.light_type == LIGHT_POINT) {
+            let to_light = light.position - world_pos;
+            let distance = length(to_light);
+            l = to_light / max(distance, 1e-4);
+            // Inverse square falloff with a smooth cutoff at the range, as in KHR_lights_punctual
+            let cutoff = clamp(1.0 - pow(distance / light.range, 4.0), 0.0, 1.0);
+            radiance *= cutoff * cutoff / max(distance * distance, 1e-4);
+            if (light.shadow_index >= 0) {
+                radiance *= point_shadow_factor(light.shadow_index, light.position, world_pos, geometric_normal);
+            }
+        } else {
+            l = -light.direction;
+            if (light.shadow_index >= 0) {
+                radiance *= shadow_factor(world_pos, geometric_normal);
+            }
+        }
+
+        if (material.shading_model == SHADING_PBR) {
+            color += shade_pbr(base_color, n, v, l, radiance);
+        } else {
+            color += shade_blinn_phong(base_color, n, v, l, radiance);
+        }
+    }
+
+    let final_color = color + emissive;
+    return vec4<f32>(apply_tint(final_color), 1.0);
+}
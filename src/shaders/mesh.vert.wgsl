@@ -0,0 +1,27 @@
+// Vertex shader for shaded meshes
+struct VertexInput {
+    @location(0) pos: vec4<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) world_pos: vec3<f32>,
+    @location(3) uv: vec2<f32>,
+    @location(4) tangent: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) @invariant pos: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) world_pos: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
+};
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var output: VertexOutput;
+    output.pos = input.pos;
+    output.normal = input.normal;
+    output.world_pos = input.world_pos;
+    output.uv = input.uv;
+    output.tangent = input.tangent;
+    return output;
+}
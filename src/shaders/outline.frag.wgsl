@@ -0,0 +1,38 @@
+// Fragment shaders for the selection outline
+struct Outline {
+    color: vec4<f32>,
+    thickness: f32,
+};
+
+@group(0) @binding(0) var<uniform> outline: Outline;
+@group(0) @binding(1) var mask: texture_2d<f32>;
+
+@fragment
+fn fs_mask() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0);
+}
+
+// Colors the pixels outside the mask that are within the thickness of a pixel inside it and
+// leaves the rest transparent
+@fragment
+fn fs_outline(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let size = vec2<i32>(textureDimensions(mask));
+    let pixel = vec2<i32>(pos.xy);
+    if (textureLoad(mask, pixel, 0).r > 0.5) {
+        return vec4<f32>(0.0);
+    }
+    let radius = i32(ceil(outline.thickness));
+    for (var dy = -radius; dy <= radius; dy++) {
+        for (var dx = -radius; dx <= radius; dx++) {
+            let neighbor = pixel + vec2<i32>(dx, dy);
+            if (f32(dx * dx + dy * dy) > outline.thickness * outline.thickness
+                || any(neighbor < vec2<i32>(0)) || any(neighbor >= size)) {
+                continue;
+            }
+            if (textureLoad(mask, neighbor, 0).r > 0.5) {
+                return outline.color;
+            }
+        }
+    }
+    return vec4<f32>(0.0);
+}
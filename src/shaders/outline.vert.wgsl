@@ -0,0 +1,18 @@
+// Vertex shaders for the selection outline: one draws the selected objects' triangles into a
+// mask, the other a triangle covering the screen to draw the outline around the mask
+struct MaskOutput {
+    @builtin(position) @invariant pos: vec4<f32>,
+};
+
+@vertex
+fn vs_mask(@location(0) pos: vec4<f32>) -> MaskOutput {
+    var output: MaskOutput;
+    output.pos = pos;
+    return output;
+}
+
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
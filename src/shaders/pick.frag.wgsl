@@ -0,0 +1,5 @@
+// Fragment shader for the object id pass used for picking
+@fragment
+fn fs_main(@location(0) @interpolate(flat) id: u32) -> @location(0) u32 {
+    return id;
+}
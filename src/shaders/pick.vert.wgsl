@@ -0,0 +1,14 @@
+// Vertex shader for the object id pass used for picking. The id comes in as the instance
+// index, so every object is drawn as the one instance with its id.
+struct VertexOutput {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) @interpolate(flat) id: u32,
+};
+
+@vertex
+fn vs_main(@location(0) pos: vec4<f32>, @builtin(instance_index) id: u32) -> VertexOutput {
+    var output: VertexOutput;
+    output.pos = pos;
+    output.id = id;
+    return output;
+}
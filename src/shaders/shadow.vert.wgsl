@@ -0,0 +1,7 @@
+// Depth-only vertex shader for rendering shadow casters from the light's view
+@group(0) @binding(0) var<uniform> light_view_projection: mat4x4<f32>;
+
+@vertex
+fn vs_main(@location(0) world_pos: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return light_view_projection * vec4<f32>(world_pos, 1.0);
+}
@@ -0,0 +1,79 @@
+use glam::Vec3;
+use std::f32::consts::FRAC_PI_4;
+use three_d::geometry::bounds::{Aabb, BoundingSphere};
+use three_d::geometry::mesh::Mesh;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::rectangular_prism::RectangularPrism;
+use three_d::geometry::triangle::Triangle;
+
+fn assert_near(actual: Vec3, expected: Vec3) {
+    assert!(
+        actual.abs_diff_eq(expected, 1e-5),
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn mesh_bounds() {
+    let prism = RectangularPrism::new(2.0, 1.0, 4.0);
+    let aabb = prism.get_mesh().get_aabb();
+    assert_near(aabb.min, Vec3::new(-1.0, -0.5, -2.0));
+    assert_near(aabb.max, Vec3::new(1.0, 0.5, 2.0));
+
+    let sphere = prism.get_mesh().get_bounding_sphere();
+    assert_near(sphere.center, Vec3::ZERO);
+    assert!((sphere.radius - Vec3::new(1.0, 0.5, 2.0).length()).abs() < 1e-5);
+}
+
+#[test]
+fn set_triangles_invalidates_bounds() {
+    let mut mesh = Mesh::new(vec![Triangle::new([Vec3::ZERO, Vec3::X, Vec3::Y])]);
+    assert_near(mesh.get_aabb().max, Vec3::new(1.0, 1.0, 0.0));
+
+    mesh.set_triangles(vec![Triangle::new([
+        Vec3::ZERO,
+        Vec3::new(3.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+    ])]);
+    assert_near(mesh.get_aabb().max, Vec3::new(3.0, 0.0, 2.0));
+    assert_eq!(
+        Mesh::new(Vec::new()).get_aabb(),
+        Aabb::new(Vec3::ZERO, Vec3::ZERO)
+    );
+}
+
+#[test]
+fn world_bounds_follow_the_transform() {
+    let mut cube = Cube::new(2.0);
+    cube.set_position(Vec3::new(5.0, 0.0, 1.0));
+    let aabb = cube.get_world_aabb();
+    assert_near(aabb.min, Vec3::new(4.0, -1.0, 0.0));
+    assert_near(aabb.max, Vec3::new(6.0, 1.0, 2.0));
+
+    // A quarter turn around y widens the box along x and z by a factor of sqrt(2)
+    cube.set_rotation(Vec3::new(0.0, FRAC_PI_4, 0.0));
+    let aabb = cube.get_world_aabb();
+    let half = 2.0f32.sqrt();
+    assert_near(aabb.min, Vec3::new(5.0 - half, -1.0, 1.0 - half));
+    assert_near(aabb.max, Vec3::new(5.0 + half, 1.0, 1.0 + half));
+
+    let sphere = cube.get_world_bounding_sphere();
+    assert_near(sphere.center, Vec3::new(5.0, 0.0, 1.0));
+    assert!((sphere.radius - 3.0f32.sqrt()).abs() < 1e-5);
+}
+
+#[test]
+fn overlap_tests() {
+    let a = Aabb::new(Vec3::ZERO, Vec3::ONE);
+    assert!(a.intersects(&Aabb::new(Vec3::splat(0.5), Vec3::splat(2.0))));
+    assert!(!a.intersects(&Aabb::new(Vec3::splat(1.5), Vec3::splat(2.0))));
+    assert!(a.contains_point(Vec3::splat(0.5)));
+    assert_eq!(
+        a.union(&Aabb::new(Vec3::splat(2.0), Vec3::splat(3.0))).max,
+        Vec3::splat(3.0)
+    );
+
+    let sphere = BoundingSphere::new(Vec3::ZERO, 1.0);
+    assert!(sphere.intersects(&BoundingSphere::new(Vec3::new(1.5, 0.0, 0.0), 0.6)));
+    assert!(!sphere.contains_point(Vec3::new(1.1, 0.0, 0.0)));
+}
@@ -0,0 +1,166 @@
+use glam::Vec3;
+use three_d::core::object::Object;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::broad_phase::{BroadPhase, PairEvent, PairEventKind};
+use three_d::physics::collider::{Collider, Pose};
+use three_d::physics::rigid_body::RigidBody;
+use three_d::physics::world::PhysicsWorld;
+
+fn cube_at(position: Vec3, half_extent: f32) -> Object {
+    let mut cube = Cube::new(1.0);
+    cube.set_position(position);
+    cube.set_collider(Some(Collider::cuboid(Vec3::splat(half_extent))));
+    cube
+}
+
+fn event(a: usize, b: usize, kind: PairEventKind) -> PairEvent {
+    PairEvent { a, b, kind }
+}
+
+#[test]
+fn finds_the_same_pairs_as_checking_every_pair() {
+    // Scattered boxes of varied sizes, from a fixed pseudo-random sequence
+    let mut state = 12345u32;
+    let mut next = || {
+        state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+        (state >> 8) as f32 / (1 << 24) as f32
+    };
+    let mut objects: Vec<Object> = (0..300)
+        .map(|_| {
+            let position = Vec3::new(next() * 40.0, next() * 4.0, next() * 20.0);
+            cube_at(position, 0.2 + next())
+        })
+        .collect();
+    // One without a collider never pairs
+    objects.push(Cube::new(1.0));
+
+    let mut broad_phase = BroadPhase::new();
+    for frame in 0..3 {
+        for (index, object) in objects.iter_mut().enumerate() {
+            let position = *object.get_position();
+            object.set_position(position + Vec3::new(0.3, 0.0, -0.2) * (index % 3) as f32);
+        }
+        broad_phase.update(&objects, 0.0);
+
+        let mut expected = Vec::new();
+        for a in 0..objects.len() {
+            for b in a + 1..objects.len() {
+                let (Some(first), Some(second)) =
+                    (objects[a].get_collider(), objects[b].get_collider())
+                else {
+                    continue;
+                };
+                let first_aabb = first.get_world_aabb(&Pose::from_object(&objects[a]));
+                let second_aabb = second.get_world_aabb(&Pose::from_object(&objects[b]));
+                if first_aabb.intersects(&second_aabb) {
+                    expected.push((a, b));
+                }
+            }
+        }
+        assert!(!expected.is_empty());
+        assert_eq!(broad_phase.get_pairs(), &expected, "frame {frame}");
+    }
+}
+
+#[test]
+fn layers_and_masks_filter_pairs() {
+    let mut objects = vec![
+        cube_at(Vec3::ZERO, 1.0),
+        cube_at(Vec3::new(0.5, 0.0, 0.0), 1.0),
+        cube_at(Vec3::new(1.0, 0.0, 0.0), 1.0),
+    ];
+    let mut broad_phase = BroadPhase::new();
+    broad_phase.update(&objects, 0.0);
+    assert_eq!(broad_phase.get_pairs(), &vec![(0, 1), (0, 2), (1, 2)]);
+
+    // Object 1 is in group 2 and only collides with group 2, so it ignores the others
+    let mut collider = objects[1].get_collider().unwrap().clone();
+    collider.set_collision_layers(0b10);
+    collider.set_collision_mask(0b10);
+    objects[1].set_collider(Some(collider));
+    broad_phase.update(&objects, 0.0);
+    assert_eq!(broad_phase.get_pairs(), &vec![(0, 2)]);
+
+    // Both sides have to accept: object 2 joins group 2 but still only collides with group 1
+    let mut collider = objects[2].get_collider().unwrap().clone();
+    collider.set_collision_layers(0b11);
+    collider.set_collision_mask(0b01);
+    objects[2].set_collider(Some(collider));
+    broad_phase.update(&objects, 0.0);
+    assert_eq!(broad_phase.get_pairs(), &vec![(0, 2)]);
+    collider = objects[2].get_collider().unwrap().clone();
+    collider.set_collision_mask(0b11);
+    objects[2].set_collider(Some(collider));
+    broad_phase.update(&objects, 0.0);
+    assert_eq!(broad_phase.get_pairs(), &vec![(0, 2), (1, 2)]);
+}
+
+#[test]
+fn reports_enter_stay_and_exit() {
+    let mut objects = vec![
+        cube_at(Vec3::ZERO, 0.5),
+        cube_at(Vec3::new(3.0, 0.0, 0.0), 0.5),
+        cube_at(Vec3::new(0.0, 0.8, 0.0), 0.5),
+    ];
+    let mut broad_phase = BroadPhase::new();
+    broad_phase.update(&objects, 0.0);
+    assert_eq!(
+        broad_phase.get_events(),
+        &vec![event(0, 2, PairEventKind::Enter)]
+    );
+
+    objects[1].set_position(Vec3::new(0.6, 0.0, 0.0));
+    broad_phase.update(&objects, 0.0);
+    assert_eq!(
+        broad_phase.get_events(),
+        &vec![
+            event(0, 1, PairEventKind::Enter),
+            event(0, 2, PairEventKind::Stay),
+            event(1, 2, PairEventKind::Enter),
+        ]
+    );
+
+    // Removing a collider ends its pairs too
+    objects[1].set_position(Vec3::new(3.0, 0.0, 0.0));
+    objects[2].set_collider(None);
+    broad_phase.update(&objects, 0.0);
+    assert_eq!(
+        broad_phase.get_events(),
+        &vec![
+            event(0, 1, PairEventKind::Exit),
+            event(0, 2, PairEventKind::Exit),
+            event(1, 2, PairEventKind::Exit),
+        ]
+    );
+
+    broad_phase.update(&objects, 0.0);
+    assert!(broad_phase.get_events().is_empty());
+}
+
+#[test]
+fn bodies_on_layers_that_ignore_each_other_pass_through() {
+    let mut ground = cube_at(Vec3::ZERO, 0.5);
+    ground.set_collider(Some(Collider::cuboid(Vec3::new(10.0, 0.5, 10.0))));
+    let mut ghost = cube_at(Vec3::new(0.0, 2.0, 0.0), 0.5);
+    let mut collider = Collider::cuboid(Vec3::splat(0.5));
+    collider.set_collision_layers(0b10);
+    collider.set_collision_mask(0b10);
+    ghost.set_collider(Some(collider));
+    ghost.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+    let mut solid = cube_at(Vec3::new(3.0, 2.0, 0.0), 0.5);
+    solid.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+    let mut objects = vec![ground, ghost, solid];
+
+    let mut world = PhysicsWorld::new();
+    let mut entered = false;
+    for _ in 0..60 {
+        world.step_fixed(&mut objects, 1.0 / 60.0);
+        entered |= world
+            .get_pair_events()
+            .contains(&event(0, 2, PairEventKind::Enter));
+    }
+    assert!(entered);
+    assert!(objects[1].get_position().y < -1.0);
+    assert!((objects[2].get_position().y - 1.0).abs() < 0.05);
+    assert_eq!(world.get_overlapping_pairs(), &vec![(0, 2)]);
+}
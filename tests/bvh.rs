@@ -0,0 +1,213 @@
+use glam::Vec3;
+use three_d::core::scene::Scene;
+use three_d::geometry::bounds::Aabb;
+use three_d::geometry::bvh::Bvh;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::sphere::Sphere;
+use three_d::geometry::ray::Ray;
+
+// Unit boxes on a 6x6x6 grid, two units apart
+fn grid_boxes() -> Vec<Aabb> {
+    let mut boxes = Vec::new();
+    for x in 0..6 {
+        for y in 0..6 {
+            for z in 0..6 {
+                let min = Vec3::new(x as f32, y as f32, z as f32) * 2.0;
+                boxes.push(Aabb::new(min, min + Vec3::ONE));
+            }
+        }
+    }
+    boxes
+}
+
+fn brute_force_raycast(boxes: &[Aabb], ray: &Ray, max_distance: f32) -> Option<(usize, f32)> {
+    boxes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, aabb)| Some((index, aabb.intersect_ray(ray, max_distance)?)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+#[test]
+fn raycast_matches_brute_force() {
+    let boxes = grid_boxes();
+    let bvh = Bvh::new(&boxes);
+    assert_eq!(bvh.len(), boxes.len());
+
+    let origins = [
+        Vec3::new(-3.0, 0.5, 0.5),
+        Vec3::new(5.5, 20.0, 4.5),
+        Vec3::new(-4.0, -3.0, -5.0),
+        Vec3::new(12.0, 12.0, 12.0),
+    ];
+    let directions = [
+        Vec3::X,
+        Vec3::NEG_Y,
+        Vec3::new(1.0, 0.9, 1.1),
+        Vec3::new(-1.0, -0.2, -0.7),
+        Vec3::new(0.3, 1.0, -0.4),
+    ];
+    for origin in origins {
+        for direction in directions {
+            let ray = Ray::new(origin, direction);
+            let hit = bvh.raycast(&ray, 100.0, |index, max| {
+                boxes[index].intersect_ray(&ray, max)
+            });
+            assert_eq!(
+                hit.map(|(_, distance)| distance),
+                brute_force_raycast(&boxes, &ray, 100.0).map(|(_, distance)| distance),
+                "ray from {origin} along {direction}"
+            );
+            assert_eq!(
+                bvh.raycast_any(&ray, 100.0, |index, max| boxes[index]
+                    .intersect_ray(&ray, max)),
+                hit.is_some()
+            );
+        }
+    }
+
+    // Nothing past the limit
+    let ray = Ray::new(Vec3::new(-3.0, 0.5, 0.5), Vec3::X);
+    assert!(
+        bvh.raycast(&ray, 2.5, |index, max| boxes[index]
+            .intersect_ray(&ray, max))
+            .is_none()
+    );
+}
+
+#[test]
+fn query_aabb_and_nearest() {
+    let boxes = grid_boxes();
+    let bvh = Bvh::new(&boxes);
+
+    let query = Aabb::new(Vec3::splat(1.5), Vec3::splat(4.5));
+    let mut found = Vec::new();
+    bvh.query_aabb(&query, |index| found.push(index));
+    found.sort();
+    let expected: Vec<usize> = (0..boxes.len())
+        .filter(|&index| boxes[index].intersects(&query))
+        .collect();
+    assert_eq!(found, expected);
+    assert_eq!(found.len(), 8);
+
+    let point = Vec3::new(4.5, 6.5, 9.7);
+    let (index, distance) = bvh
+        .nearest(point, f32::INFINITY, |index| {
+            boxes[index].distance_to_point(point)
+        })
+        .unwrap();
+    assert_eq!(boxes[index].min, Vec3::new(4.0, 6.0, 10.0));
+    assert!((distance - 0.3).abs() < 1e-5);
+    assert!(
+        bvh.nearest(point, 0.1, |index| boxes[index].distance_to_point(point))
+            .is_none()
+    );
+}
+
+#[test]
+fn refit_follows_moved_primitives() {
+    let mut boxes = grid_boxes();
+    let mut bvh = Bvh::new(&boxes);
+
+    // Move the first box far away
+    boxes[0] = Aabb::new(Vec3::splat(50.0), Vec3::splat(51.0));
+    bvh.refit(&boxes);
+    assert_eq!(bvh.get_bounds().unwrap().max, Vec3::splat(51.0));
+
+    let ray = Ray::new(Vec3::splat(60.0), Vec3::NEG_ONE);
+    let hit = bvh.raycast(&ray, 100.0, |index, max| {
+        boxes[index].intersect_ray(&ray, max)
+    });
+    assert_eq!(hit.map(|(index, _)| index), Some(0));
+
+    let mut found = Vec::new();
+    bvh.query_aabb(&Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5)), |index| {
+        found.push(index)
+    });
+    assert!(found.is_empty());
+}
+
+#[test]
+fn mesh_queries() {
+    let sphere = Sphere::new(1.0, 16);
+    let mesh = sphere.get_mesh();
+
+    let hit = mesh
+        .raycast(&Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z), 100.0)
+        .unwrap();
+    assert!((hit.distance - 4.0).abs() < 0.05);
+    assert!((hit.barycentric.element_sum() - 1.0).abs() < 1e-5);
+    let triangle = &mesh.get_triangles()[hit.triangle];
+    let [a, b, c] = triangle.get_vertices();
+    let point = a * hit.barycentric.x + b * hit.barycentric.y + c * hit.barycentric.z;
+    assert!(point.abs_diff_eq(Vec3::new(0.0, 0.0, -5.0 + hit.distance), 1e-4));
+    assert!(
+        mesh.raycast(&Ray::new(Vec3::new(0.0, 3.0, -5.0), Vec3::Z), 100.0)
+            .is_none()
+    );
+
+    let (_, closest) = mesh.closest_point(Vec3::new(3.0, 0.0, 0.0)).unwrap();
+    assert!((closest.x - 1.0).abs() < 0.05);
+    assert!(closest.y.abs() < 0.1 && closest.z.abs() < 0.1);
+
+    let nearby = mesh.query_aabb(&Aabb::new(
+        Vec3::new(0.9, -0.1, -0.1),
+        Vec3::new(1.1, 0.1, 0.1),
+    ));
+    assert!(!nearby.is_empty());
+    assert!(nearby.len() < mesh.get_triangles().len() / 4);
+}
+
+#[test]
+fn scene_refits_after_update() {
+    let mut scene = Scene::new();
+    let mut cube = Cube::new(1.0);
+    cube.set_update(|object, delta_time| {
+        let position = *object.get_position();
+        object.set_position(position + Vec3::X * delta_time);
+    });
+    scene.add_object(cube);
+    let mut still = Cube::new(1.0);
+    still.set_position(Vec3::new(0.0, 5.0, 0.0));
+    scene.add_object(still);
+
+    let origin = Aabb::new(Vec3::splat(-0.1), Vec3::splat(0.1));
+    assert_eq!(scene.query_aabb(&origin), vec![0]);
+
+    scene.update(10.0);
+    assert!(scene.query_aabb(&origin).is_empty());
+    assert_eq!(
+        scene.query_aabb(&Aabb::new(
+            Vec3::new(9.9, -0.1, -0.1),
+            Vec3::new(10.1, 0.1, 0.1)
+        )),
+        vec![0]
+    );
+    assert_eq!(
+        scene.get_bvh().get_bounds().unwrap().max,
+        Vec3::new(10.5, 5.5, 0.5)
+    );
+}
+
+#[test]
+fn scene_refits_after_objects_are_moved_directly() {
+    let mut scene = Scene::new();
+    scene.add_object(Cube::new(1.0));
+    let mut still = Cube::new(1.0);
+    still.set_position(Vec3::new(0.0, 5.0, 0.0));
+    scene.add_object(still);
+    let origin = Aabb::new(Vec3::splat(-0.1), Vec3::splat(0.1));
+    assert_eq!(scene.query_aabb(&origin), vec![0]);
+
+    // No update in between
+    scene
+        .get_object_mut(0)
+        .set_position(Vec3::new(3.0, 0.0, 0.0));
+    assert!(scene.query_aabb(&origin).is_empty());
+    let ray = Ray::new(Vec3::new(3.0, 0.0, -5.0), Vec3::Z);
+    assert_eq!(scene.raycast(&ray, 10.0).unwrap().object, 0);
+    assert_eq!(
+        scene.get_bvh().get_bounds().unwrap().max,
+        Vec3::new(3.5, 5.5, 0.5)
+    );
+}
@@ -0,0 +1,220 @@
+use glam::Vec3;
+use three_d::core::object::Object;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::character_controller::CharacterController;
+use three_d::physics::collider::Collider;
+use three_d::physics::world::PhysicsWorld;
+
+const DT: f32 = 1.0 / 60.0;
+// Capsule 1.8 tall, so it stands with its center 0.9 above the ground
+const STANDING: f32 = 0.9;
+
+// Static box
+fn block(center: Vec3, half_extents: Vec3) -> Object {
+    let mut block = Cube::new(1.0);
+    block.set_position(center);
+    block.set_collider(Some(Collider::cuboid(half_extents)));
+    block
+}
+
+fn floor() -> Object {
+    block(Vec3::new(0.0, -0.5, 0.0), Vec3::new(20.0, 0.5, 20.0))
+}
+
+// Ramp rising toward +x at `angle`, its surface passing through the origin
+fn ramp(angle: f32) -> Object {
+    let half_extents = Vec3::new(10.0, 0.5, 5.0);
+    let normal = Vec3::new(-angle.sin(), angle.cos(), 0.0);
+    let mut ramp = block(-normal * 0.5, half_extents);
+    ramp.set_rotation(Vec3::new(0.0, 0.0, angle));
+    ramp
+}
+
+fn character(position: Vec3) -> Object {
+    let mut character = Cube::new(1.0);
+    character.set_position(position);
+    character.set_character_controller(Some(CharacterController::new(0.3, 0.6)));
+    character
+}
+
+fn controller(object: &mut Object) -> &mut CharacterController {
+    object.get_character_controller_mut().unwrap()
+}
+
+fn run(world: &mut PhysicsWorld, objects: &mut [Object], steps: usize) {
+    for _ in 0..steps {
+        world.step_fixed(objects, DT);
+    }
+}
+
+#[test]
+fn falls_and_lands_on_the_ground() {
+    let mut objects = vec![floor(), character(Vec3::new(0.0, 3.0, 0.0))];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 10);
+    assert!(
+        !objects[1]
+            .get_character_controller()
+            .unwrap()
+            .get_grounded()
+    );
+    assert!(
+        objects[1]
+            .get_character_controller()
+            .unwrap()
+            .get_velocity()
+            .y
+            < -1.0
+    );
+
+    run(&mut world, &mut objects, 110);
+    let landed = &objects[1];
+    assert!(landed.get_character_controller().unwrap().get_grounded());
+    assert!(
+        (landed.get_position().y - STANDING).abs() < 0.02,
+        "{}",
+        landed.get_position()
+    );
+    // and stays there
+    run(&mut world, &mut objects, 60);
+    assert!((objects[1].get_position().y - STANDING).abs() < 0.02);
+}
+
+#[test]
+fn slides_along_walls() {
+    let wall = block(Vec3::new(2.5, 1.0, 0.0), Vec3::new(0.5, 1.0, 10.0));
+    let mut objects = vec![floor(), wall, character(Vec3::new(0.0, STANDING, 0.0))];
+    let mut world = PhysicsWorld::new();
+    controller(&mut objects[2]).set_walk_velocity(Vec3::new(3.0, 0.0, 1.0));
+    run(&mut world, &mut objects, 120);
+
+    let position = *objects[2].get_position();
+    // Stopped by the wall's face at x = 2, but kept going along it
+    assert!(
+        position.x < 2.0 - 0.3 + 0.02 && position.x > 2.0 - 0.3 - 0.05,
+        "{position}"
+    );
+    assert!((position.z - 2.0).abs() < 0.1, "{position}");
+    assert!((position.y - STANDING).abs() < 0.02, "{position}");
+}
+
+#[test]
+fn steps_up_ledges_but_not_walls() {
+    let step = block(Vec3::new(2.5, 0.125, 0.0), Vec3::new(1.5, 0.125, 10.0));
+    let ledge = block(Vec3::new(5.0, 0.25 + 0.3, 0.0), Vec3::new(1.0, 0.3, 10.0));
+    let mut objects = vec![
+        floor(),
+        step,
+        ledge,
+        character(Vec3::new(0.0, STANDING, 0.0)),
+    ];
+    let mut world = PhysicsWorld::new();
+    controller(&mut objects[3]).set_walk_velocity(Vec3::new(2.0, 0.0, 0.0));
+    run(&mut world, &mut objects, 60);
+
+    // Up onto the 0.25 step...
+    let position = *objects[3].get_position();
+    assert!((position.x - 2.0).abs() < 0.1, "{position}");
+    assert!((position.y - STANDING - 0.25).abs() < 0.02, "{position}");
+
+    // ...but the 0.6 ledge after it is too tall
+    run(&mut world, &mut objects, 120);
+    let position = *objects[3].get_position();
+    assert!((position.x - (4.0 - 0.3)).abs() < 0.05, "{position}");
+    assert!((position.y - STANDING - 0.25).abs() < 0.02, "{position}");
+    assert!(
+        objects[3]
+            .get_character_controller()
+            .unwrap()
+            .get_grounded()
+    );
+}
+
+#[test]
+fn climbs_gentle_slopes_but_not_steep_ones() {
+    let mut gentle = vec![
+        ramp(25f32.to_radians()),
+        character(Vec3::new(-2.0, 2.0, 0.0)),
+    ];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut gentle, 60);
+    let start = *gentle[1].get_position();
+    assert!(gentle[1].get_character_controller().unwrap().get_grounded());
+    // Standing still, it doesn't creep downhill
+    run(&mut world, &mut gentle, 60);
+    assert!(gentle[1].get_position().distance(start) < 0.01);
+
+    controller(&mut gentle[1]).set_walk_velocity(Vec3::new(2.0, 0.0, 0.0));
+    run(&mut world, &mut gentle, 60);
+    let climbed = *gentle[1].get_position() - start;
+    assert!(climbed.x > 1.9 && (climbed.y - climbed.x * 25f32.to_radians().tan()).abs() < 0.05);
+    assert!(gentle[1].get_character_controller().unwrap().get_grounded());
+    let normal = gentle[1]
+        .get_character_controller()
+        .unwrap()
+        .get_ground_normal();
+    assert!((normal.y - 25f32.to_radians().cos()).abs() < 0.01);
+
+    let mut steep = vec![
+        floor(),
+        ramp(60f32.to_radians()),
+        character(Vec3::new(-1.0, STANDING, 0.0)),
+    ];
+    controller(&mut steep[2]).set_walk_velocity(Vec3::new(2.0, 0.0, 0.0));
+    run(&mut world, &mut steep, 120);
+    // Pressed against the slope's foot without riding up it
+    let position = *steep[2].get_position();
+    assert!(position.y < STANDING + 0.35, "{position}");
+    assert!(position.x < 0.5, "{position}");
+}
+
+#[test]
+fn jumps_only_from_the_ground() {
+    let mut objects = vec![floor(), character(Vec3::new(0.0, STANDING, 0.0))];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 2);
+    controller(&mut objects[1]).jump();
+
+    let mut highest = 0.0f32;
+    for step in 0..120 {
+        world.step_fixed(&mut objects, DT);
+        highest = highest.max(objects[1].get_position().y);
+        if step == 20 {
+            // Jumping again in the air does nothing
+            assert!(
+                !objects[1]
+                    .get_character_controller()
+                    .unwrap()
+                    .get_grounded()
+            );
+            controller(&mut objects[1]).jump();
+        }
+    }
+    // v^2 / 2g above where it stood
+    let expected = STANDING + 5.0 * 5.0 / (2.0 * 9.81);
+    assert!((highest - expected).abs() < 0.1, "{highest}");
+    assert!(
+        objects[1]
+            .get_character_controller()
+            .unwrap()
+            .get_grounded()
+    );
+    assert!((objects[1].get_position().y - STANDING).abs() < 0.02);
+}
+
+#[test]
+fn walks_on_triangle_meshes() {
+    let mut ground = Cube::new(20.0);
+    ground.set_position(Vec3::new(0.0, -10.0, 0.0));
+    let mesh = ground.get_mesh().clone();
+    ground.set_collider(Some(Collider::triangle_mesh(&mesh)));
+    let mut objects = vec![ground, character(Vec3::new(0.0, 2.0, 0.0))];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 60);
+    controller(&mut objects[1]).set_walk_velocity(Vec3::new(0.0, 0.0, -3.0));
+    run(&mut world, &mut objects, 60);
+
+    let position = *objects[1].get_position();
+    assert!((position.z + 3.0).abs() < 0.1, "{position}");
+    assert!((position.y - STANDING).abs() < 0.02, "{position}");
+}
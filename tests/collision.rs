@@ -0,0 +1,273 @@
+use glam::{Mat3, Vec3};
+use three_d::core::object::Object;
+use three_d::geometry::mesh::Mesh;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::collider::{Collider, Pose};
+use three_d::physics::contact::{collide, intersects};
+use three_d::physics::convex_hull::ConvexHull;
+use three_d::physics::mass_properties::MassProperties;
+use three_d::physics::rigid_body::RigidBody;
+use three_d::physics::world::PhysicsWorld;
+
+fn assert_close(a: Vec3, b: Vec3, tolerance: f32) {
+    assert!((a - b).length() <= tolerance, "{a} != {b}");
+}
+
+fn at(x: f32, y: f32, z: f32) -> Pose {
+    Pose::from_position(Vec3::new(x, y, z))
+}
+
+#[test]
+fn spheres() {
+    let sphere = Collider::sphere(1.0);
+    let contacts = collide(&sphere, &at(0.0, 0.0, 0.0), &sphere, &at(1.5, 0.0, 0.0));
+    assert_eq!(contacts.len(), 1);
+    let contact = &contacts[0];
+    assert_close(contact.normal, Vec3::X, 1e-4);
+    assert!((contact.depth - 0.5).abs() < 1e-4);
+    assert_eq!(contact.points.len(), 1);
+    assert_close(contact.points[0].point, Vec3::new(0.75, 0.0, 0.0), 1e-4);
+
+    assert!(collide(&sphere, &at(0.0, 0.0, 0.0), &sphere, &at(2.1, 0.0, 0.0)).is_empty());
+    assert!(intersects(
+        &sphere,
+        &at(0.0, 0.0, 0.0),
+        &sphere,
+        &at(1.9, 0.0, 0.0)
+    ));
+    assert!(!intersects(
+        &sphere,
+        &at(0.0, 0.0, 0.0),
+        &sphere,
+        &at(0.0, 2.1, 0.0)
+    ));
+}
+
+#[test]
+fn box_resting_on_box_has_four_points() {
+    let ground = Collider::cuboid(Vec3::new(5.0, 0.5, 5.0));
+    let crate_box = Collider::cuboid(Vec3::splat(0.5));
+    let contacts = collide(&ground, &at(0.0, 0.0, 0.0), &crate_box, &at(0.3, 0.9, 0.0));
+    assert_eq!(contacts.len(), 1);
+    let contact = &contacts[0];
+    assert_close(contact.normal, Vec3::Y, 1e-3);
+    assert!((contact.depth - 0.1).abs() < 1e-3, "{}", contact.depth);
+    assert_eq!(contact.points.len(), 4);
+    for point in &contact.points {
+        assert!((point.depth - 0.1).abs() < 1e-3);
+        assert!((point.point.y - 0.45).abs() < 1e-3);
+        assert!((point.point.x - 0.3).abs() <= 0.5 + 1e-3);
+    }
+}
+
+#[test]
+fn tilted_box_touches_on_an_edge() {
+    let ground = Collider::cuboid(Vec3::new(5.0, 0.5, 5.0));
+    let crate_box = Collider::cuboid(Vec3::splat(0.5));
+    // Turned 45 degrees about z, its lowest edge runs along z at y = 1 - 0.5√2
+    let pose = Pose::new(
+        Vec3::new(0.0, 1.0, 0.0),
+        Mat3::from_rotation_z(std::f32::consts::FRAC_PI_4),
+    );
+    let contacts = collide(&ground, &at(0.0, 0.0, 0.0), &crate_box, &pose);
+    assert_eq!(contacts.len(), 1);
+    let contact = &contacts[0];
+    assert_close(contact.normal, Vec3::Y, 1e-3);
+    let depth = 0.5 - (1.0 - 0.5 * 2f32.sqrt());
+    assert!((contact.depth - depth).abs() < 1e-3, "{}", contact.depth);
+    assert_eq!(contact.points.len(), 2);
+    for point in &contact.points {
+        assert!(point.point.x.abs() < 1e-3);
+        assert!((point.point.z.abs() - 0.5).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn capsule_lying_on_box_touches_along_its_side() {
+    let ground = Collider::cuboid(Vec3::new(5.0, 0.5, 5.0));
+    let capsule = Collider::capsule(0.25, 1.0);
+    // Lying along x
+    let pose = Pose::new(
+        Vec3::new(0.0, 0.7, 0.0),
+        Mat3::from_rotation_z(std::f32::consts::FRAC_PI_2),
+    );
+    let contacts = collide(&ground, &at(0.0, 0.0, 0.0), &capsule, &pose);
+    assert_eq!(contacts.len(), 1);
+    let contact = &contacts[0];
+    assert_close(contact.normal, Vec3::Y, 1e-3);
+    assert!((contact.depth - 0.05).abs() < 1e-3);
+    let mut xs: Vec<f32> = contact.points.iter().map(|point| point.point.x).collect();
+    xs.sort_by(f32::total_cmp);
+    assert_eq!(xs.len(), 2);
+    assert!(
+        (xs[0] + 1.0).abs() < 1e-3 && (xs[1] - 1.0).abs() < 1e-3,
+        "{xs:?}"
+    );
+
+    // Standing up, it touches at the bottom of its lower cap
+    let contacts = collide(&ground, &at(0.0, 0.0, 0.0), &capsule, &at(0.0, 1.7, 0.0));
+    assert_eq!(contacts[0].points.len(), 1);
+    assert_close(
+        contacts[0].points[0].point,
+        Vec3::new(0.0, 0.475, 0.0),
+        1e-3,
+    );
+}
+
+#[test]
+fn deep_overlap_pushes_out_the_short_way() {
+    let wall = Collider::cuboid(Vec3::new(1.0, 2.0, 2.0));
+    let sphere = Collider::sphere(0.5);
+    // Center inside the box, 0.2 from its +x face
+    let contacts = collide(&wall, &at(0.0, 0.0, 0.0), &sphere, &at(0.8, 0.3, 0.0));
+    assert_eq!(contacts.len(), 1);
+    assert_close(contacts[0].normal, Vec3::X, 1e-3);
+    assert!(
+        (contacts[0].depth - 0.7).abs() < 1e-3,
+        "{}",
+        contacts[0].depth
+    );
+}
+
+#[test]
+fn convex_hull_of_cube_acts_like_box() {
+    let cube = Cube::new(1.0);
+    let hull = ConvexHull::from_mesh(cube.get_mesh()).unwrap();
+    assert_eq!(hull.get_vertices().len(), 8);
+    assert_eq!(hull.get_faces().len(), 6);
+    assert!(hull.get_faces().iter().all(|face| face.vertices.len() == 4));
+    assert!(hull.contains_point(Vec3::splat(0.49)));
+    assert!(!hull.contains_point(Vec3::new(0.51, 0.0, 0.0)));
+
+    let hull_collider = Collider::convex_hull(cube.get_mesh()).unwrap();
+    let box_collider = Collider::cuboid(Vec3::splat(0.5));
+    let ground = Collider::cuboid(Vec3::new(5.0, 0.5, 5.0));
+    let pose = Pose::new(Vec3::new(0.2, 0.95, 0.1), Mat3::from_rotation_y(0.3));
+    let from_hull = collide(&ground, &at(0.0, 0.0, 0.0), &hull_collider, &pose);
+    let from_box = collide(&ground, &at(0.0, 0.0, 0.0), &box_collider, &pose);
+    assert_eq!(from_hull.len(), 1);
+    assert_close(from_hull[0].normal, from_box[0].normal, 1e-3);
+    assert!((from_hull[0].depth - from_box[0].depth).abs() < 1e-3);
+    assert_eq!(from_hull[0].points.len(), from_box[0].points.len());
+
+    let mass = hull_collider.get_mass_properties(2.0);
+    let expected = MassProperties::from_box(Vec3::splat(0.5), 2.0);
+    assert!(mass.inertia.abs_diff_eq(expected.inertia, 1e-4));
+
+    // Flat points have no hull
+    let quad = [Vec3::ZERO, Vec3::X, Vec3::Z, Vec3::new(1.0, 0.0, 1.0)];
+    assert!(ConvexHull::new(&quad).is_none());
+}
+
+#[test]
+fn triangle_mesh_against_convex_shapes() {
+    // A 4x4 floor of two triangles at y = 0
+    let floor = Mesh::from_raw_coordinates(vec![
+        [-2.0, 0.0, -2.0, -2.0, 0.0, 2.0, 2.0, 0.0, 2.0],
+        [-2.0, 0.0, -2.0, 2.0, 0.0, 2.0, 2.0, 0.0, -2.0],
+    ]);
+    let floor = Collider::triangle_mesh(&floor);
+    let raised = at(0.0, 1.0, 0.0);
+
+    let sphere = Collider::sphere(0.5);
+    let contacts = collide(&sphere, &at(1.0, 1.4, 0.5), &floor, &raised);
+    assert_eq!(contacts.len(), 1);
+    assert_close(contacts[0].normal, -Vec3::Y, 1e-3);
+    assert!((contacts[0].depth - 0.1).abs() < 1e-3);
+
+    // A box over the diagonal touches both triangles
+    let crate_box = Collider::cuboid(Vec3::splat(0.5));
+    let contacts = collide(&floor, &raised, &crate_box, &at(0.0, 1.45, 0.0));
+    assert_eq!(contacts.len(), 2);
+    for contact in &contacts {
+        assert_close(contact.normal, Vec3::Y, 1e-3);
+        assert!((contact.depth - 0.05).abs() < 1e-3);
+    }
+    assert!(intersects(&floor, &raised, &crate_box, &at(0.0, 1.45, 0.0)));
+    assert!(!intersects(&floor, &raised, &crate_box, &at(0.0, 1.6, 0.0)));
+    assert!(!intersects(
+        &floor,
+        &raised,
+        &crate_box,
+        &at(3.0, 1.45, 0.0)
+    ));
+    assert!(collide(&floor, &raised, &floor, &raised).is_empty());
+}
+
+#[test]
+fn box_comes_to_rest_on_ground() {
+    let mut ground = Cube::new(1.0);
+    ground.set_collider(Some(Collider::cuboid(Vec3::new(10.0, 0.5, 10.0))));
+    let mut falling = Cube::new(1.0);
+    falling.set_position(Vec3::new(0.0, 3.0, 0.0));
+    falling.set_rotation(Vec3::new(0.0, 0.4, 0.0));
+    falling.set_collider(Some(Collider::cuboid(Vec3::splat(0.5))));
+    falling.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+    let mut objects: Vec<Object> = vec![ground, falling];
+
+    let mut world = PhysicsWorld::new();
+    for _ in 0..240 {
+        world.step_fixed(&mut objects, 1.0 / 60.0);
+    }
+    let resting = &objects[1];
+    assert!(
+        (resting.get_position().y - 1.0).abs() < 0.02,
+        "{}",
+        resting.get_position()
+    );
+    assert!(resting.get_position().x.abs() < 0.01);
+    assert!((resting.get_rotation().y - 0.4).abs() < 0.01);
+    let body = resting.get_rigid_body().unwrap();
+    assert!(body.get_linear_velocity().length() < 0.05);
+    assert!(body.get_angular_velocity().length() < 0.05);
+}
+
+#[test]
+fn bouncy_sphere_rebounds() {
+    let mut ground = Cube::new(1.0);
+    let mut ground_collider = Collider::cuboid(Vec3::new(10.0, 0.5, 10.0));
+    ground_collider.set_restitution(0.8);
+    ground.set_collider(Some(ground_collider));
+    let mut ball = Cube::new(1.0);
+    ball.set_position(Vec3::new(0.0, 3.0, 0.0));
+    ball.set_collider(Some(Collider::sphere(0.5)));
+    ball.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+    let mut objects = vec![ground, ball];
+
+    let mut world = PhysicsWorld::new();
+    let mut lowest = f32::INFINITY;
+    let mut rebounded = false;
+    for _ in 0..120 {
+        world.step_fixed(&mut objects, 1.0 / 60.0);
+        let y = objects[1].get_position().y;
+        lowest = lowest.min(y);
+        rebounded |= objects[1].get_rigid_body().unwrap().get_linear_velocity().y > 3.0;
+    }
+    assert!(rebounded);
+    // It never sinks far into the ground
+    assert!(lowest > 0.95, "{lowest}");
+}
+
+#[test]
+fn stack_stays_standing() {
+    let mut ground = Cube::new(1.0);
+    ground.set_collider(Some(Collider::cuboid(Vec3::new(10.0, 0.5, 10.0))));
+    let mut objects = vec![ground];
+    for level in 0..5 {
+        let mut crate_box = Cube::new(1.0);
+        crate_box.set_position(Vec3::new(0.0, 1.0 + level as f32, 0.0));
+        crate_box.set_collider(Some(Collider::cuboid(Vec3::splat(0.5))));
+        crate_box.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+        objects.push(crate_box);
+    }
+
+    let mut world = PhysicsWorld::new();
+    for _ in 0..600 {
+        world.step_fixed(&mut objects, 1.0 / 60.0);
+    }
+    for (level, crate_box) in objects[1..].iter().enumerate() {
+        let expected = Vec3::new(0.0, 1.0 + level as f32, 0.0);
+        assert_close(*crate_box.get_position(), expected, 0.02);
+        assert!(crate_box.get_rotation().length() < 0.01);
+    }
+}
@@ -0,0 +1,98 @@
+use glam::Vec3;
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::light::DirectionalLight;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::geometry::bounds::{Aabb, BoundingSphere};
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::rigid_body::RigidBody;
+
+#[test]
+fn frustum_planes() {
+    let frustum = Camera::new().get_frustum();
+    assert!(frustum.contains_point(Vec3::new(0.0, 0.0, 5.0)));
+    assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 0.5))); // in front of the near plane
+    assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 11.0))); // beyond the far plane
+    assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -5.0)));
+    assert!(!frustum.contains_point(Vec3::new(20.0, 0.0, 5.0)));
+
+    // Boxes and spheres straddling a plane count as visible
+    assert!(frustum.intersects_aabb(&Aabb::new(
+        Vec3::new(-1.0, -1.0, 9.5),
+        Vec3::new(1.0, 1.0, 10.5)
+    )));
+    assert!(!frustum.intersects_aabb(&Aabb::new(
+        Vec3::new(-1.0, -1.0, -3.0),
+        Vec3::new(1.0, 1.0, -1.0)
+    )));
+    assert!(frustum.intersects_sphere(&BoundingSphere::new(Vec3::new(0.0, 0.0, 0.5), 0.6)));
+    assert!(!frustum.intersects_sphere(&BoundingSphere::new(Vec3::new(0.0, 0.0, -2.0), 0.6)));
+}
+
+#[test]
+fn objects_outside_the_view_are_culled() {
+    let mut scene = Scene::new();
+    scene.add_light(DirectionalLight::new(Vec3::new(1.0, -1.0, 1.0)));
+    let positions = [
+        Vec3::new(0.0, 0.0, 3.0),  // in view
+        Vec3::new(0.0, 0.0, -3.0), // behind the camera
+        Vec3::new(30.0, 0.0, 3.0), // far off to the side
+        Vec3::new(0.0, 0.0, 30.0), // beyond the far plane
+        Vec3::new(2.5, 0.0, 2.0),  // partly in view at the right edge
+    ];
+    for position in positions {
+        let mut cube = Cube::new(1.0);
+        cube.set_position(position);
+        scene.add_object(cube);
+    }
+
+    let mut renderer = SoftwareRenderer::new(160, 120);
+    let image = renderer.render(&scene, &Camera::new());
+    let stats = renderer.get_stats();
+    assert_eq!(stats.drawn_objects, 2);
+    assert_eq!(stats.culled_objects, 3);
+    assert!(stats.drawn_triangles > 0);
+
+    // Culling must not change the picture
+    let mut visible = Scene::new();
+    visible.add_light(DirectionalLight::new(Vec3::new(1.0, -1.0, 1.0)));
+    for position in [positions[0], positions[4]] {
+        let mut cube = Cube::new(1.0);
+        cube.set_position(position);
+        visible.add_object(cube);
+    }
+    assert_eq!(image, renderer.render(&visible, &Camera::new()));
+}
+
+#[test]
+fn objects_are_culled_where_they_are_drawn() {
+    // In one fixed step the cube leaves the view to the right, but a quarter of a step past
+    // it, it is drawn three quarters of the way back, still partly in view
+    let mut scene = Scene::new();
+    scene.add_light(DirectionalLight::new(Vec3::new(1.0, -1.0, 1.0)));
+    scene.get_physics_mut().set_gravity(Vec3::ZERO);
+    let mut cube = Cube::new(1.0);
+    cube.set_position(Vec3::new(3.0, 0.0, 3.0));
+    let mut body = RigidBody::dynamic(1.0);
+    body.set_linear_velocity(Vec3::new(180.0, 0.0, 0.0));
+    cube.set_rigid_body(Some(body));
+    scene.add_object(cube);
+    let step = scene.get_physics().get_fixed_timestep();
+    scene.update(step * 1.25);
+
+    let frustum = Camera::new().get_frustum();
+    let cube = &scene.get_objects()[0];
+    assert!(!frustum.intersects_aabb(&cube.get_world_aabb()));
+    assert!(frustum.intersects_aabb(&cube.get_interpolated_world_aabb(0.25)));
+
+    let mut software = SoftwareRenderer::new(160, 120);
+    let image = software.render(&scene, &Camera::new());
+    assert_eq!(software.get_stats().drawn_objects, 1);
+    assert!(image.pixels().any(|pixel| pixel.0[..3] != [0, 0, 0]));
+    let mut gpu = pollster::block_on(HeadlessRenderer::new(160, 120));
+    let image = gpu.render(&scene, &Camera::new());
+    assert_eq!(gpu.get_stats().drawn_objects, 1);
+    assert!(image.pixels().any(|pixel| pixel.0[..3] != [0, 0, 0]));
+}
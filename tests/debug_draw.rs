@@ -0,0 +1,150 @@
+use glam::Vec3;
+use image::RgbaImage;
+use three_d::core::camera::Camera;
+use three_d::core::golden::{GoldenSettings, assert_golden};
+use three_d::core::light::{DirectionalLight, PointLight};
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::geometry::primitives::cube::Cube;
+
+#[test]
+fn debug_shapes() {
+    let mut scene = Scene::new();
+    let mut light = DirectionalLight::new(Vec3::new(1.0, -1.0, 1.0));
+    light.set_intensity(2.0);
+    let mut point_light = PointLight::new(Vec3::new(1.2, 0.8, 3.0), 6.0);
+    point_light.set_color(Vec3::new(1.0, 0.7, 0.3));
+
+    let mut cube = Cube::new(0.8);
+    cube.set_position(Vec3::new(0.0, 0.0, 3.0));
+    cube.set_rotation(Vec3::new(0.3, 0.5, 0.0));
+
+    let debug_draw = scene.get_debug_draw_mut();
+    debug_draw.grid(
+        Vec3::new(0.0, -0.8, 3.5),
+        4.0,
+        8,
+        Vec3::new(0.3, 0.3, 0.3),
+        None,
+    );
+    debug_draw.aabb(
+        Vec3::new(-0.6, -0.6, 2.4),
+        Vec3::new(0.6, 0.6, 3.6),
+        Vec3::new(1.0, 1.0, 0.0),
+        None,
+    );
+    debug_draw.axes(cube.get_transform(), 0.7, None);
+    debug_draw.sphere(
+        Vec3::new(-1.3, 0.5, 3.0),
+        0.3,
+        Vec3::new(0.0, 1.0, 1.0),
+        Some(1.0),
+    );
+    debug_draw.arrow(
+        Vec3::new(-1.5, -0.5, 2.5),
+        Vec3::new(-0.8, -0.2, 2.5),
+        Vec3::new(1.0, 0.0, 1.0),
+        Some(1.0),
+    );
+    debug_draw.light(&point_light, None);
+    debug_draw.light(&light, None);
+
+    scene.add_light(light);
+    scene.add_light(point_light);
+    scene.add_object(cube);
+
+    let mut renderer = SoftwareRenderer::new(160, 120);
+    let reference = format!(
+        "{}/tests/golden/debug_shapes.png",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    assert_golden(
+        &mut renderer,
+        &scene,
+        &Camera::new(),
+        reference,
+        &GoldenSettings::new(),
+    );
+}
+
+#[test]
+fn lines_last_their_lifetime() {
+    let mut scene = Scene::new();
+    let debug_draw = scene.get_debug_draw_mut();
+    debug_draw.line(Vec3::ZERO, Vec3::new(0.0, 0.0, 3.0), Vec3::ONE, None);
+    debug_draw.sphere(Vec3::new(0.0, 0.0, 3.0), 0.5, Vec3::ONE, Some(1.0));
+    let mut renderer = SoftwareRenderer::new(160, 120);
+    let is_drawn = |image: &RgbaImage| image.pixels().any(|pixel| pixel.0[..3] != [0, 0, 0]);
+
+    // Single-frame shapes are gone after a frame, the others once their lifetime runs out
+    scene.update(0.5);
+    assert_eq!(scene.get_debug_draw().get_lines().len(), 3 * 24);
+    assert!(is_drawn(&renderer.render(&scene, &Camera::new())));
+    scene.get_debug_draw_mut().advance(0.6);
+    assert!(scene.get_debug_draw().get_lines().is_empty());
+    assert!(!is_drawn(&renderer.render(&scene, &Camera::new())));
+
+    let debug_draw = scene.get_debug_draw_mut();
+    debug_draw.line(Vec3::ZERO, Vec3::new(0.0, 0.0, 3.0), Vec3::ONE, Some(10.0));
+    debug_draw.clear();
+    assert!(!is_drawn(&renderer.render(&scene, &Camera::new())));
+}
+
+#[test]
+fn every_scene_has_its_own_lines() {
+    let mut drawn = Scene::new();
+    drawn.get_debug_draw_mut().aabb(
+        Vec3::new(-0.5, -0.5, 2.5),
+        Vec3::new(0.5, 0.5, 3.5),
+        Vec3::ONE,
+        None,
+    );
+    let empty = Scene::new();
+    assert_eq!(drawn.get_debug_draw().get_lines().len(), 12);
+    assert!(empty.get_debug_draw().get_lines().is_empty());
+
+    let mut renderer = SoftwareRenderer::new(160, 120);
+    let image = renderer.render(&empty, &Camera::new());
+    assert!(image.pixels().all(|pixel| pixel.0[..3] == [0, 0, 0]));
+}
+
+#[test]
+fn update_callbacks_draw_into_the_scene() {
+    let mut scene = Scene::new();
+    let mut cube = Cube::new(1.0);
+    cube.set_position(Vec3::new(0.0, 0.0, 3.0));
+    // Axes every frame, and a line lasting a second from the first frame only
+    let mut first_frame = true;
+    cube.set_update(move |object, _| {
+        let transform = object.get_transform();
+        let debug_draw = object.get_debug_draw_mut();
+        debug_draw.axes(transform, 1.0, None);
+        if first_frame {
+            debug_draw.line(Vec3::ZERO, Vec3::X, Vec3::ONE, Some(1.0));
+            first_frame = false;
+        }
+    });
+    scene.add_object(cube);
+
+    // Shown the frame they are drawn, and moved out of the object
+    scene.update(0.5);
+    let lines = scene.get_debug_draw().get_lines();
+    assert_eq!(lines.len(), 4);
+    assert!(lines.contains(&(Vec3::new(0.0, 0.0, 3.0), Vec3::new(1.0, 0.0, 3.0), Vec3::X)));
+    assert!(
+        scene.get_objects()[0]
+            .get_debug_draw()
+            .get_lines()
+            .is_empty()
+    );
+    let mut renderer = SoftwareRenderer::new(160, 120);
+    let image = renderer.render(&scene, &Camera::new());
+    assert!(image.pixels().any(|pixel| pixel.0[..3] != [0, 0, 0]));
+
+    // The single-frame axes are replaced each frame, and the line expires after its second
+    scene.update(0.4);
+    assert_eq!(scene.get_debug_draw().get_lines().len(), 4);
+    scene.update(0.7);
+    assert_eq!(scene.get_debug_draw().get_lines().len(), 3);
+}
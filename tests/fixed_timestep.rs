@@ -0,0 +1,184 @@
+use glam::Vec3;
+use std::cell::Cell;
+use std::rc::Rc;
+use three_d::core::camera::Camera;
+use three_d::core::light::DirectionalLight;
+use three_d::core::object::Object;
+use three_d::core::path_tracer::PathTracer;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::render_mode::RenderMode;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::rigid_body::RigidBody;
+
+const STEP: f32 = 1.0 / 60.0;
+
+// Object counting its fixed_update and update calls, and the timesteps fixed_update got
+fn counter() -> (Object, Rc<Cell<u32>>, Rc<Cell<u32>>) {
+    let (fixed, frames) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+    let mut object = Cube::new(1.0);
+    let fixed_count = fixed.clone();
+    object.set_fixed_update(move |_, fixed_timestep| {
+        assert_eq!(fixed_timestep, STEP);
+        fixed_count.set(fixed_count.get() + 1);
+    });
+    let frame_count = frames.clone();
+    object.set_update(move |_, _| frame_count.set(frame_count.get() + 1));
+    (object, fixed, frames)
+}
+
+fn falling_body() -> Object {
+    let mut body = Cube::new(1.0);
+    body.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+    body
+}
+
+#[test]
+fn fixed_update_keeps_its_rate_at_any_frame_rate() {
+    for frame_rate in [30, 60, 144] {
+        let mut scene = Scene::new();
+        let (object, fixed, frames) = counter();
+        scene.add_object(object);
+        for _ in 0..frame_rate * 2 {
+            scene.update(1.0 / frame_rate as f32);
+        }
+        assert_eq!(frames.get(), frame_rate * 2);
+        // Two seconds' worth, give or take the time still in the accumulator
+        assert!((119..=120).contains(&fixed.get()), "{}", fixed.get());
+    }
+}
+
+#[test]
+fn long_frames_catch_up_only_so_far() {
+    let mut scene = Scene::new();
+    scene.get_physics_mut().set_max_substeps(5);
+    let (object, fixed, frames) = counter();
+    scene.add_object(object);
+
+    scene.update(3.0);
+    assert_eq!(fixed.get(), 5);
+    assert_eq!(frames.get(), 1);
+    // The rest of the stall is dropped instead of running over the next frames
+    scene.update(STEP * 0.5);
+    assert!(fixed.get() <= 6, "{}", fixed.get());
+    scene.update(STEP);
+    assert!(fixed.get() <= 7, "{}", fixed.get());
+}
+
+#[test]
+fn physics_runs_the_same_at_any_frame_rate() {
+    let fall = |frame_rate: u32| {
+        let mut scene = Scene::new();
+        scene.add_object(falling_body());
+        let (object, fixed, _) = counter();
+        scene.add_object(object);
+        // A little past a second, so rounding can't leave a step behind
+        for _ in 0..frame_rate + frame_rate / 10 {
+            scene.update(1.0 / frame_rate as f32);
+        }
+        // The last of the steps may still be waiting, so run to the same count
+        while fixed.get() < 66 {
+            scene.update(STEP * 0.5);
+        }
+        assert_eq!(fixed.get(), 66);
+        *scene.get_objects()[0].get_position()
+    };
+    let slow = fall(20);
+    assert_eq!(slow, fall(60));
+    assert_eq!(slow, fall(165));
+}
+
+#[test]
+fn alpha_is_the_share_of_a_step_left_over() {
+    let mut scene = Scene::new();
+    scene.get_physics_mut().set_fixed_timestep(0.1);
+    assert_eq!(scene.get_physics().get_fixed_timestep(), 0.1);
+    scene.update(0.25);
+    assert!((scene.get_interpolation_alpha() - 0.5).abs() < 1e-4);
+    scene.update(0.03);
+    assert!((scene.get_interpolation_alpha() - 0.8).abs() < 1e-4);
+    scene.update(0.05);
+    assert!((scene.get_interpolation_alpha() - 0.3).abs() < 1e-4);
+}
+
+#[test]
+fn objects_are_drawn_between_their_last_two_steps() {
+    let mut scene = Scene::new();
+    scene.get_physics_mut().set_gravity(Vec3::ZERO);
+    let mut body = falling_body();
+    let body_settings = body.get_rigid_body_mut().unwrap();
+    body_settings.set_linear_velocity(Vec3::new(6.0, 0.0, 0.0));
+    body_settings.set_angular_velocity(Vec3::new(0.0, 3.0, 0.0));
+    scene.add_object(body);
+    // Moved by its update every frame instead of by fixed steps
+    let mut mover = Cube::new(1.0);
+    mover.set_update(|object, delta_time| {
+        let position = *object.get_position();
+        object.set_position(position + Vec3::new(0.0, delta_time, 0.0));
+    });
+    scene.add_object(mover);
+
+    scene.update(STEP * 2.25);
+    let alpha = scene.get_interpolation_alpha();
+    assert!((alpha - 0.25).abs() < 1e-3);
+
+    // A quarter of the way through the second step: 6 * 1.25 / 60 along, turned 3 * 1.25 / 60
+    let body = &scene.get_objects()[0];
+    assert!((body.get_position().x - 6.0 * 2.0 * STEP).abs() < 1e-4);
+    let (position, rotation) = body.get_interpolated_pose(alpha);
+    assert!((position.x - 6.0 * 1.25 * STEP).abs() < 1e-3, "{position}");
+    let forward = rotation * Vec3::X;
+    let angle = (-forward.z).atan2(forward.x);
+    assert!((angle - 3.0 * 1.25 * STEP).abs() < 1e-3, "{angle}");
+    let transform = body.get_interpolated_transform(alpha);
+    assert!(transform.transform_point3(Vec3::ZERO).distance(position) < 1e-5);
+
+    // Only the fixed steps' motion is drawn behind; per frame motion shows where it is
+    let mover = &scene.get_objects()[1];
+    assert_eq!(mover.get_interpolated_pose(alpha).0, *mover.get_position());
+    // At an alpha of 1, everything is drawn where it is
+    assert_eq!(body.get_interpolated_pose(1.0).0, *body.get_position());
+}
+
+#[test]
+fn cpu_renderers_draw_the_interpolated_pose() {
+    // A body sliding sideways, drawn a quarter of the way through its second step, against
+    // the same body standing where that puts it
+    let scene = |moving: bool| {
+        let mut scene = Scene::new();
+        scene.get_physics_mut().set_gravity(Vec3::ZERO);
+        scene.add_light(DirectionalLight::new(Vec3::new(0.3, -0.5, 1.0)));
+        let mut body = falling_body();
+        body.set_position(Vec3::new(-0.5, 0.0, 4.0));
+        if moving {
+            body.get_rigid_body_mut()
+                .unwrap()
+                .set_linear_velocity(Vec3::new(30.0, 0.0, 0.0));
+        } else {
+            body.set_position(Vec3::new(-0.5 + 30.0 * 1.25 * STEP, 0.0, 4.0));
+        }
+        scene.add_object(body);
+        scene.update(STEP * 2.25);
+        scene
+    };
+    let (moving, standing) = (scene(true), scene(false));
+    assert!((moving.get_interpolation_alpha() - 0.25).abs() < 1e-3);
+
+    let mut software = SoftwareRenderer::new(80, 60);
+    let drawn = software.render(&moving, &Camera::new());
+    assert_eq!(drawn, software.render(&standing, &Camera::new()));
+
+    let (mut moving, mut standing) = (moving, standing);
+    moving.set_render_mode(RenderMode::Wireframe);
+    standing.set_render_mode(RenderMode::Wireframe);
+    let drawn = software.render(&moving, &Camera::new());
+    assert_eq!(drawn, software.render(&standing, &Camera::new()));
+
+    let mut tracer = PathTracer::new(40, 30);
+    tracer.set_samples_per_pixel(1);
+    tracer.set_max_bounces(0);
+    let traced = tracer.render(&moving, &Camera::new());
+    tracer.reset();
+    assert_eq!(traced, tracer.render(&standing, &Camera::new()));
+}
@@ -0,0 +1,307 @@
+// Locks down the look of every primitive and lighting mode with the software renderer, which
+// gives the same pixels on every machine. Run with UPDATE_GOLDEN=1 to re-bless the references
+// in tests/golden after an intended change, and review the new images before committing.
+use glam::{Vec3, Vec4};
+use std::sync::Arc;
+use three_d::core::camera::Camera;
+use three_d::core::golden::{GoldenSettings, assert_golden};
+use three_d::core::light::{DirectionalLight, PointLight};
+use three_d::core::material::Material;
+use three_d::core::object::Object;
+use three_d::core::render_mode::RenderMode;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::core::texture::{FilterMode, Sampler, Texture};
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::cylinder::Cylinder;
+use three_d::geometry::primitives::plane::Plane;
+use three_d::geometry::primitives::pyramid::Pyramid;
+use three_d::geometry::primitives::rectangular_prism::RectangularPrism;
+use three_d::geometry::primitives::sphere::Sphere;
+use three_d::geometry::primitives::triangular_prism::TriangularPrism;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+
+fn check(name: &str, scene: &Scene) {
+    let mut renderer = SoftwareRenderer::new(WIDTH, HEIGHT);
+    let reference = format!("{}/tests/golden/{name}.png", env!("CARGO_MANIFEST_DIR"));
+    assert_golden(
+        &mut renderer,
+        scene,
+        &Camera::new(),
+        reference,
+        &GoldenSettings::new(),
+    );
+}
+
+fn add_lights(scene: &mut Scene) {
+    let mut key = DirectionalLight::new(Vec3::new(1.0, -1.0, 1.0));
+    key.set_intensity(2.0);
+    scene.add_light(key);
+    // A dim fill from the other side, so no visible face is completely black
+    let mut fill = DirectionalLight::new(Vec3::new(-1.0, 0.3, 0.2));
+    fill.set_intensity(0.4);
+    scene.add_light(fill);
+}
+
+// A single object in front of the camera, turned so three of its sides show
+fn single_object(mut object: Object, material: Material) -> Scene {
+    object.set_position(Vec3::new(0.0, 0.0, 2.2));
+    object.set_rotation(Vec3::new(0.5, 0.6, 0.0));
+    object.set_material(material);
+
+    let mut scene = Scene::new();
+    add_lights(&mut scene);
+    scene.add_object(object);
+    scene
+}
+
+fn checker_texture() -> Arc<Texture> {
+    let mut data = Vec::new();
+    for y in 0..8 {
+        for x in 0..8 {
+            let value = if (x + y) % 2 == 0 { 230 } else { 40 };
+            data.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+    let mut texture = Texture::from_rgba8(8, 8, data).unwrap();
+    texture.set_sampler(Sampler {
+        filter: FilterMode::Nearest,
+        ..Sampler::new()
+    });
+    Arc::new(texture)
+}
+
+#[test]
+fn cube() {
+    check("cube", &single_object(Cube::new(1.0), Material::new()));
+}
+
+#[test]
+fn rectangular_prism() {
+    check(
+        "rectangular_prism",
+        &single_object(RectangularPrism::new(1.4, 0.8, 0.6), Material::new()),
+    );
+}
+
+#[test]
+fn pyramid() {
+    check(
+        "pyramid",
+        &single_object(Pyramid::new(1.0, 1.2), Material::new()),
+    );
+}
+
+#[test]
+fn triangular_prism() {
+    check(
+        "triangular_prism",
+        &single_object(TriangularPrism::new(1.0, 1.0, 1.0), Material::new()),
+    );
+}
+
+#[test]
+fn sphere() {
+    check(
+        "sphere",
+        &single_object(Sphere::new(0.7, 24), Material::new()),
+    );
+}
+
+#[test]
+fn cylinder() {
+    check(
+        "cylinder",
+        &single_object(Cylinder::new(0.5, 1.2, 24), Material::new()),
+    );
+}
+
+#[test]
+fn plane() {
+    // Tipped towards the camera, so its top shows
+    let mut scene = single_object(Plane::new(1.4, 1.4, 4, 4), Material::new());
+    scene
+        .get_object_mut(0)
+        .set_rotation(Vec3::new(-0.9, 0.6, 0.0));
+    check("plane", &scene);
+}
+
+#[test]
+fn unlit() {
+    check(
+        "unlit",
+        &single_object(
+            Sphere::new(0.7, 24),
+            Material::unlit(Vec4::new(0.2, 0.6, 0.9, 1.0)),
+        ),
+    );
+}
+
+#[test]
+fn blinn_phong() {
+    check(
+        "blinn_phong",
+        &single_object(
+            Sphere::new(0.7, 24),
+            Material::blinn_phong(Vec4::new(0.8, 0.2, 0.2, 1.0), 0.6, 48.0),
+        ),
+    );
+}
+
+#[test]
+fn pbr_dielectric() {
+    check(
+        "pbr_dielectric",
+        &single_object(
+            Sphere::new(0.7, 24),
+            Material::pbr(Vec4::new(0.2, 0.7, 0.3, 1.0), 0.0, 0.4),
+        ),
+    );
+}
+
+#[test]
+fn pbr_metal() {
+    check(
+        "pbr_metal",
+        &single_object(
+            Sphere::new(0.7, 24),
+            Material::pbr(Vec4::new(0.95, 0.75, 0.35, 1.0), 1.0, 0.3),
+        ),
+    );
+}
+
+#[test]
+fn emissive() {
+    let mut material = Material::blinn_phong(Vec4::new(0.1, 0.1, 0.1, 1.0), 0.0, 32.0);
+    material.set_emissive(Vec3::new(0.9, 0.4, 0.1));
+    check("emissive", &single_object(Cube::new(1.0), material));
+}
+
+#[test]
+fn base_color_texture() {
+    let mut material = Material::new();
+    material.set_base_color_texture(Some(checker_texture()));
+    check(
+        "base_color_texture",
+        &single_object(Cube::new(1.0), material),
+    );
+}
+
+// Stripes whose normals lean alternately left and right, like a corrugated sheet
+fn ridged_normal_texture() -> Arc<Texture> {
+    let mut data = Vec::new();
+    for _ in 0..8 {
+        for x in 0..8 {
+            let lean = if x % 2 == 0 { 0.6f32 } else { -0.6 };
+            let normal = Vec3::new(lean, 0.0, 1.0).normalize();
+            let encoded = (normal * 0.5 + 0.5) * 255.0;
+            data.extend_from_slice(&[encoded.x as u8, encoded.y as u8, encoded.z as u8, 255]);
+        }
+    }
+    let mut texture = Texture::from_rgba8(8, 8, data).unwrap();
+    texture.set_sampler(Sampler {
+        filter: FilterMode::Nearest,
+        ..Sampler::new()
+    });
+    Arc::new(texture)
+}
+
+#[test]
+fn normal_texture() {
+    let mut material = Material::blinn_phong(Vec4::new(0.7, 0.7, 0.7, 1.0), 0.3, 32.0);
+    material.set_normal_texture(Some(ridged_normal_texture()));
+    check("normal_texture", &single_object(Cube::new(1.0), material));
+}
+
+#[test]
+fn point_light() {
+    let mut scene = Scene::new();
+    let mut light = PointLight::new(Vec3::new(-1.0, 1.0, 1.5), 6.0);
+    light.set_color(Vec3::new(1.0, 0.8, 0.6));
+    light.set_intensity(4.0);
+    scene.add_light(light);
+
+    let mut floor = RectangularPrism::new(6.0, 0.2, 6.0);
+    floor.set_position(Vec3::new(0.0, -1.0, 4.0));
+    scene.add_object(floor);
+    let mut cube = Cube::new(0.8);
+    cube.set_position(Vec3::new(0.0, -0.5, 3.0));
+    cube.set_rotation(Vec3::new(0.0, 0.7, 0.0));
+    cube.set_material(Material::blinn_phong(
+        Vec4::new(0.3, 0.5, 0.9, 1.0),
+        0.4,
+        32.0,
+    ));
+    scene.add_object(cube);
+
+    check("point_light", &scene);
+}
+
+#[test]
+fn multiple_lights() {
+    let mut scene = single_object(
+        Sphere::new(0.7, 24),
+        Material::pbr(Vec4::new(0.8, 0.8, 0.8, 1.0), 0.0, 0.5),
+    );
+    let mut fill = DirectionalLight::new(Vec3::new(-1.0, 0.2, 0.5));
+    fill.set_color(Vec3::new(0.3, 0.4, 1.0));
+    scene.add_light(fill);
+    check("multiple_lights", &scene);
+}
+
+#[test]
+fn wireframe() {
+    let mut scene = single_object(Sphere::new(0.7, 12), Material::new());
+    scene.set_render_mode(RenderMode::Wireframe);
+    scene.set_line_color(Vec3::new(0.2, 1.0, 0.4));
+    check("wireframe", &scene);
+}
+
+#[test]
+fn solid_wireframe() {
+    let mut scene = single_object(
+        Cube::new(1.0),
+        Material::pbr(Vec4::new(0.2, 0.4, 0.8, 1.0), 0.0, 0.5),
+    );
+    scene.set_render_mode(RenderMode::SolidWireframe);
+    scene.set_line_color(Vec3::new(1.0, 1.0, 1.0));
+    check("solid_wireframe", &scene);
+}
+
+#[test]
+fn per_object_render_mode() {
+    let mut scene = Scene::new();
+    add_lights(&mut scene);
+    let mut solid = Cube::new(0.8);
+    solid.set_position(Vec3::new(-0.7, 0.0, 2.5));
+    solid.set_rotation(Vec3::new(0.5, 0.6, 0.0));
+    scene.add_object(solid);
+    let mut wireframe = Cube::new(0.8);
+    wireframe.set_position(Vec3::new(0.7, 0.0, 2.5));
+    wireframe.set_rotation(Vec3::new(0.5, 0.6, 0.0));
+    wireframe.set_render_mode(Some(RenderMode::Wireframe));
+    scene.add_object(wireframe);
+    scene.set_line_color(Vec3::new(1.0, 0.8, 0.2));
+    check("per_object_render_mode", &scene);
+}
+
+#[test]
+fn selection_outline_and_hover() {
+    let mut scene = Scene::new();
+    add_lights(&mut scene);
+    let mut selected = Cube::new(0.8);
+    selected.set_position(Vec3::new(-0.6, 0.0, 2.5));
+    selected.set_rotation(Vec3::new(0.5, 0.6, 0.0));
+    scene.add_object(selected);
+    // Partly in front of the selected cube, so the outline follows what's visible
+    let mut hovered = Sphere::new(0.4, 16);
+    hovered.set_position(Vec3::new(0.1, -0.2, 2.0));
+    scene.add_object(hovered);
+    scene.set_selected_objects(vec![0]);
+    scene.set_hovered_object(Some(1));
+    scene.set_outline_thickness(3.0);
+    scene.set_hover_tint(Vec4::new(0.3, 0.6, 1.0, 0.4));
+    check("selection_outline_and_hover", &scene);
+}
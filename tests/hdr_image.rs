@@ -0,0 +1,123 @@
+use glam::Vec3;
+use std::path::Path;
+use three_d::core::hdr_image::HdrImage;
+
+// Three columns and two rows, every channel of every pixel different
+fn image() -> HdrImage {
+    let pixels = (0..6)
+        .map(|i| Vec3::new(i as f32, i as f32 + 0.25, -(i as f32) - 0.5) * 1.5)
+        .collect();
+    HdrImage::new(3, 2, pixels)
+}
+
+fn read(path: &Path) -> Vec<u8> {
+    let bytes = std::fs::read(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    bytes
+}
+
+fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn i32_at(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+// Reads a null-terminated string, returning it and the offset just past the null
+fn string_at(bytes: &[u8], offset: usize) -> (&str, usize) {
+    let length = bytes[offset..].iter().position(|byte| *byte == 0).unwrap();
+    let string = std::str::from_utf8(&bytes[offset..offset + length]).unwrap();
+    (string, offset + length + 1)
+}
+
+#[test]
+fn pfm_is_little_endian_from_the_bottom_row() {
+    let image = image();
+    let path = std::env::temp_dir().join("three_d_hdr_image.pfm");
+    image.save_pfm(&path).unwrap();
+    let bytes = read(&path);
+
+    // A negative scale marks the floats as little-endian
+    let header = b"PF\n3 2\n-1.0\n";
+    assert_eq!(&bytes[..header.len()], header);
+    let data = &bytes[header.len()..];
+    assert_eq!(data.len(), 3 * 2 * 3 * 4);
+
+    let mut pixels = vec![Vec3::ZERO; 6];
+    for (i, pixel) in data.chunks(12).enumerate() {
+        let (x, row_from_bottom) = (i % 3, i / 3);
+        pixels[(1 - row_from_bottom) * 3 + x] =
+            Vec3::new(f32_at(pixel, 0), f32_at(pixel, 4), f32_at(pixel, 8));
+    }
+    assert_eq!(pixels, image.get_pixels());
+}
+
+#[test]
+fn exr_lists_its_channels_and_points_at_every_scanline() {
+    let image = image();
+    let path = std::env::temp_dir().join("three_d_hdr_image.exr");
+    image.save_exr(&path).unwrap();
+    let bytes = read(&path);
+
+    assert_eq!(bytes[..4], [0x76, 0x2f, 0x31, 0x01]);
+    // Version 2 with no flags set: a single-part scanline file
+    assert_eq!(i32_at(&bytes, 4), 2);
+
+    let mut offset = 8;
+    let mut attributes = Vec::new();
+    while bytes[offset] != 0 {
+        let (name, next) = string_at(&bytes, offset);
+        let (kind, next) = string_at(&bytes, next);
+        let size = i32_at(&bytes, next) as usize;
+        let value = &bytes[next + 4..next + 4 + size];
+        attributes.push((name, kind, value));
+        offset = next + 4 + size;
+    }
+    let table = offset + 1;
+    let attribute = |wanted: &str| {
+        let (_, kind, value) = attributes
+            .iter()
+            .find(|(name, _, _)| *name == wanted)
+            .unwrap();
+        (*kind, *value)
+    };
+
+    // Three 32-bit float channels, in alphabetical order and sampled at every pixel
+    let (kind, channels) = attribute("channels");
+    assert_eq!(kind, "chlist");
+    let mut names = Vec::new();
+    let mut channel = 0;
+    while channels[channel] != 0 {
+        let (name, next) = string_at(channels, channel);
+        names.push(name);
+        assert_eq!(i32_at(channels, next), 2);
+        assert_eq!(
+            (i32_at(channels, next + 8), i32_at(channels, next + 12)),
+            (1, 1)
+        );
+        channel = next + 16;
+    }
+    assert_eq!(names, ["B", "G", "R"]);
+    assert_eq!(attribute("compression"), ("compression", &[0u8][..]));
+    let (kind, window) = attribute("dataWindow");
+    assert_eq!(kind, "box2i");
+    let window: Vec<i32> = (0..4).map(|i| i32_at(window, i * 4)).collect();
+    assert_eq!(window, [0, 0, 2, 1]);
+    assert_eq!(attribute("lineOrder"), ("lineOrder", &[0u8][..]));
+
+    // One offset per scanline, each pointing at that line's y, size and channel planes
+    let mut pixels = vec![Vec3::ZERO; 6];
+    for y in 0..2 {
+        let entry = table + y * 8;
+        let block = u64::from_le_bytes(bytes[entry..entry + 8].try_into().unwrap()) as usize;
+        assert_eq!(i32_at(&bytes, block), y as i32);
+        assert_eq!(i32_at(&bytes, block + 4), 3 * 3 * 4);
+        for x in 0..3 {
+            let sample = |plane: usize| f32_at(&bytes, block + 8 + (plane * 3 + x) * 4);
+            pixels[y * 3 + x] = Vec3::new(sample(2), sample(1), sample(0));
+        }
+    }
+    assert_eq!(pixels, image.get_pixels());
+    assert_eq!(bytes.len(), table + 2 * 8 + 2 * (8 + 3 * 3 * 4));
+}
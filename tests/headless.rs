@@ -0,0 +1,63 @@
+use glam::{Vec3, Vec4};
+use image::RgbaImage;
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::material::Material;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::geometry::primitives::cube::Cube;
+
+// A white unlit cube straight ahead, its front face square on to the camera
+fn scene() -> Scene {
+    let mut cube = Cube::new(1.0);
+    cube.set_position(Vec3::new(0.0, 0.0, 3.0));
+    cube.set_material(Material::unlit(Vec4::ONE));
+    let mut scene = Scene::new();
+    scene.add_object(cube);
+    scene
+}
+
+fn covered(image: &RgbaImage) -> usize {
+    image.pixels().filter(|pixel| pixel.0[0] > 0).count()
+}
+
+#[test]
+fn renders_at_the_requested_size() {
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(160, 60));
+    assert_eq!((renderer.get_width(), renderer.get_height()), (160, 60));
+    let image = renderer.render(&scene(), &Camera::new());
+    assert_eq!(image.dimensions(), (160, 60));
+    assert!(covered(&image) > 0);
+
+    // The square face stays square in a wide image: as many pixels across as down
+    let across = (0..160)
+        .filter(|x| image.get_pixel(*x, 30).0[0] > 0)
+        .count();
+    let down = (0..60).filter(|y| image.get_pixel(80, *y).0[0] > 0).count();
+    assert!(across.abs_diff(down) <= 1, "{across} {down}");
+}
+
+#[test]
+fn renderers_of_other_sizes_dont_change_each_other() {
+    let mut wide = pollster::block_on(HeadlessRenderer::new(160, 60));
+    let before = wide.render(&scene(), &Camera::new());
+    let mut tall = pollster::block_on(HeadlessRenderer::new(60, 160));
+    let tall_image = tall.render(&scene(), &Camera::new());
+    assert_eq!(tall_image.dimensions(), (60, 160));
+    assert_eq!(wide.render(&scene(), &Camera::new()), before);
+}
+
+#[test]
+fn writes_the_render_to_a_png() {
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(96, 64));
+    let path = std::env::temp_dir().join("three_d_headless_render.png");
+    renderer
+        .render_to_png(&scene(), &Camera::new(), &path)
+        .unwrap();
+
+    let saved = image::open(&path).unwrap().to_rgba8();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(saved.dimensions(), (96, 64));
+    assert!(covered(&saved) > 0);
+    assert_eq!(saved, renderer.render(&scene(), &Camera::new()));
+}
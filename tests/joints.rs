@@ -0,0 +1,220 @@
+use glam::Vec3;
+use three_d::core::object::Object;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::collider::Collider;
+use three_d::physics::joint::{Joint, JointKind, JointMotor};
+use three_d::physics::rigid_body::RigidBody;
+use three_d::physics::world::PhysicsWorld;
+
+const DT: f32 = 1.0 / 60.0;
+
+// Immovable object to hang things from
+fn anchor(position: Vec3) -> Object {
+    let mut anchor = Cube::new(1.0);
+    anchor.set_position(position);
+    anchor
+}
+
+fn body(position: Vec3) -> Object {
+    let mut body = Cube::new(1.0);
+    body.set_position(position);
+    body.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+    body
+}
+
+#[test]
+fn pendulum_keeps_its_length() {
+    let pivot = Vec3::new(0.0, 5.0, 0.0);
+    let mut objects = vec![anchor(pivot), body(Vec3::new(2.0, 5.0, 0.0))];
+    let mut world = PhysicsWorld::new();
+    world.add_joint(Joint::ball_socket(0, 1, pivot));
+
+    let (mut lowest, mut leftmost) = (f32::INFINITY, f32::INFINITY);
+    for _ in 0..300 {
+        world.step_fixed(&mut objects, DT);
+        let position = *objects[1].get_position();
+        assert!((position.distance(pivot) - 2.0).abs() < 0.05, "{position}");
+        lowest = lowest.min(position.y);
+        leftmost = leftmost.min(position.x);
+    }
+    assert!(lowest < 3.05, "{lowest}");
+    assert!(leftmost < -1.5, "{leftmost}");
+}
+
+#[test]
+fn motorized_hinge_turns_about_its_axis_up_to_its_limit() {
+    let hinge = Vec3::new(0.0, 1.0, 0.0);
+    let mut objects = vec![anchor(Vec3::ZERO), body(Vec3::new(0.5, 1.0, 0.0))];
+    let mut world = PhysicsWorld::new();
+    world.set_gravity(Vec3::ZERO);
+    let mut joint = Joint::hinge(0, 1, hinge, Vec3::Y);
+    joint.set_limits(Some((0.0, 1.2)));
+    joint.set_motor(Some(JointMotor::new(1.0, 100.0)));
+    let index = world.add_joint(joint);
+
+    for step in 0..180 {
+        world.step_fixed(&mut objects, DT);
+        let door = &objects[1];
+        // The door swings around the hinge line, never along or off it
+        let offset = *door.get_position() - hinge;
+        assert!(offset.y.abs() < 0.01, "{offset}");
+        assert!((offset.length() - 0.5).abs() < 0.01, "{offset}");
+        assert!(door.get_rotation().x.abs() < 0.01 && door.get_rotation().z.abs() < 0.01);
+        if step == 59 {
+            // A second at one radian per second
+            assert!((door.get_rotation().y - 1.0).abs() < 0.05);
+        }
+    }
+    assert!((objects[1].get_rotation().y - 1.2).abs() < 0.02);
+
+    // Reversing the motor swings it back to the lower limit
+    world
+        .get_joint_mut(index)
+        .set_motor(Some(JointMotor::new(-2.0, 100.0)));
+    for _ in 0..120 {
+        world.step_fixed(&mut objects, DT);
+    }
+    assert!(objects[1].get_rotation().y.abs() < 0.02);
+    assert!(matches!(
+        world.get_joints()[index].get_kind(),
+        JointKind::Hinge {
+            limits: Some(_),
+            motor: Some(_),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn fixed_joint_holds_a_cantilever() {
+    let mut objects = vec![anchor(Vec3::ZERO), body(Vec3::new(1.5, 0.0, 0.0))];
+    let mut world = PhysicsWorld::new();
+    world.add_joint(Joint::fixed(0, 1, Vec3::new(0.75, 0.0, 0.0)));
+    for _ in 0..120 {
+        world.step_fixed(&mut objects, DT);
+    }
+    let held = &objects[1];
+    assert!(held.get_position().distance(Vec3::new(1.5, 0.0, 0.0)) < 0.05);
+    assert!(
+        held.get_rotation().length() < 0.05,
+        "{}",
+        held.get_rotation()
+    );
+}
+
+#[test]
+fn slider_moves_only_along_its_axis() {
+    let mut objects = vec![anchor(Vec3::ZERO), body(Vec3::ZERO)];
+    let mut world = PhysicsWorld::new();
+    world.set_gravity(Vec3::new(3.0, -9.81, 2.0));
+    let mut joint = Joint::slider(0, 1, Vec3::ZERO, Vec3::X);
+    joint.set_limits(Some((-0.5, 1.0)));
+    world.add_joint(joint);
+    for _ in 0..120 {
+        world.step_fixed(&mut objects, DT);
+    }
+    let slid = &objects[1];
+    assert!(
+        (slid.get_position().x - 1.0).abs() < 0.02,
+        "{}",
+        slid.get_position()
+    );
+    assert!(slid.get_position().y.abs() < 0.02 && slid.get_position().z.abs() < 0.02);
+    assert!(slid.get_rotation().length() < 0.01);
+}
+
+#[test]
+fn rope_goes_slack_but_never_stretches() {
+    let top = Vec3::new(0.0, 5.0, 0.0);
+    let mut objects = vec![anchor(top), body(Vec3::new(0.5, 4.0, 0.0))];
+    let mut world = PhysicsWorld::new();
+    let mut joint = Joint::distance(0, 1, top, Vec3::new(0.5, 4.0, 0.0));
+    joint.set_kind(JointKind::Distance {
+        min_length: 0.0,
+        max_length: 2.0,
+    });
+    world.add_joint(joint);
+
+    let mut longest = 0.0f32;
+    for _ in 0..180 {
+        world.step_fixed(&mut objects, DT);
+        longest = longest.max(objects[1].get_position().distance(top));
+    }
+    assert!(longest > 1.9 && longest < 2.05, "{longest}");
+}
+
+#[test]
+fn spring_settles_where_it_holds_the_weight() {
+    let top = Vec3::new(0.0, 5.0, 0.0);
+    let mut objects = vec![anchor(top), body(Vec3::new(0.0, 4.0, 0.0))];
+    let mut world = PhysicsWorld::new();
+    world.add_joint(Joint::spring(
+        0,
+        1,
+        top,
+        Vec3::new(0.0, 4.0, 0.0),
+        100.0,
+        5.0,
+    ));
+
+    let mut lowest = f32::INFINITY;
+    for _ in 0..600 {
+        world.step_fixed(&mut objects, DT);
+        lowest = lowest.min(objects[1].get_position().y);
+    }
+    // Stretched by mg / k, after overshooting it on the way down
+    let rest = 4.0 - 9.81 / 100.0;
+    assert!((objects[1].get_position().y - rest).abs() < 0.01);
+    assert!(lowest < rest - 0.02);
+}
+
+#[test]
+fn chain_stays_connected() {
+    let mut objects = vec![anchor(Vec3::ZERO)];
+    let mut world = PhysicsWorld::new();
+    for link in 1..=6 {
+        objects.push(body(Vec3::new(link as f32 * 0.5, 0.0, 0.0)));
+        let joint_point = Vec3::new((link as f32 - 0.5) * 0.5, 0.0, 0.0);
+        world.add_joint(Joint::ball_socket(link - 1, link, joint_point));
+    }
+    let mut lowest = f32::INFINITY;
+    for _ in 0..180 {
+        world.step_fixed(&mut objects, DT);
+        lowest = lowest.min(objects[6].get_position().y);
+    }
+    // Each link's end stays on the next link's start
+    for link in 1..=6 {
+        let end = objects[link - 1].transform_point(Vec3::new(0.25, 0.0, 0.0));
+        let start = objects[link].transform_point(Vec3::new(-0.25, 0.0, 0.0));
+        assert!(end.distance(start) < 0.02, "{link}: {end} {start}");
+    }
+    // It swung down from horizontal
+    assert!(lowest < -2.0, "{lowest}");
+}
+
+#[test]
+fn jointed_objects_only_collide_when_asked() {
+    let overlapping = || {
+        let mut objects = vec![body(Vec3::ZERO), body(Vec3::new(0.8, 0.0, 0.0))];
+        for object in &mut objects {
+            object.set_collider(Some(Collider::cuboid(Vec3::splat(0.5))));
+        }
+        objects
+    };
+    let mut world = PhysicsWorld::new();
+    world.set_gravity(Vec3::ZERO);
+    let index = world.add_joint(Joint::ball_socket(0, 1, Vec3::new(0.4, 0.0, 0.0)));
+
+    let mut objects = overlapping();
+    for _ in 0..10 {
+        world.step_fixed(&mut objects, DT);
+    }
+    assert!(objects[1].get_position().distance(Vec3::new(0.8, 0.0, 0.0)) < 1e-4);
+
+    world.get_joint_mut(index).set_collide_connected(true);
+    let mut objects = overlapping();
+    for _ in 0..10 {
+        world.step_fixed(&mut objects, DT);
+    }
+    assert!(objects[1].get_position().distance(Vec3::new(0.8, 0.0, 0.0)) > 1e-3);
+}
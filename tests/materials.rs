@@ -0,0 +1,106 @@
+use glam::{Vec3, Vec4};
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::light::DirectionalLight;
+use three_d::core::material::{Material, ShadingModel};
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::core::texture::linear_to_srgb;
+use three_d::geometry::primitives::cube::Cube;
+
+const WIDTH: u32 = 80;
+const HEIGHT: u32 = 60;
+
+// A cube whose front face fills the middle of the view, squarely facing the camera, lit
+// head-on unless `light_angle` turns the light away from the face normal
+fn scene(material: Material, light_angle: f32, intensity: f32) -> Scene {
+    let mut scene = Scene::new();
+    let mut light = DirectionalLight::new(Vec3::new(light_angle.sin(), 0.0, light_angle.cos()));
+    light.set_intensity(intensity);
+    scene.add_light(light);
+    let mut cube = Cube::new(2.0);
+    cube.set_position(Vec3::new(0.0, 0.0, 3.0));
+    cube.set_material(material);
+    scene.add_object(cube);
+    scene
+}
+
+// Red, green and blue of the pixel in the middle of the image
+fn center(scene: &Scene) -> [u8; 3] {
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+    let image = renderer.render(scene, &Camera::new());
+    let [r, g, b, _] = image.get_pixel(WIDTH / 2, HEIGHT / 2).0;
+    [r, g, b]
+}
+
+fn encoded(linear: f32) -> u8 {
+    (linear_to_srgb(linear.clamp(0.0, 1.0)) * 255.0).round() as u8
+}
+
+fn assert_close(actual: [u8; 3], expected: [u8; 3]) {
+    let close = actual.iter().zip(expected).all(|(a, b)| a.abs_diff(b) <= 2);
+    assert!(close, "{actual:?} != {expected:?}");
+}
+
+#[test]
+fn constructors_set_the_shading_model_and_clamp_factors() {
+    assert_eq!(
+        Material::new().get_shading_model(),
+        ShadingModel::BlinnPhong
+    );
+    assert_eq!(Material::new().get_specular(), 0.0);
+    let unlit = Material::unlit(Vec4::new(0.2, 0.4, 0.6, 1.0));
+    assert_eq!(unlit.get_shading_model(), ShadingModel::Unlit);
+    assert_eq!(unlit.get_base_color(), Vec4::new(0.2, 0.4, 0.6, 1.0));
+    let pbr = Material::pbr(Vec4::ONE, 1.5, -0.5);
+    assert_eq!(pbr.get_shading_model(), ShadingModel::Pbr);
+    assert_eq!((pbr.get_metallic(), pbr.get_roughness()), (1.0, 0.0));
+    let gltf = Material::from_gltf([1.0, 0.5, 0.25, 1.0], 0.0, 0.5, [0.1, 0.2, 0.3]);
+    assert_eq!(gltf.get_shading_model(), ShadingModel::Pbr);
+    assert_eq!(gltf.get_emissive(), Vec3::new(0.1, 0.2, 0.3));
+}
+
+#[test]
+fn unlit_ignores_the_lights() {
+    let material = Material::unlit(Vec4::new(0.5, 0.2, 0.0, 1.0));
+    let expected = [encoded(0.5), encoded(0.2), 0];
+    assert_close(center(&scene(material.clone(), 0.0, 1.0)), expected);
+    assert_close(center(&scene(material, 1.2, 0.1)), expected);
+}
+
+#[test]
+fn blinn_phong_follows_the_cosine_law_and_adds_emissive() {
+    let head_on = center(&scene(Material::new(), 0.0, 0.5));
+    assert_close(head_on, [encoded(0.5); 3]);
+    let angle = 60f32.to_radians();
+    let slanted = center(&scene(Material::new(), angle, 0.5));
+    assert_close(slanted, [encoded(0.5 * angle.cos()); 3]);
+
+    let mut glowing = Material::new();
+    glowing.set_emissive(Vec3::new(0.0, 0.0, 0.25));
+    let glowing = center(&scene(glowing, angle, 0.5));
+    assert_close(
+        glowing,
+        [encoded(0.25), encoded(0.25), encoded(0.25 + 0.25)],
+    );
+}
+
+#[test]
+fn pbr_metals_reflect_their_color_and_dielectrics_diffuse_it() {
+    // Lit from the side so the mirror highlight points away from the camera
+    let angle = 50f32.to_radians();
+    let color = Vec4::new(0.9, 0.5, 0.1, 1.0);
+    let dielectric = center(&scene(Material::pbr(color, 0.0, 0.6), angle, 3.0));
+    let metal = center(&scene(Material::pbr(color, 1.0, 0.6), angle, 3.0));
+    // Diffuse keeps the hue; a rough metal tints its dimmer reflection instead
+    assert!(dielectric[0] > dielectric[1] && dielectric[1] > dielectric[2]);
+    assert!(metal[0] > metal[2]);
+    assert!(metal[0] < dielectric[0], "{metal:?} {dielectric:?}");
+    // Facing away from the light, it gets none
+    let unlit = center(&scene(
+        Material::pbr(color, 0.0, 0.6),
+        100f32.to_radians(),
+        3.0,
+    ));
+    assert_eq!(unlit, [0, 0, 0]);
+}
@@ -0,0 +1,153 @@
+// Uses the path tracer as ground truth for the rasterized lighting. With bounces turned off
+// and nothing to cast shadows, both should shade every surface the same; only the
+// antialiased silhouettes of the path-traced image may differ.
+use glam::{Vec3, Vec4};
+use three_d::core::camera::Camera;
+use three_d::core::golden::compare_images;
+use three_d::core::light::{DirectionalLight, PointLight};
+use three_d::core::material::Material;
+use three_d::core::path_tracer::PathTracer;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::rectangular_prism::RectangularPrism;
+use three_d::geometry::primitives::sphere::Sphere;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+
+fn check_direct_lighting(material: Material, (width, height): (u32, u32)) {
+    let mut scene = Scene::new();
+    let mut key = DirectionalLight::new(Vec3::new(1.0, -1.0, 1.0));
+    key.set_intensity(2.0);
+    scene.add_light(key);
+    let mut point = PointLight::new(Vec3::new(-1.5, 0.5, 1.0), 6.0);
+    point.set_color(Vec3::new(0.4, 0.6, 1.0));
+    point.set_intensity(3.0);
+    scene.add_light(point);
+
+    let mut sphere = Sphere::new(0.7, 24);
+    sphere.set_position(Vec3::new(0.0, 0.0, 2.2));
+    sphere.set_rotation(Vec3::new(0.5, 0.6, 0.0));
+    sphere.set_material(material);
+    scene.add_object(sphere);
+
+    let camera = Camera::new();
+    let rasterized = SoftwareRenderer::new(width, height).render(&scene, &camera);
+    let mut tracer = PathTracer::new(width, height);
+    tracer.set_max_bounces(0);
+    tracer.set_samples_per_pixel(16);
+    let traced = tracer.render(&scene, &camera);
+
+    let diff = compare_images(&traced, &rasterized, 3);
+    assert!(
+        diff.get_differing_percent() < 5.0,
+        "{:.2}% of pixels differ (max difference {})",
+        diff.get_differing_percent(),
+        diff.max_difference
+    );
+}
+
+#[test]
+fn blinn_phong_matches_rasterizer() {
+    check_direct_lighting(
+        Material::blinn_phong(Vec4::new(0.8, 0.2, 0.2, 1.0), 0.6, 48.0),
+        (WIDTH, HEIGHT),
+    );
+}
+
+#[test]
+fn pbr_matches_rasterizer() {
+    check_direct_lighting(
+        Material::pbr(Vec4::new(0.2, 0.7, 0.3, 1.0), 0.0, 0.4),
+        (WIDTH, HEIGHT),
+    );
+}
+
+#[test]
+fn wide_images_match_rasterizer() {
+    // Each renderer projects for its own image, whatever size was created last
+    check_direct_lighting(Material::new(), (160, 60));
+    check_direct_lighting(Material::new(), (60, 100));
+}
+
+#[test]
+fn progressive_accumulation() {
+    let mut scene = Scene::new();
+    scene.add_light(DirectionalLight::new(Vec3::new(0.0, -1.0, 1.0)));
+    let mut sphere = Sphere::new(0.7, 16);
+    sphere.set_position(Vec3::new(0.0, 0.0, 2.2));
+    scene.add_object(sphere);
+    let camera = Camera::new();
+
+    let mut tracer = PathTracer::new(32, 24);
+    tracer.accumulate(&scene, &camera, 2);
+    tracer.accumulate(&scene, &camera, 3);
+    assert_eq!(tracer.get_sample_count(), 5);
+    let image = tracer.get_image();
+    assert!(image.get_pixel(16, 12).length() > 0.0);
+    assert_eq!(image.get_pixel(0, 0), Vec3::ZERO);
+
+    tracer.reset();
+    assert_eq!(tracer.get_sample_count(), 0);
+}
+
+// A floor with its top at y = -0.9 under the whole view, lit straight from above
+fn lit_floor() -> Scene {
+    let mut scene = Scene::new();
+    scene.add_light(DirectionalLight::new(Vec3::new(0.0, -1.0, 0.0)));
+    let mut floor = RectangularPrism::new(12.0, 0.2, 12.0);
+    floor.set_position(Vec3::new(0.0, -1.0, 6.0));
+    scene.add_object(floor);
+    scene
+}
+
+// Average radiance of the pixels around (x, y)
+fn radiance(tracer: &mut PathTracer, scene: &Scene, bounces: u32, (x, y): (u32, u32)) -> f32 {
+    tracer.reset();
+    tracer.set_max_bounces(bounces);
+    tracer.accumulate(scene, &Camera::new(), 32);
+    let image = tracer.get_image();
+    let mut sum = 0.0;
+    for y in y - 1..=y + 1 {
+        for x in x - 1..=x + 1 {
+            sum += image.get_pixel(x, y).element_sum();
+        }
+    }
+    sum / 9.0
+}
+
+#[test]
+fn occluders_cast_shadows() {
+    // The floor 3 ahead, seen under the cube that hangs over it
+    let floor_point = (WIDTH / 2, (HEIGHT as f32 * (1.0 + 0.9 / 3.0) / 2.0) as u32);
+    let mut tracer = PathTracer::new(WIDTH, HEIGHT);
+    let lit = radiance(&mut tracer, &lit_floor(), 0, floor_point);
+
+    let mut shadowed = lit_floor();
+    let mut cube = Cube::new(1.0);
+    cube.set_position(Vec3::new(0.0, 0.6, 3.0));
+    shadowed.add_object(cube);
+    let dark = radiance(&mut tracer, &shadowed, 0, floor_point);
+    assert!(lit > 0.5, "{lit}");
+    assert_eq!(dark, 0.0);
+}
+
+#[test]
+fn bounces_light_surfaces_the_lights_miss() {
+    // A wall straight ahead, standing on the floor, its face edge on to the light above
+    let mut scene = lit_floor();
+    let mut wall = RectangularPrism::new(12.0, 4.0, 0.2);
+    wall.set_position(Vec3::new(0.0, 1.0, 4.0));
+    scene.add_object(wall);
+    let middle = (WIDTH / 2, HEIGHT / 2);
+    let mut tracer = PathTracer::new(WIDTH, HEIGHT);
+
+    assert_eq!(radiance(&mut tracer, &scene, 0, middle), 0.0);
+    // Light off the floor reaches it after one bounce, and a little more after a few
+    let one_bounce = radiance(&mut tracer, &scene, 1, middle);
+    assert!(one_bounce > 0.05, "{one_bounce}");
+    let bounces = radiance(&mut tracer, &scene, 4, middle);
+    assert!(bounces > one_bounce, "{bounces} {one_bounce}");
+}
@@ -0,0 +1,86 @@
+use glam::{Vec3, Vec4};
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::material::Material;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::sphere::Sphere;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+
+// A red cube with a green sphere partly in front of it and a blue cube off to the side, all
+// unlit so every covered pixel shows its object's color
+fn scene() -> Scene {
+    let mut scene = Scene::new();
+    let mut cube = Cube::new(1.5);
+    cube.set_position(Vec3::new(-0.5, 0.0, 5.0));
+    cube.set_rotation(Vec3::new(0.4, 0.7, 0.0));
+    cube.set_material(Material::unlit(Vec4::new(1.0, 0.0, 0.0, 1.0)));
+    scene.add_object(cube);
+    let mut sphere = Sphere::new(0.6, 16);
+    sphere.set_position(Vec3::new(0.3, 0.2, 4.0));
+    sphere.set_material(Material::unlit(Vec4::new(0.0, 1.0, 0.0, 1.0)));
+    scene.add_object(sphere);
+    let mut side = Cube::new(0.8);
+    side.set_position(Vec3::new(2.5, -1.0, 5.0));
+    side.set_material(Material::unlit(Vec4::new(0.0, 0.0, 1.0, 1.0)));
+    scene.add_object(side);
+    scene
+}
+
+#[test]
+fn pick_matches_rendered_pixels() {
+    let scene = scene();
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+    let image = renderer.render(&scene, &Camera::new());
+
+    // Every few pixels, the id under the pixel is the object whose color was drawn there
+    let mut seen = [0; 3];
+    for y in (0..HEIGHT).step_by(5) {
+        for x in (0..WIDTH).step_by(5) {
+            let pixel = image.get_pixel(x, y).0;
+            let expected = match pixel {
+                [0, 0, 0, _] => None,
+                [r, _, _, _] if r > 128 => Some(0),
+                [_, g, _, _] if g > 128 => Some(1),
+                _ => Some(2),
+            };
+            let picked = renderer.pick(x, y);
+            assert_eq!(picked, expected, "pixel ({x}, {y}) is {pixel:?}");
+            if let Some(object) = picked {
+                seen[object] += 1;
+            }
+        }
+    }
+    assert!(seen.iter().all(|&count| count > 0), "{seen:?}");
+}
+
+#[test]
+fn marquee_selection() {
+    let scene = scene();
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+    renderer.render(&scene, &Camera::new());
+
+    assert_eq!(
+        renderer.pick_rect((0, 0), (WIDTH - 1, HEIGHT - 1)),
+        vec![0, 1, 2]
+    );
+    // Corners in either order; the left half holds the red cube and the green sphere
+    assert_eq!(
+        renderer.pick_rect((WIDTH / 2, HEIGHT - 1), (0, 0)),
+        vec![0, 1]
+    );
+    // The empty top-right corner
+    assert!(
+        renderer
+            .pick_rect((WIDTH - 10, 0), (WIDTH - 1, 10))
+            .is_empty()
+    );
+    // Corners past the image are clamped
+    assert_eq!(
+        renderer.pick_rect((WIDTH - 60, HEIGHT - 60), (WIDTH + 50, HEIGHT + 50)),
+        vec![2]
+    );
+}
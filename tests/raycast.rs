@@ -0,0 +1,106 @@
+use glam::Vec3;
+use three_d::core::camera::Camera;
+use three_d::core::scene::Scene;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::sphere::Sphere;
+use three_d::geometry::ray::Ray;
+
+fn scene() -> Scene {
+    let mut scene = Scene::new();
+    let mut cube = Cube::new(1.0);
+    cube.set_position(Vec3::new(0.0, 0.0, 5.0));
+    cube.set_rotation(Vec3::new(0.0, 0.6, 0.3));
+    scene.add_object(cube);
+    let mut sphere = Sphere::new(0.5, 12);
+    sphere.set_position(Vec3::new(1.5, 0.5, 4.0));
+    scene.add_object(sphere);
+    let mut back = Cube::new(3.0);
+    back.set_position(Vec3::new(0.0, 0.0, 9.0));
+    scene.add_object(back);
+    scene
+}
+
+#[test]
+fn raycast_matches_brute_force() {
+    let scene = scene();
+    let origin = Vec3::ZERO;
+    for x in -6..=6 {
+        for y in -6..=6 {
+            let ray = Ray::new(origin, Vec3::new(x as f32 * 0.05, y as f32 * 0.05, 1.0));
+            let expected = scene
+                .get_objects()
+                .iter()
+                .enumerate()
+                .flat_map(|(index, object)| {
+                    object
+                        .get_mesh()
+                        .get_triangles()
+                        .iter()
+                        .filter_map(move |triangle| {
+                            let vertices = object.transformed_triangle(triangle.clone());
+                            let (distance, _, _) =
+                                ray.intersect_triangle(vertices.get_vertices())?;
+                            Some((index, distance))
+                        })
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            let hit = scene.raycast(&ray, 100.0);
+            assert_eq!(
+                hit.map(|hit| hit.object),
+                expected.map(|hit| hit.0),
+                "{x} {y}"
+            );
+            if let (Some(hit), Some((_, distance))) = (hit, expected) {
+                assert!((hit.distance - distance).abs() < 1e-4);
+            }
+        }
+    }
+}
+
+#[test]
+fn hit_details() {
+    let scene = scene();
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 0.0), Vec3::Z);
+    let hit = scene.raycast(&ray, 100.0).unwrap();
+    assert_eq!(hit.object, 0);
+    assert!(hit.point.abs_diff_eq(ray.at(hit.distance), 1e-5));
+
+    // The point and normal are in world space and agree with the rotated triangle
+    let object = &scene.get_objects()[0];
+    let triangle =
+        object.transformed_triangle(object.get_mesh().get_triangles()[hit.triangle].clone());
+    let [a, b, c] = triangle.get_vertices();
+    let point = a * hit.barycentric.x + b * hit.barycentric.y + c * hit.barycentric.z;
+    assert!(point.abs_diff_eq(hit.point, 1e-4));
+    assert!(hit.normal.abs_diff_eq(triangle.get_normal(), 1e-5));
+    assert!(hit.normal.dot(ray.direction) < 0.0);
+
+    // Too short to reach anything
+    assert!(scene.raycast(&ray, 4.0).is_none());
+    // Past the cube the big box behind it is next
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 6.0), Vec3::Z);
+    assert_eq!(scene.raycast(&ray, 100.0).unwrap().object, 2);
+}
+
+#[test]
+fn screen_point_to_ray() {
+    let camera = Camera::new();
+    // The center of an 800x600 screen looks straight ahead
+    let ray = camera.screen_point_to_ray(400.0, 300.0);
+    assert!(ray.direction.abs_diff_eq(Vec3::Z, 1e-6));
+
+    // A ray through a pixel passes through the points that project onto it
+    let view_projection = camera.get_view_projection_matrix();
+    let point = Vec3::new(1.5, 0.5, 4.0);
+    let clip = view_projection * point.extend(1.0);
+    let ndc = clip.truncate() / clip.w;
+    let x = (ndc.x + 1.0) * 0.5 * 800.0;
+    let y = (1.0 - ndc.y) * 0.5 * 600.0;
+    let ray = camera.screen_point_to_ray(x, y);
+    let to_point = point - ray.origin;
+    assert!(ray.direction.abs_diff_eq(to_point.normalize(), 1e-5));
+
+    // Which picks the sphere centered there
+    assert_eq!(scene().raycast(&ray, 100.0).unwrap().object, 1);
+}
@@ -0,0 +1,183 @@
+use glam::{Mat3, Vec3};
+use three_d::core::scene::Scene;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::collider::Collider;
+use three_d::physics::mass_properties::MassProperties;
+use three_d::physics::rigid_body::RigidBody;
+use three_d::physics::world::PhysicsWorld;
+
+fn assert_close(a: Vec3, b: Vec3, tolerance: f32) {
+    assert!((a - b).length() <= tolerance, "{a} != {b}");
+}
+
+#[test]
+fn cube_mesh_inertia_matches_solid_box() {
+    let cube = Cube::new(2.0);
+    let properties = MassProperties::from_mesh(cube.get_mesh(), 3.0);
+    assert!((properties.mass - 3.0).abs() < 1e-6);
+    assert_close(properties.center_of_mass, Vec3::ZERO, 1e-5);
+    // m s² / 6 on the diagonal, nothing off it
+    let expected = MassProperties::from_box(Vec3::ONE, 3.0).inertia;
+    assert!(properties.inertia.abs_diff_eq(expected, 1e-4));
+    assert!(expected.abs_diff_eq(Mat3::from_diagonal(Vec3::splat(2.0)), 1e-6));
+}
+
+#[test]
+fn colliders_keep_explicit_mass_properties() {
+    // Derived ones follow the shape, keeping the mass given to the body
+    let mut derived = Cube::new(2.0);
+    let mut body = RigidBody::dynamic(3.0);
+    body.set_mass(4.0);
+    derived.set_rigid_body(Some(body));
+    derived.set_collider(Some(Collider::sphere(1.0)));
+    let body = derived.get_rigid_body().unwrap();
+    assert!(body.has_derived_mass_properties());
+    assert_eq!(
+        body.get_mass_properties(),
+        Some(MassProperties::from_sphere(1.0, 4.0))
+    );
+
+    // Explicit ones stay whichever order the body and collider are set in
+    let explicit = MassProperties::new(5.0, Vec3::new(0.0, -0.5, 0.0), Mat3::IDENTITY);
+    let mut body = RigidBody::dynamic(1.0);
+    body.set_mass_properties(explicit);
+    let mut before = Cube::new(2.0);
+    before.set_rigid_body(Some(body.clone()));
+    before.set_collider(Some(Collider::sphere(1.0)));
+    let mut after = Cube::new(2.0);
+    after.set_collider(Some(Collider::sphere(1.0)));
+    after.set_rigid_body(Some(body));
+    for object in [before, after] {
+        let body = object.get_rigid_body().unwrap();
+        assert!(!body.has_derived_mass_properties());
+        assert_eq!(body.get_mass_properties(), Some(explicit));
+        assert_eq!(body.get_mass(), 5.0);
+    }
+}
+
+#[test]
+fn free_fall_matches_analytic_result() {
+    let mut world = PhysicsWorld::new();
+    let mut cube = Cube::new(1.0);
+    cube.set_position(Vec3::new(0.0, 10.0, 0.0));
+    let mut body = RigidBody::dynamic(2.0);
+    body.set_angular_damping(0.0);
+    cube.set_rigid_body(Some(body));
+    let mut objects = vec![cube];
+
+    let dt = world.get_fixed_timestep();
+    let steps = 60;
+    for _ in 0..steps {
+        world.step_fixed(&mut objects, dt);
+    }
+    // Semi-implicit Euler falls g dt² n(n+1)/2 after n steps
+    let n = steps as f32;
+    let fallen = 9.81 * dt * dt * n * (n + 1.0) / 2.0;
+    assert_close(
+        *objects[0].get_position(),
+        Vec3::new(0.0, 10.0 - fallen, 0.0),
+        1e-3,
+    );
+    let velocity = objects[0].get_rigid_body().unwrap().get_linear_velocity();
+    assert_close(velocity, Vec3::new(0.0, -9.81, 0.0), 1e-3);
+}
+
+#[test]
+fn fixed_step_ignores_frame_rate() {
+    let simulate = |frame_time: f32, frames: u32| {
+        let mut scene = Scene::new();
+        let mut cube = Cube::new(1.0);
+        cube.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+        scene.add_object(cube);
+        for _ in 0..frames {
+            scene.update(frame_time);
+        }
+        *scene.get_objects()[0].get_position()
+    };
+    // Half a second at 30 and at 120 frames per second runs the same 30 steps
+    let slow = simulate(1.0 / 30.0, 15);
+    let fast = simulate(1.0 / 120.0, 60);
+    assert_close(slow, fast, 1e-4);
+    assert!(slow.y < -1.0);
+
+    // A long frame runs at most max_substeps steps
+    let mut world = PhysicsWorld::new();
+    let mut objects = vec![Cube::new(1.0)];
+    assert_eq!(world.step(&mut objects, 1.0), world.get_max_substeps());
+    assert_eq!(world.step(&mut objects, 0.0), 0);
+}
+
+#[test]
+fn stalls_are_dropped_rather_than_owed() {
+    let mut world = PhysicsWorld::new();
+    world.set_fixed_timestep(0.1);
+    world.set_max_substeps(4);
+    // Ten and a quarter steps' worth runs four, keeping only the quarter step
+    assert_eq!(world.advance(1.025), 4);
+    let alpha = world.get_interpolation_alpha();
+    assert!((alpha - 0.25).abs() < 1e-3, "{alpha}");
+    // so a short frame after it doesn't make up a step
+    assert_eq!(world.advance(0.05), 0);
+    let alpha = world.get_interpolation_alpha();
+    assert!(alpha < 1.0 && (alpha - 0.75).abs() < 1e-3, "{alpha}");
+    assert_eq!(world.advance(0.05), 1);
+}
+
+#[test]
+fn impulses_move_and_spin() {
+    let mut world = PhysicsWorld::new();
+    world.set_gravity(Vec3::ZERO);
+    let mut cube = Cube::new(1.0);
+    let mut body = RigidBody::dynamic(2.0);
+    body.set_angular_damping(0.0);
+    cube.set_rigid_body(Some(body));
+
+    // Through the center it only moves
+    cube.apply_impulse_at_point(Vec3::new(4.0, 0.0, 0.0), Vec3::ZERO);
+    let body = cube.get_rigid_body().unwrap();
+    assert_close(body.get_linear_velocity(), Vec3::new(2.0, 0.0, 0.0), 1e-5);
+    assert_close(body.get_angular_velocity(), Vec3::ZERO, 1e-5);
+
+    // Off center it also spins: torque (0.5, 0, 0) x (0, 1, 0) about z, over m s² / 6
+    cube.get_rigid_body_mut()
+        .unwrap()
+        .set_linear_velocity(Vec3::ZERO);
+    cube.apply_impulse_at_point(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.5, 0.0, 0.0));
+    let spin = cube.get_rigid_body().unwrap().get_angular_velocity();
+    assert_close(spin, Vec3::new(0.0, 0.0, 1.5), 1e-4);
+
+    cube.get_rigid_body_mut()
+        .unwrap()
+        .set_linear_velocity(Vec3::ZERO);
+    let mut objects = vec![cube];
+    for _ in 0..30 {
+        world.step_fixed(&mut objects, 1.0 / 60.0);
+    }
+    assert!((objects[0].get_rotation().z - 0.75).abs() < 1e-3);
+    assert_close(*objects[0].get_position(), Vec3::ZERO, 1e-5);
+}
+
+#[test]
+fn kinematic_and_static_bodies() {
+    let mut world = PhysicsWorld::new();
+    let mut kinematic = Cube::new(1.0);
+    let mut body = RigidBody::kinematic();
+    body.set_linear_velocity(Vec3::new(1.0, 0.0, 0.0));
+    kinematic.set_rigid_body(Some(body));
+    let mut fixed = Cube::new(1.0);
+    fixed.set_position(Vec3::new(0.0, 2.0, 0.0));
+    let mut body = RigidBody::fixed();
+    body.set_linear_velocity(Vec3::new(1.0, 0.0, 0.0));
+    fixed.set_rigid_body(Some(body));
+    let mut objects = vec![kinematic, fixed];
+
+    for _ in 0..60 {
+        world.step_fixed(&mut objects, 1.0 / 60.0);
+    }
+    // Gravity and impulses don't reach either
+    assert_close(*objects[0].get_position(), Vec3::new(1.0, 0.0, 0.0), 1e-4);
+    assert_close(*objects[1].get_position(), Vec3::new(0.0, 2.0, 0.0), 0.0);
+    let body = objects[0].get_rigid_body_mut().unwrap();
+    body.apply_impulse(Vec3::new(0.0, 5.0, 0.0));
+    assert_close(body.get_linear_velocity(), Vec3::new(1.0, 0.0, 0.0), 0.0);
+}
@@ -0,0 +1,77 @@
+use glam::{Vec3, Vec4};
+use image::RgbaImage;
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::material::Material;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::sphere::Sphere;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+
+// A grey cube and sphere, unlit so only the outline and tint add color
+fn scene() -> Scene {
+    let mut scene = Scene::new();
+    let mut cube = Cube::new(1.2);
+    cube.set_position(Vec3::new(-0.6, 0.0, 4.0));
+    cube.set_rotation(Vec3::new(0.5, 0.6, 0.0));
+    cube.set_material(Material::unlit(Vec4::new(0.5, 0.5, 0.5, 1.0)));
+    scene.add_object(cube);
+    let mut sphere = Sphere::new(0.6, 16);
+    sphere.set_position(Vec3::new(0.6, 0.0, 3.5));
+    sphere.set_material(Material::unlit(Vec4::new(0.5, 0.5, 0.5, 1.0)));
+    scene.add_object(sphere);
+    scene.set_outline_color(Vec4::new(1.0, 0.0, 0.0, 1.0));
+    scene.set_hover_tint(Vec4::new(0.0, 0.0, 1.0, 0.5));
+    scene
+}
+
+fn count(image: &RgbaImage, matches: impl Fn([u8; 4]) -> bool) -> usize {
+    image.pixels().filter(|pixel| matches(pixel.0)).count()
+}
+
+fn is_outline(pixel: [u8; 4]) -> bool {
+    pixel[0] > 200 && pixel[1] < 50 && pixel[2] < 50
+}
+
+fn is_tinted(pixel: [u8; 4]) -> bool {
+    pixel[2] as u32 > pixel[0] as u32 + 50
+}
+
+#[test]
+fn outline_and_tint_match_software_renderer() {
+    let mut scene = scene();
+    let camera = Camera::new();
+    let mut gpu = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+    let mut software = SoftwareRenderer::new(WIDTH, HEIGHT);
+
+    // Nothing selected or hovered draws neither
+    let image = gpu.render(&scene, &camera);
+    assert_eq!(count(&image, is_outline), 0);
+    assert_eq!(count(&image, is_tinted), 0);
+
+    scene.set_selected_objects(vec![0]);
+    scene.set_hovered_object(Some(1));
+    scene.set_outline_thickness(2.0);
+    let gpu_image = gpu.render(&scene, &camera);
+    let software_image = software.render(&scene, &camera);
+    for matches in [is_outline, is_tinted] {
+        let gpu_count = count(&gpu_image, matches);
+        let software_count = count(&software_image, matches);
+        assert!(gpu_count > 50, "{gpu_count}");
+        assert!(
+            gpu_count.abs_diff(software_count) * 10 <= software_count,
+            "GPU {gpu_count}, software {software_count}"
+        );
+    }
+
+    // A thicker outline covers more, and a zero-thickness one none
+    scene.set_outline_thickness(4.0);
+    let thick = count(&gpu.render(&scene, &camera), is_outline);
+    assert!(thick > count(&gpu_image, is_outline) * 3 / 2);
+    scene.set_outline_thickness(0.0);
+    assert_eq!(count(&gpu.render(&scene, &camera), is_outline), 0);
+}
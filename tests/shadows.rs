@@ -0,0 +1,128 @@
+use glam::Vec3;
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::light::{DirectionalLight, PointLight, ShadowSettings};
+use three_d::core::object::Object;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::rectangular_prism::RectangularPrism;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+const FLOOR: f32 = -0.9;
+
+// A floor under the whole view, its top at FLOOR, plus the given unit cubes standing on air
+fn scene(cubes: &[Vec3]) -> Scene {
+    let mut scene = Scene::new();
+    let mut floor = RectangularPrism::new(12.0, 0.2, 12.0);
+    floor.set_position(Vec3::new(0.0, FLOOR - 0.1, 6.0));
+    scene.add_object(floor);
+    for position in cubes {
+        let mut cube: Object = Cube::new(1.0);
+        cube.set_position(*position);
+        scene.add_object(cube);
+    }
+    scene
+}
+
+// Where a point on the floor lands in the image, for the default camera
+fn pixel(x: f32, z: f32) -> (u32, u32) {
+    let aspect = WIDTH as f32 / HEIGHT as f32;
+    let column = (1.0 + x / (z * aspect)) * WIDTH as f32 / 2.0;
+    let row = (1.0 - FLOOR / z) * HEIGHT as f32 / 2.0;
+    (column as u32, row as u32)
+}
+
+fn brightness(renderer: &mut HeadlessRenderer, scene: &Scene, points: &[(f32, f32)]) -> Vec<u8> {
+    let image = renderer.render(scene, &Camera::new());
+    points
+        .iter()
+        .map(|(x, z)| {
+            let (column, row) = pixel(*x, *z);
+            image.get_pixel(column, row).0[0]
+        })
+        .collect()
+}
+
+// Shines down and towards the camera, so shadows fall in front of the cubes casting them
+fn sun(shadow_settings: ShadowSettings) -> DirectionalLight {
+    let mut light = DirectionalLight::new(Vec3::new(0.0, -1.0, -1.0));
+    light.set_shadow_settings(Some(shadow_settings));
+    light
+}
+
+#[test]
+fn directional_light_shadows_fall_behind_occluders() {
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+    // 0.9 below the cube's center at 45 degrees, so 0.9 closer to the camera
+    let shadowed = [(-1.0, 3.1)];
+    let mut lit = scene(&[]);
+    lit.add_light(sun(ShadowSettings::new()));
+    let mut occluded = scene(&[Vec3::new(-1.0, 0.0, 4.0)]);
+    occluded.add_light(sun(ShadowSettings::new()));
+
+    let lit = brightness(&mut renderer, &lit, &shadowed)[0];
+    let dark = brightness(&mut renderer, &occluded, &shadowed)[0];
+    assert!(lit > 100, "{lit}");
+    assert!(dark < lit / 4, "{dark} {lit}");
+    // Away from the shadow the floor is untouched
+    let beside = [(1.0, 3.1)];
+    assert_eq!(brightness(&mut renderer, &occluded, &beside)[0], lit);
+}
+
+#[test]
+fn cascades_meet_without_a_seam() {
+    // Two cascades out to 10 split a little past 4.3 from the camera
+    let settings = ShadowSettings {
+        max_distance: Some(10.0),
+        ..ShadowSettings::cascaded(2)
+    };
+    let mut scene = scene(&[Vec3::new(-1.0, 0.0, 4.0), Vec3::new(1.0, 0.0, 6.5)]);
+    scene.add_light(sun(settings));
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+
+    // One shadow in each cascade
+    let shadows = brightness(&mut renderer, &scene, &[(-1.0, 3.1), (1.0, 5.6)]);
+    // and a strip of open floor running across the split between them
+    let strip: Vec<(f32, f32)> = (0..10).map(|i| (0.0, 3.5 + i as f32 * 0.2)).collect();
+    let open = brightness(&mut renderer, &scene, &strip);
+    assert!(open[0] > 100, "{open:?}");
+    for brightness in &open {
+        assert!(brightness.abs_diff(open[0]) <= 1, "{open:?}");
+    }
+    for shadow in shadows {
+        assert!(shadow < open[0] / 4, "{shadow} {open:?}");
+    }
+}
+
+#[test]
+fn point_light_shadows_on_every_side() {
+    // Occluders beside and in front of the light land in its +X, -X and -Z cube faces, each
+    // casting a shadow on the floor 1.9 times as far out as the cube is
+    let light_position = Vec3::new(0.0, 1.0, 4.0);
+    let cubes = [
+        Vec3::new(1.5, 0.0, 4.0),
+        Vec3::new(-1.5, 0.0, 4.0),
+        Vec3::new(0.0, 0.0, 2.5),
+    ];
+    let shadowed = [(2.85, 4.0), (-2.85, 4.0), (0.0, 1.15)];
+    let light = || {
+        let mut light = PointLight::new(light_position, 10.0);
+        light.set_intensity(6.0);
+        light.set_shadow_settings(Some(ShadowSettings::new()));
+        light
+    };
+    let mut lit = scene(&[]);
+    lit.add_light(light());
+    let mut occluded = scene(&cubes);
+    occluded.add_light(light());
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+
+    let lit = brightness(&mut renderer, &lit, &shadowed);
+    let dark = brightness(&mut renderer, &occluded, &shadowed);
+    for (lit, dark) in lit.iter().zip(&dark) {
+        assert!(*lit > 60, "{lit}");
+        assert!(*dark < lit / 4, "{dark:?} {lit}");
+    }
+}
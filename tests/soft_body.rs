@@ -0,0 +1,212 @@
+use glam::Vec3;
+use three_d::core::object::Object;
+use three_d::geometry::bounds::Aabb;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::plane::Plane;
+use three_d::geometry::primitives::sphere::Sphere;
+use three_d::physics::collider::Collider;
+use three_d::physics::soft_body::SoftBody;
+use three_d::physics::world::PhysicsWorld;
+
+const DT: f32 = 1.0 / 60.0;
+
+// 2 by 2 cloth lying flat at `height`, in squares of 0.2
+fn cloth(height: f32) -> Object {
+    let mut cloth = Plane::new(2.0, 2.0, 10, 10);
+    cloth.set_position(Vec3::new(0.0, height, 0.0));
+    let soft_body = SoftBody::new(&cloth, 1.0);
+    cloth.set_soft_body(Some(soft_body));
+    cloth
+}
+
+// Cloth held up by the two corners on its far edge
+fn hanging_cloth() -> Object {
+    let mut cloth = cloth(3.0);
+    let soft_body = cloth.get_soft_body_mut().unwrap();
+    for x in [-1.0, 1.0] {
+        let corner = Vec3::new(x, 3.0, -1.0);
+        let region = Aabb::new(corner - 0.01, corner + 0.01);
+        assert_eq!(soft_body.pin_within(&region), 1);
+    }
+    cloth
+}
+
+fn floor() -> Object {
+    let mut floor = Cube::new(1.0);
+    floor.set_position(Vec3::new(0.0, -0.5, 0.0));
+    floor.set_collider(Some(Collider::cuboid(Vec3::new(10.0, 0.5, 10.0))));
+    floor
+}
+
+fn particles(object: &Object) -> &Vec<Vec3> {
+    object.get_soft_body().unwrap().get_particles()
+}
+
+fn run(world: &mut PhysicsWorld, objects: &mut [Object], steps: usize) {
+    for _ in 0..steps {
+        world.step_fixed(objects, DT);
+    }
+}
+
+#[test]
+fn cloth_hangs_from_its_pins_without_stretching() {
+    let mut objects = vec![hanging_cloth()];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 240);
+
+    let soft_body = objects[0].get_soft_body().unwrap();
+    let pinned: Vec<Vec3> = (0..soft_body.get_particles().len())
+        .filter(|particle| soft_body.get_pinned(*particle))
+        .map(|particle| soft_body.get_particles()[particle])
+        .collect();
+    assert_eq!(
+        pinned,
+        vec![Vec3::new(-1.0, 3.0, -1.0), Vec3::new(1.0, 3.0, -1.0)]
+    );
+    // Down to about its length below the pins
+    let lowest = particles(&objects[0])
+        .iter()
+        .map(|particle| particle.y)
+        .fold(f32::INFINITY, f32::min);
+    assert!(lowest > 0.8 && lowest < 1.2, "{lowest}");
+
+    // No edge grew much past its rest length: 0.2 along the grid, more across the diagonals
+    let longest = objects[0]
+        .get_mesh()
+        .get_triangles()
+        .iter()
+        .flat_map(|triangle| {
+            let [a, b, c] = triangle.get_vertices();
+            [a.distance(b), b.distance(c), c.distance(a)]
+        })
+        .fold(0.0, f32::max);
+    assert!(longest < 0.2 * 2f32.sqrt() * 1.05, "{longest}");
+}
+
+#[test]
+fn mesh_follows_the_particles() {
+    let mut objects = vec![hanging_cloth()];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 120);
+
+    let cloth = &objects[0];
+    let soft_body = cloth.get_soft_body().unwrap();
+    let triangles = cloth.get_mesh().get_triangles();
+    assert_eq!(triangles.len(), 200);
+    for (triangle, corners) in triangles.iter().zip(soft_body.get_corners()) {
+        for (vertex, particle) in triangle.get_vertices().iter().zip(corners) {
+            let world_vertex = cloth.transform_point(*vertex);
+            assert!(world_vertex.distance(soft_body.get_particles()[*particle]) < 1e-4);
+        }
+        // Normals turn with the triangles
+        let [a, b, c] = triangle.get_vertices();
+        let normal = (b - a).cross(c - a).normalize();
+        assert!(triangle.get_normal().dot(normal) > 0.999);
+        assert!(triangle.has_tangents());
+    }
+    // Hanging, the cloth mostly faces sideways rather than up
+    let facing_up = triangles
+        .iter()
+        .map(|triangle| triangle.get_normal().y.abs())
+        .sum::<f32>()
+        / triangles.len() as f32;
+    assert!(facing_up < 0.5, "{facing_up}");
+    // and the renderer's bounds grow to match
+    assert!(cloth.get_world_aabb().min.y < 1.5);
+}
+
+#[test]
+fn cloth_settles_flat_on_the_floor() {
+    let mut objects = vec![floor(), cloth(0.5)];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 120);
+
+    let soft_body = objects[1].get_soft_body().unwrap();
+    let thickness = soft_body.get_thickness();
+    for particle in soft_body.get_particles() {
+        assert!((particle.y - thickness).abs() < 0.005, "{particle}");
+    }
+    for velocity in soft_body.get_velocities() {
+        assert!(velocity.length() < 0.01, "{velocity}");
+    }
+}
+
+#[test]
+fn cloth_drapes_over_a_ball() {
+    let mut ball = Sphere::new(0.5, 16);
+    ball.set_collider(Some(Collider::sphere(0.5)));
+    let mut objects = vec![ball, cloth(1.0)];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 180);
+
+    let thickness = objects[1].get_soft_body().unwrap().get_thickness();
+    for particle in particles(&objects[1]) {
+        assert!(particle.length() > 0.5 + thickness - 0.005, "{particle}");
+    }
+    // Resting on top, with the edges hanging down around it
+    let top = particles(&objects[1])
+        .iter()
+        .min_by(|a, b| a.with_y(0.0).length().total_cmp(&b.with_y(0.0).length()))
+        .unwrap();
+    assert!((top.y - 0.5 - thickness).abs() < 0.01, "{top}");
+    let lowest = particles(&objects[1])
+        .iter()
+        .map(|particle| particle.y)
+        .fold(f32::INFINITY, f32::min);
+    assert!(lowest < 0.0, "{lowest}");
+}
+
+#[test]
+fn wind_blows_hanging_cloth() {
+    let mean_z = |objects: &[Object]| {
+        particles(&objects[0])
+            .iter()
+            .map(|particle| particle.z)
+            .sum::<f32>()
+            / particles(&objects[0]).len() as f32
+    };
+    let mut world = PhysicsWorld::new();
+    let mut still = vec![hanging_cloth()];
+    run(&mut world, &mut still, 180);
+
+    let mut blown = vec![hanging_cloth()];
+    blown[0]
+        .get_soft_body_mut()
+        .unwrap()
+        .set_wind(Vec3::new(0.0, 0.0, 5.0));
+    run(&mut world, &mut blown, 180);
+    assert!(mean_z(&blown) > mean_z(&still) + 0.3);
+}
+
+#[test]
+fn closed_soft_body_keeps_its_volume() {
+    let mut ball = Sphere::new(0.5, 12);
+    ball.set_position(Vec3::new(0.0, 1.5, 0.0));
+    let mut soft_body = SoftBody::new(&ball, 1.0);
+    soft_body.set_stretch_compliance(0.001);
+    soft_body.set_volume_compliance(Some(0.0));
+    let rest_volume = soft_body.get_rest_volume();
+    assert!((rest_volume.abs() - 4.0 / 3.0 * std::f32::consts::PI * 0.125).abs() < 0.05);
+    ball.set_soft_body(Some(soft_body));
+
+    let mut objects = vec![floor(), ball];
+    let mut world = PhysicsWorld::new();
+    run(&mut world, &mut objects, 120);
+
+    let soft_body = objects[1].get_soft_body().unwrap();
+    assert!((soft_body.get_volume() / rest_volume - 1.0).abs() < 0.05);
+    let lowest = particles(&objects[1])
+        .iter()
+        .map(|particle| particle.y)
+        .fold(f32::INFINITY, f32::min);
+    assert!(
+        (lowest - soft_body.get_thickness()).abs() < 0.01,
+        "{lowest}"
+    );
+    // Still a ball rather than a puddle
+    let highest = particles(&objects[1])
+        .iter()
+        .map(|particle| particle.y)
+        .fold(f32::NEG_INFINITY, f32::max);
+    assert!(highest > 0.8, "{highest}");
+}
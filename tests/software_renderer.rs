@@ -0,0 +1,78 @@
+use glam::{Vec3, Vec4};
+use image::RgbaImage;
+use three_d::core::camera::Camera;
+use three_d::core::light::DirectionalLight;
+use three_d::core::material::Material;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::core::software_renderer::SoftwareRenderer;
+use three_d::geometry::primitives::cube::Cube;
+
+// A lit cube straight ahead, its front face square on to the camera, beside an unlit one
+fn scene() -> Scene {
+    let mut scene = Scene::new();
+    scene.add_light(DirectionalLight::new(Vec3::new(0.0, 0.0, 1.0)));
+    let mut cube = Cube::new(1.0);
+    cube.set_position(Vec3::new(0.0, 0.0, 3.0));
+    scene.add_object(cube);
+    let mut unlit = Cube::new(0.5);
+    unlit.set_position(Vec3::new(-2.0, 0.0, 3.0));
+    unlit.set_material(Material::unlit(Vec4::new(1.0, 0.0, 0.0, 1.0)));
+    scene.add_object(unlit);
+    scene
+}
+
+fn covered(image: &RgbaImage) -> usize {
+    image.pixels().filter(|pixel| pixel.0[0] > 0).count()
+}
+
+#[test]
+fn renders_at_the_requested_size() {
+    let mut renderer = SoftwareRenderer::new(160, 60);
+    assert_eq!((renderer.get_width(), renderer.get_height()), (160, 60));
+    let image = renderer.render(&scene(), &Camera::new());
+    assert_eq!(image.dimensions(), (160, 60));
+    assert_eq!(renderer.get_stats().drawn_objects, 2);
+    // Lit white in the middle and unlit red off to the left
+    assert!(
+        image.get_pixel(80, 30).0[..3]
+            .iter()
+            .all(|value| *value > 200)
+    );
+    let red = image.pixels().filter(|pixel| pixel.0 == [255, 0, 0, 255]);
+    assert!(red.count() > 0);
+
+    // The square face stays square in a wide image: as many pixels across as down
+    let across = (70..90)
+        .filter(|x| image.get_pixel(*x, 30).0[0] > 0)
+        .count();
+    let down = (0..60).filter(|y| image.get_pixel(80, *y).0[0] > 0).count();
+    assert!(across.abs_diff(down) <= 1, "{across} {down}");
+}
+
+#[test]
+fn renderers_of_other_sizes_dont_change_each_other() {
+    let mut wide = SoftwareRenderer::new(160, 60);
+    let before = wide.render(&scene(), &Camera::new());
+    let mut tall = SoftwareRenderer::new(60, 160);
+    assert_eq!(
+        tall.render(&scene(), &Camera::new()).dimensions(),
+        (60, 160)
+    );
+    assert_eq!(wide.render(&scene(), &Camera::new()), before);
+}
+
+#[test]
+fn writes_the_render_to_a_png() {
+    let mut renderer = SoftwareRenderer::new(96, 64);
+    let path = std::env::temp_dir().join("three_d_software_render.png");
+    renderer
+        .render_to_png(&scene(), &Camera::new(), &path)
+        .unwrap();
+
+    let saved = image::open(&path).unwrap().to_rgba8();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(saved.dimensions(), (96, 64));
+    assert!(covered(&saved) > 0);
+    assert_eq!(saved, renderer.render(&scene(), &Camera::new()));
+}
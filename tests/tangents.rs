@@ -0,0 +1,68 @@
+use glam::{Vec3, Vec4};
+use three_d::geometry::mesh::Mesh;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::cylinder::Cylinder;
+use three_d::geometry::primitives::plane::Plane;
+use three_d::geometry::primitives::pyramid::Pyramid;
+
+// Every corner's tangent is a unit vector in the triangle's plane, pointing the way u grows,
+// with w giving the side the bitangent (the way v shrinks) is on
+fn assert_tangent_frames(mesh: &Mesh) {
+    for triangle in mesh.get_triangles() {
+        let normal = triangle.get_normal();
+        let (u_direction, v_direction) = triangle.calculate_uv_basis().unwrap();
+        for tangent in triangle.get_tangents() {
+            let (direction, handedness) = (tangent.truncate(), tangent.w);
+            assert!((direction.length() - 1.0).abs() < 1e-4, "{tangent}");
+            assert!(direction.dot(normal).abs() < 1e-4, "{tangent} {normal}");
+            assert!(direction.dot(u_direction.normalize()) > 0.5, "{tangent}");
+            assert!(handedness == 1.0 || handedness == -1.0);
+            let bitangent = normal.cross(direction) * handedness;
+            assert!(bitangent.dot(v_direction.normalize()) > 0.5, "{tangent}");
+        }
+    }
+}
+
+#[test]
+fn generated_tangents_follow_the_texture_coordinates() {
+    // u runs along x and v along z, so the bitangent points towards -z
+    let plane = Plane::new(2.0, 2.0, 2, 2);
+    for triangle in plane.get_mesh().get_triangles() {
+        assert!(triangle.has_tangents());
+        for tangent in triangle.get_tangents() {
+            assert!(tangent.distance(Vec4::new(1.0, 0.0, 0.0, 1.0)) < 1e-5);
+        }
+    }
+    // Flat sides, sides meeting at sharp edges, and a smooth curve around the cylinder
+    for object in [
+        Plane::new(2.0, 2.0, 2, 2),
+        Cube::new(1.0),
+        Pyramid::new(1.0, 1.0),
+        Cylinder::new(0.5, 1.0, 12),
+    ] {
+        assert_tangent_frames(object.get_mesh());
+    }
+}
+
+#[test]
+fn mirrored_texture_coordinates_flip_the_handedness() {
+    // The plane's first square again, with u running the other way
+    let mut mesh = Mesh::from_raw_coordinates_with_uvs(
+        vec![
+            [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 0.0],
+        ],
+        vec![
+            [1.0, 0.0, 1.0, 1.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+        ],
+    );
+    mesh.generate_tangents();
+    for triangle in mesh.get_triangles() {
+        assert_eq!(triangle.get_normal(), Vec3::Y);
+        for tangent in triangle.get_tangents() {
+            assert!(tangent.distance(Vec4::new(-1.0, 0.0, 0.0, -1.0)) < 1e-5);
+        }
+    }
+    assert_tangent_frames(&mesh);
+}
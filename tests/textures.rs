@@ -0,0 +1,132 @@
+use glam::{Vec2, Vec3, Vec4};
+use std::sync::Arc;
+use three_d::core::camera::Camera;
+use three_d::core::headless::HeadlessRenderer;
+use three_d::core::material::Material;
+use three_d::core::render_backend::RenderBackend;
+use three_d::core::scene::Scene;
+use three_d::core::texture::{AddressMode, FilterMode, Sampler, Texture};
+use three_d::geometry::primitives::cube::Cube;
+use three_d::geometry::primitives::cylinder::Cylinder;
+use three_d::geometry::primitives::pyramid::Pyramid;
+use three_d::geometry::primitives::rectangular_prism::RectangularPrism;
+use three_d::geometry::primitives::sphere::Sphere;
+use three_d::geometry::primitives::triangular_prism::TriangularPrism;
+
+const WIDTH: u32 = 80;
+const HEIGHT: u32 = 60;
+
+// Two by two: white and black on the top row, black and white below
+fn checker(filter: FilterMode, address_mode: AddressMode) -> Texture {
+    let (white, black) = ([255, 255, 255, 255], [0, 0, 0, 255]);
+    let mut texture = Texture::from_rgba8(2, 2, [white, black, black, white].concat()).unwrap();
+    texture.set_sampler(Sampler {
+        filter,
+        address_mode,
+    });
+    texture
+}
+
+#[test]
+fn sampling_filters_and_wraps_like_the_gpu() {
+    let nearest = checker(FilterMode::Nearest, AddressMode::Repeat);
+    assert_eq!(nearest.sample(Vec2::new(0.25, 0.25), false), Vec4::ONE);
+    assert_eq!(nearest.sample(Vec2::new(0.75, 0.25), false).x, 0.0);
+    // Repeat wraps whole periods away
+    assert_eq!(nearest.sample(Vec2::new(1.25, -0.75), false), Vec4::ONE);
+
+    // Halfway between texel centers blends them
+    let linear = checker(FilterMode::Linear, AddressMode::ClampToEdge);
+    let blended = linear.sample(Vec2::new(0.5, 0.25), false);
+    assert!((blended.x - 0.5).abs() < 1e-5, "{blended}");
+    // Clamping holds the edge texel beyond the edge
+    assert_eq!(linear.sample(Vec2::new(-3.0, 0.25), false), Vec4::ONE);
+
+    let mirrored = checker(FilterMode::Nearest, AddressMode::MirroredRepeat);
+    assert_eq!(mirrored.sample(Vec2::new(1.25, 0.25), false).x, 0.0);
+    assert_eq!(mirrored.sample(Vec2::new(1.75, 0.25), false), Vec4::ONE);
+
+    // sRGB texels are decoded to linear, but alpha never is
+    let grey = Texture::from_rgba8(1, 1, vec![128, 128, 128, 128]).unwrap();
+    let decoded = grey.sample(Vec2::splat(0.5), true);
+    assert!((decoded.x - 0.2158).abs() < 1e-3, "{decoded}");
+    assert!((decoded.w - 128.0 / 255.0).abs() < 1e-6);
+}
+
+#[test]
+fn primitives_have_texture_coordinates() {
+    for object in [
+        Cube::new(1.0),
+        RectangularPrism::new(1.0, 2.0, 3.0),
+        Pyramid::new(1.0, 1.0),
+        TriangularPrism::new(1.0, 1.0, 1.0),
+        Sphere::new(1.0, 8),
+        Cylinder::new(1.0, 1.0, 8),
+    ] {
+        let uvs: Vec<Vec2> = object
+            .get_mesh()
+            .get_triangles()
+            .iter()
+            .flat_map(|triangle| triangle.get_uvs())
+            .collect();
+        assert!(
+            uvs.iter()
+                .all(|uv| uv.cmpge(Vec2::ZERO).all() && uv.cmple(Vec2::ONE).all())
+        );
+        // Spread over the whole texture rather than all at one point
+        let (min, max) = uvs.iter().fold((Vec2::ONE, Vec2::ZERO), |(min, max), uv| {
+            (min.min(*uv), max.max(*uv))
+        });
+        assert_eq!((min, max), (Vec2::ZERO, Vec2::ONE));
+    }
+}
+
+#[test]
+fn textures_color_the_rendered_faces() {
+    // The front face of the cube fills the middle half of the image, facing the camera
+    let mut cube = Cube::new(2.0);
+    cube.set_position(Vec3::new(0.0, 0.0, 3.0));
+    let mut material = Material::unlit(Vec4::ONE);
+    material.set_base_color_texture(Some(Arc::new(checker(
+        FilterMode::Nearest,
+        AddressMode::Repeat,
+    ))));
+    cube.set_material(material);
+    let mut scene = Scene::new();
+    scene.add_object(cube);
+
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(WIDTH, HEIGHT));
+    let image = renderer.render(&scene, &Camera::new());
+    let quarter = |x: u32, y: u32| image.get_pixel(x, y).0[0];
+    let (left, right, top, bottom) = (30, 50, 22, 38);
+    // One square per quarter of the face, alike across the diagonals
+    assert!(quarter(left, top).abs_diff(quarter(right, top)) > 200);
+    assert_eq!(quarter(left, top), quarter(right, bottom));
+    assert_eq!(quarter(right, top), quarter(left, bottom));
+
+    // An emissive texture scales the emissive factor the same way
+    let mut glowing = Material::new();
+    glowing.set_base_color(Vec4::new(0.0, 0.0, 0.0, 1.0));
+    glowing.set_emissive(Vec3::new(1.0, 0.0, 0.0));
+    glowing.set_emissive_texture(Some(Arc::new(checker(
+        FilterMode::Nearest,
+        AddressMode::Repeat,
+    ))));
+    scene.get_object_mut(0).set_material(glowing);
+    let image = renderer.render(&scene, &Camera::new());
+    let quarter = |x: u32, y: u32| image.get_pixel(x, y).0;
+    assert!(quarter(left, top)[0].abs_diff(quarter(right, top)[0]) > 200);
+    assert_eq!(quarter(left, top)[1], 0);
+}
+
+#[test]
+fn pixel_data_must_fit_the_size() {
+    // Nothing to sample without a pixel
+    assert!(Texture::from_rgba8(0, 0, Vec::new()).is_err());
+    assert!(Texture::from_rgba8(0, 4, Vec::new()).is_err());
+    assert!(Texture::from_rgba8(2, 1, vec![0; 4]).is_err());
+    // Sizes whose byte count doesn't fit in 32 bits are checked without wrapping around
+    assert!(Texture::from_rgba8(u32::MAX, u32::MAX, vec![0; 4]).is_err());
+    assert!(Texture::from_rgba8(65536, 16384, Vec::new()).is_err());
+    assert!(Texture::from_rgba8(2, 1, vec![0; 8]).is_ok());
+}
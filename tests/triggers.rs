@@ -0,0 +1,119 @@
+use glam::Vec3;
+use std::cell::Cell;
+use std::rc::Rc;
+use three_d::core::object::Object;
+use three_d::core::scene::Scene;
+use three_d::geometry::primitives::cube::Cube;
+use three_d::physics::broad_phase::PairEventKind;
+use three_d::physics::collider::Collider;
+use three_d::physics::rigid_body::RigidBody;
+use three_d::physics::world::{PhysicsWorld, TriggerEvent};
+
+// Zone two units tall around y = 0
+fn zone() -> Object {
+    let mut zone = Cube::new(1.0);
+    let mut collider = Collider::cuboid(Vec3::new(2.0, 1.0, 2.0));
+    collider.set_trigger(true);
+    zone.set_collider(Some(collider));
+    zone
+}
+
+fn ball(height: f32) -> Object {
+    let mut ball = Cube::new(1.0);
+    ball.set_position(Vec3::new(0.0, height, 0.0));
+    ball.set_collider(Some(Collider::sphere(0.25)));
+    ball.set_rigid_body(Some(RigidBody::dynamic(1.0)));
+    ball
+}
+
+#[test]
+fn falling_through_a_zone_enters_stays_and_exits() {
+    let mut objects = vec![zone(), ball(2.0)];
+    let mut world = PhysicsWorld::new();
+    let mut events = Vec::new();
+    for _ in 0..90 {
+        world.step_fixed(&mut objects, 1.0 / 60.0);
+        events.extend(world.take_trigger_events());
+    }
+    assert!(world.take_trigger_events().is_empty());
+
+    let kinds: Vec<PairEventKind> = events.iter().map(|event| event.kind).collect();
+    assert_eq!(kinds.first(), Some(&PairEventKind::Enter));
+    assert_eq!(kinds.last(), Some(&PairEventKind::Exit));
+    assert!(
+        kinds[1..kinds.len() - 1]
+            .iter()
+            .all(|kind| *kind == PairEventKind::Stay)
+    );
+    assert!(kinds.len() > 3);
+    for event in &events {
+        assert_eq!((event.trigger, event.other), (0, 1));
+    }
+
+    // The zone never pushed back, so the ball fell freely
+    let time = 1.5f32;
+    let expected = 2.0 - 0.5 * 9.81 * time * time;
+    assert!((objects[1].get_position().y - expected).abs() < 0.2);
+}
+
+#[test]
+fn triggers_respect_layers() {
+    let mut objects = vec![zone(), ball(0.0)];
+    let mut collider = objects[1].get_collider().unwrap().clone();
+    collider.set_collision_layers(0b10);
+    collider.set_collision_mask(0b10);
+    objects[1].set_collider(Some(collider));
+
+    let mut world = PhysicsWorld::new();
+    world.step_fixed(&mut objects, 1.0 / 60.0);
+    assert!(world.take_trigger_events().is_empty());
+}
+
+#[test]
+fn scene_delivers_events_to_update_callbacks() {
+    let mut scene = Scene::new();
+    scene.get_physics_mut().set_gravity(Vec3::ZERO);
+    scene.add_object(zone());
+
+    let entered = Rc::new(Cell::new(0));
+    let left = Rc::new(Cell::new(0));
+    let mut visitor = ball(0.0);
+    visitor.set_position(Vec3::new(-4.0, 0.0, 0.0));
+    visitor
+        .get_rigid_body_mut()
+        .unwrap()
+        .set_linear_velocity(Vec3::new(6.0, 0.0, 0.0));
+    let (entered_count, left_count) = (entered.clone(), left.clone());
+    visitor.set_update(move |object, _| {
+        for event in object.get_trigger_events() {
+            match event.kind {
+                PairEventKind::Enter => entered_count.set(entered_count.get() + 1),
+                PairEventKind::Exit => left_count.set(left_count.get() + 1),
+                PairEventKind::Stay => {}
+            }
+        }
+    });
+    scene.add_object(visitor);
+
+    let mut seen = Vec::new();
+    let mut zone_heard = 0;
+    for _ in 0..90 {
+        scene.update(1.0 / 60.0);
+        seen.extend(scene.get_trigger_events().iter().copied());
+        zone_heard += scene.get_objects()[0].get_trigger_events().len();
+    }
+    assert_eq!(entered.get(), 1);
+    assert_eq!(left.get(), 1);
+    assert_eq!(
+        seen.first(),
+        Some(&TriggerEvent {
+            trigger: 0,
+            other: 1,
+            kind: PairEventKind::Enter
+        })
+    );
+    assert_eq!(seen.last().unwrap().kind, PairEventKind::Exit);
+    // The zone's own object hears about it too
+    assert_eq!(zone_heard, seen.len());
+    assert!(scene.get_objects()[1].get_position().x > 4.0);
+}